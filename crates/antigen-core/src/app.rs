@@ -0,0 +1,355 @@
+use std::collections::BTreeMap;
+
+use legion::{storage::Component, systems::ParallelRunnable, World};
+
+use crate::{
+    register_component_hooks, ComponentHooks, ImmutableResources, ImmutableSchedule,
+    ImmutableWorld, ReadWriteLock, ScheduleBuildError, ScheduleBuilder, Serial,
+};
+
+/// Points in the application lifecycle at which [`App`] systems run
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// Once, before any other stage
+    Assemble,
+    /// Once, after assembly
+    Startup,
+    /// Every game thread tick
+    Tick,
+    /// Every event loop update, before [`Stage::Update`]
+    PreUpdate,
+    /// Every event loop update
+    Update,
+    /// Every event loop update, after [`Stage::Update`]
+    PostUpdate,
+    /// Every event loop redraw, before [`Stage::Render`]
+    PreRender,
+    /// Every event loop redraw
+    Render,
+    /// Every event loop redraw, after [`Stage::Render`]
+    PostRender,
+}
+
+impl Stage {
+    /// Stages run once by [`App::build`]
+    pub const ONCE: [Stage; 2] = [Stage::Assemble, Stage::Startup];
+
+    /// Stages run by the event loop on update, in order
+    pub const UPDATE: [Stage; 3] = [Stage::PreUpdate, Stage::Update, Stage::PostUpdate];
+
+    /// Stages run by the event loop on redraw, in order
+    pub const RENDER: [Stage; 3] = [Stage::PreRender, Stage::Render, Stage::PostRender];
+}
+
+/// A self-contained unit of functionality that registers its assembly, systems and event hooks with an [`App`]
+///
+/// `E` is the event type of the driving event loop, ex. `winit::event::Event<'static, T>`.
+/// Plugins that don't handle events should implement `Plugin<E>` for any `E`.
+pub trait Plugin<E = ()> {
+    fn build(&self, app: App<E>) -> App<E>;
+}
+
+/// Function run against the world by [`App::build`], before [`Stage::Assemble`]
+type Assembler = Box<dyn FnOnce(&mut World)>;
+
+/// Predicate selecting the events an [`EventHook`] runs for
+type EventFilter<E> = Box<dyn Fn(&E) -> bool>;
+
+/// A schedule run whenever the event loop receives an event matching `filter`
+struct EventHook<E> {
+    filter: EventFilter<E>,
+    schedule: ImmutableSchedule<Serial>,
+}
+
+/// Builder composing [`Plugin`]s into per-[`Stage`] schedules
+///
+/// ```ignore
+/// let AppSchedules {
+///     tick_schedule,
+///     event_loop_schedules,
+/// } = App::new(&world)
+///     .add_plugin(TransformIntegrationPlugin)
+///     .add_plugin(PhosphorPlugin)
+///     .build(&world)?;
+/// ```
+pub struct App<E = ()> {
+    resources: ImmutableResources,
+    assemblers: Vec<Assembler>,
+    stages: BTreeMap<Stage, ScheduleBuilder>,
+    event_hooks: Vec<(EventFilter<E>, ScheduleBuilder)>,
+}
+
+impl<E> App<E> {
+    /// Create an app whose systems share the resources of `world`
+    pub fn new(world: &ImmutableWorld) -> Self {
+        App {
+            resources: world.resources().clone(),
+            assemblers: Default::default(),
+            stages: Default::default(),
+            event_hooks: Default::default(),
+        }
+    }
+
+    /// Resources of the world this app was created for, to be passed to systems as state
+    pub fn resources(&self) -> &ImmutableResources {
+        &self.resources
+    }
+
+    pub fn add_plugin<P: Plugin<E>>(self, plugin: P) -> Self {
+        plugin.build(self)
+    }
+
+    /// Register a function to run against the world before [`Stage::Assemble`]
+    pub fn add_assembler(mut self, assembler: impl FnOnce(&mut World) + 'static) -> Self {
+        self.assemblers.push(Box::new(assembler));
+        self
+    }
+
+    /// Register lifecycle hooks for component type `C`
+    pub fn add_component_hooks<C: Component>(self, hooks: ComponentHooks<C>) -> Self {
+        self.add_assembler(move |world| register_component_hooks(world, hooks))
+    }
+
+    /// Register a system, or nested schedule, to run during `stage`
+    ///
+    /// Systems in the same stage are ordered via [`ScheduleBuilder`] labels and constraints,
+    /// and run in parallel otherwise.
+    pub fn add_system<S: ParallelRunnable + 'static>(mut self, stage: Stage, system: S) -> Self {
+        let builder = self.stages.remove(&stage).unwrap_or_default();
+        self.stages.insert(stage, builder.add_system(system));
+        self
+    }
+
+    /// Register a system, or nested schedule, to run whenever the event loop receives an event matching `filter`
+    pub fn add_event_system<F, S>(mut self, filter: F, system: S) -> Self
+    where
+        F: Fn(&E) -> bool + 'static,
+        S: ParallelRunnable + 'static,
+    {
+        self.event_hooks.push((
+            Box::new(filter),
+            ScheduleBuilder::default().add_system(system),
+        ));
+        self
+    }
+
+    /// Run assemblers and one-shot stages against `world`, then build the remaining stages
+    pub fn build(mut self, world: &ImmutableWorld) -> Result<AppSchedules<E>, ScheduleBuildError> {
+        for assembler in self.assemblers.drain(..) {
+            assembler(&mut world.write());
+        }
+
+        for stage in Stage::ONCE {
+            self.take_stage(stage)?.execute_and_flush(world);
+        }
+
+        let tick_schedule = self.take_stage(Stage::Tick)?;
+
+        let stages = Stage::UPDATE
+            .into_iter()
+            .chain(Stage::RENDER)
+            .map(|stage| Ok((stage, self.take_stage(stage)?)))
+            .collect::<Result<_, ScheduleBuildError>>()?;
+
+        let event_hooks = self
+            .event_hooks
+            .into_iter()
+            .map(|(filter, builder)| {
+                Ok(EventHook {
+                    filter,
+                    schedule: builder.build()?,
+                })
+            })
+            .collect::<Result<_, ScheduleBuildError>>()?;
+
+        Ok(AppSchedules {
+            tick_schedule,
+            event_loop_schedules: EventLoopSchedules {
+                stages,
+                event_hooks,
+            },
+        })
+    }
+
+    fn take_stage(
+        &mut self,
+        stage: Stage,
+    ) -> Result<ImmutableSchedule<Serial>, ScheduleBuildError> {
+        let schedule = self.stages.remove(&stage).unwrap_or_default().build()?;
+        Ok(schedule.label(format!("{:?}", stage)))
+    }
+}
+
+/// Schedules produced by [`App::build`]
+pub struct AppSchedules<E> {
+    /// [`Stage::Tick`], to be run by the game thread
+    pub tick_schedule: ImmutableSchedule<Serial>,
+    /// Update and render stages plus event hooks, to be run by the event loop
+    pub event_loop_schedules: EventLoopSchedules<E>,
+}
+
+/// Event loop side of an [`App`]
+///
+/// Event loop integrations decide when each stage runs, ex. `antigen_winit::app_event_handler`.
+pub struct EventLoopSchedules<E> {
+    stages: BTreeMap<Stage, ImmutableSchedule<Serial>>,
+    event_hooks: Vec<EventHook<E>>,
+}
+
+impl<E> EventLoopSchedules<E> {
+    pub fn execute_stage(&mut self, stage: Stage, world: &ImmutableWorld) {
+        if let Some(schedule) = self.stages.get_mut(&stage) {
+            schedule.execute_and_flush(world);
+        }
+    }
+
+    /// Run every event hook whose filter matches `event`
+    pub fn execute_event_hooks(&mut self, event: &E, world: &ImmutableWorld) {
+        for hook in &mut self.event_hooks {
+            if (hook.filter)(event) {
+                hook.schedule.execute_and_flush(world);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use legion::systems::SystemBuilder;
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    type Log = Arc<Mutex<Vec<&'static str>>>;
+
+    fn system(log: &Log, name: &'static str) -> impl ParallelRunnable {
+        let log = log.clone();
+        SystemBuilder::new(name).build(move |_, _, _, _| log.lock().push(name))
+    }
+
+    fn take(log: &Log) -> Vec<&'static str> {
+        std::mem::take(&mut *log.lock())
+    }
+
+    struct StagePlugin(Log, &'static str);
+
+    impl<E> Plugin<E> for StagePlugin {
+        fn build(&self, app: App<E>) -> App<E> {
+            let log = self.0.clone();
+            let name = self.1;
+            app.add_assembler(move |_| log.lock().push(name))
+                .add_system(Stage::Update, system(&self.0, name))
+        }
+    }
+
+    #[test]
+    fn plugins_compose_into_shared_stages() {
+        let world = ImmutableWorld::default();
+        let log = Log::default();
+
+        let AppSchedules {
+            mut event_loop_schedules,
+            ..
+        } = App::<()>::new(&world)
+            .add_plugin(StagePlugin(log.clone(), "a"))
+            .add_plugin(StagePlugin(log.clone(), "b"))
+            .build(&world)
+            .unwrap();
+
+        let mut assembled = take(&log);
+        assembled.sort();
+        assert_eq!(assembled, vec!["a", "b"]);
+
+        event_loop_schedules.execute_stage(Stage::Update, &world);
+        let mut updated = take(&log);
+        updated.sort();
+        assert_eq!(updated, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn build_runs_assemblers_then_once_stages_exactly_once() {
+        let world = ImmutableWorld::default();
+        let log = Log::default();
+        let assembler_log = log.clone();
+
+        let AppSchedules {
+            mut tick_schedule,
+            mut event_loop_schedules,
+        } = App::<()>::new(&world)
+            .add_system(Stage::Startup, system(&log, "startup"))
+            .add_system(Stage::Assemble, system(&log, "assemble"))
+            .add_assembler(move |_| assembler_log.lock().push("assembler"))
+            .add_system(Stage::Tick, system(&log, "tick"))
+            .build(&world)
+            .unwrap();
+
+        assert_eq!(take(&log), vec!["assembler", "assemble", "startup"]);
+
+        tick_schedule.execute_and_flush(&world);
+        for stage in Stage::ONCE.into_iter().chain(Stage::UPDATE) {
+            event_loop_schedules.execute_stage(stage, &world);
+        }
+        assert_eq!(take(&log), vec!["tick"]);
+    }
+
+    #[test]
+    fn event_loop_stages_run_in_stage_order() {
+        let world = ImmutableWorld::default();
+        let log = Log::default();
+
+        let AppSchedules {
+            mut event_loop_schedules,
+            ..
+        } = App::<()>::new(&world)
+            .add_system(Stage::PostRender, system(&log, "post_render"))
+            .add_system(Stage::Render, system(&log, "render"))
+            .add_system(Stage::PreRender, system(&log, "pre_render"))
+            .add_system(Stage::PostUpdate, system(&log, "post_update"))
+            .add_system(Stage::Update, system(&log, "update"))
+            .add_system(Stage::PreUpdate, system(&log, "pre_update"))
+            .build(&world)
+            .unwrap();
+
+        for stage in Stage::UPDATE.into_iter().chain(Stage::RENDER) {
+            event_loop_schedules.execute_stage(stage, &world);
+        }
+
+        assert_eq!(
+            take(&log),
+            vec![
+                "pre_update",
+                "update",
+                "post_update",
+                "pre_render",
+                "render",
+                "post_render"
+            ]
+        );
+    }
+
+    #[test]
+    fn event_hooks_run_for_matching_events() {
+        let world = ImmutableWorld::default();
+        let log = Log::default();
+
+        let AppSchedules {
+            mut event_loop_schedules,
+            ..
+        } = App::<u32>::new(&world)
+            .add_event_system(|event| *event == 1, system(&log, "one"))
+            .add_event_system(|event| *event % 2 == 1, system(&log, "odd"))
+            .build(&world)
+            .unwrap();
+
+        event_loop_schedules.execute_event_hooks(&1, &world);
+        assert_eq!(take(&log), vec!["one", "odd"]);
+
+        event_loop_schedules.execute_event_hooks(&2, &world);
+        assert!(take(&log).is_empty());
+
+        event_loop_schedules.execute_event_hooks(&3, &world);
+        assert_eq!(take(&log), vec!["odd"]);
+    }
+}
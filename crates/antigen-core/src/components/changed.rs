@@ -1,17 +1,35 @@
 use std::{
     borrow::{Borrow, BorrowMut},
+    collections::HashMap,
     ops::{Deref, DerefMut},
-    sync::atomic::{AtomicBool, Ordering},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Weak,
+    },
 };
 
-use crate::ReadWriteLock;
+use legion::Entity;
+use parking_lot::Mutex;
+
+use crate::{Construct, ReadWriteLock, With};
 
 pub struct ChangedFlag(pub bool);
 
-// Changed flag
+/// Changed flag and generation counter
+///
+/// Writers set the changed flag, which also advances the generation.
+/// Consumers never reset the flag; each observes new generations exactly once
+/// via its own [`ChangeTick`] or [`ChangeTracker`], so any number of them can watch the same data.
+///
+/// Clearing the flag is left to single-consumer handshakes that own the data outright,
+/// ex. surface textures and staging belts, and does not affect the generation.
+#[derive(ReadWriteLock, Construct, With)]
 pub struct Changed<T> {
+    #[antigen(inner)]
     pub data: T,
     flag: AtomicBool,
+    generation: AtomicUsize,
+    alive: Arc<()>,
 }
 
 impl<T> Changed<T> {
@@ -19,6 +37,8 @@ impl<T> Changed<T> {
         Changed {
             data,
             flag: AtomicBool::new(changed),
+            generation: AtomicUsize::new(changed as usize),
+            alive: Default::default(),
         }
     }
 
@@ -53,16 +73,37 @@ impl<T> DerefMut for Changed<T> {
     }
 }
 
-impl<T, V> ReadWriteLock<V> for Changed<T>
-where
-    T: ReadWriteLock<V>,
-{
-    fn read(&self) -> parking_lot::RwLockReadGuard<V> {
-        self.data.read()
+/// Serialized as its data and changed flag; the generation restarts on load
+#[cfg(feature = "serialize")]
+impl<T: serde::Serialize> serde::Serialize for Changed<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        #[serde(rename = "Changed")]
+        struct ChangedRef<'a, T> {
+            data: &'a T,
+            changed: bool,
+        }
+
+        ChangedRef {
+            data: &self.data,
+            changed: self.get_changed(),
+        }
+        .serialize(serializer)
     }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Changed<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Changed")]
+        struct ChangedOwned<T> {
+            data: T,
+            changed: bool,
+        }
 
-    fn write(&self) -> parking_lot::RwLockWriteGuard<V> {
-        self.data.write()
+        let ChangedOwned { data, changed } = ChangedOwned::deserialize(deserializer)?;
+        Ok(Changed::new(data, changed))
     }
 }
 
@@ -70,6 +111,12 @@ where
 pub trait ChangedTrait {
     fn get_changed(&self) -> bool;
     fn set_changed(&self, dirty: bool);
+
+    /// Number of times the changed flag has been set
+    fn generation(&self) -> usize;
+
+    /// Handle that expires when this value is dropped
+    fn liveness(&self) -> Weak<()>;
 }
 
 impl<T> ChangedTrait for Changed<T> {
@@ -78,35 +125,123 @@ impl<T> ChangedTrait for Changed<T> {
     }
 
     fn set_changed(&self, dirty: bool) {
+        if dirty {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
         self.flag.store(dirty, Ordering::Relaxed);
     }
+
+    fn generation(&self) -> usize {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    fn liveness(&self) -> Weak<()> {
+        Arc::downgrade(&self.alive)
+    }
 }
 
-/// Construct implementation
-impl<T> crate::Construct<T, crate::peano::Z> for Changed<T> {
-    fn construct(t: T) -> Self {
-        Changed {
-            data: t,
-            flag: false.into(),
-        }
+/// Last generation of a single [`ChangedTrait`] seen by a given consumer
+///
+/// Intended to be stored alongside the consumer,
+/// ex. inside the component that describes a write operation.
+#[derive(Debug, Default)]
+pub struct ChangeTick(AtomicUsize);
+
+impl ChangeTick {
+    /// Returns true if `changed` has been changed since this tick last observed it
+    pub fn has_changed<C: ChangedTrait>(&self, changed: &C) -> bool {
+        self.0.load(Ordering::Relaxed) != changed.generation()
+    }
+
+    /// Returns true if `changed` has been changed since this tick last observed it,
+    /// and marks the current generation as seen
+    pub fn observe<C: ChangedTrait>(&self, changed: &C) -> bool {
+        let generation = changed.generation();
+        self.0.swap(generation, Ordering::Relaxed) != generation
     }
 }
 
-impl<T, I, N> crate::Construct<T, crate::peano::S<I>> for Changed<N>
-where
-    N: crate::Construct<T, I>,
-{
-    fn construct(t: T) -> Self {
-        Changed {
-            data: N::construct(t),
-            flag: false.into(),
+/// Per-entity [`ChangeTick`] storage for systems that consume many [`ChangedTrait`] components
+///
+/// Intended to be held as system state:
+/// ```ignore
+/// #[legion::system(par_for_each)]
+/// fn my_system(#[state] tracker: &ChangeTracker, entity: &Entity, size: &SizeComponent) {
+///     if !tracker.observe(*entity, size) {
+///         return;
+///     }
+///     ...
+/// }
+///
+/// my_system_system(ChangeTracker::default())
+/// ```
+///
+/// Ticks are tied to the observed component rather than just its entity,
+/// so a replaced component reads as changed, and ticks for dropped components
+/// (ex. those of despawned entities) are discarded as the tracker grows.
+#[derive(Debug, Default)]
+pub struct ChangeTracker(Mutex<ChangeTrackerTicks>);
+
+#[derive(Debug, Default)]
+struct ChangeTrackerTicks {
+    ticks: HashMap<Entity, (usize, Weak<()>)>,
+    /// Length past which dropped components are pruned
+    prune_len: usize,
+}
+
+impl ChangeTrackerTicks {
+    fn generation<C: ChangedTrait>(&self, entity: Entity, changed: &C) -> usize {
+        match self.ticks.get(&entity) {
+            Some((generation, liveness)) if liveness.ptr_eq(&changed.liveness()) => *generation,
+            _ => 0,
+        }
+    }
+
+    fn prune(&mut self) {
+        if self.ticks.len() <= self.prune_len {
+            return;
         }
+
+        self.ticks
+            .retain(|_, (_, liveness)| liveness.strong_count() > 0);
+        self.prune_len = (self.ticks.len() * 2).max(16);
+    }
+}
+
+impl ChangeTracker {
+    /// Returns true if `changed` has been changed since this tracker last observed it on `entity`
+    pub fn has_changed<C: ChangedTrait>(&self, entity: Entity, changed: &C) -> bool {
+        self.0.lock().generation(entity, changed) != changed.generation()
+    }
+
+    /// Returns true if `changed` has been changed since this tracker last observed it on `entity`,
+    /// and marks the current generation as seen
+    pub fn observe<C: ChangedTrait>(&self, entity: Entity, changed: &C) -> bool {
+        let generation = changed.generation();
+        let mut ticks = self.0.lock();
+        let previous = ticks.generation(entity, changed);
+        ticks.ticks.insert(entity, (generation, changed.liveness()));
+        ticks.prune();
+        previous != generation
+    }
+
+    /// Number of entities this tracker holds a tick for
+    pub fn len(&self) -> usize {
+        self.0.lock().ticks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 
 /// With implementation
 impl<T> crate::With<ChangedFlag, crate::peano::Z> for Changed<T> {
     fn with(self, t: ChangedFlag) -> Self {
+        if t.0 {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+
         Changed {
             flag: t.0.into(),
             ..self
@@ -114,14 +249,83 @@ impl<T> crate::With<ChangedFlag, crate::peano::Z> for Changed<T> {
     }
 }
 
-impl<T, I, N> crate::With<T, crate::peano::S<I>> for Changed<N>
+/// Project and Lens implementations
+impl<T, I, N> crate::Project<T, crate::peano::S<I>> for Changed<N>
 where
-    N: crate::With<T, I>,
+    N: crate::Project<T, I>,
 {
-    fn with(self, t: T) -> Self {
-        Changed {
-            data: self.data.with(t),
-            ..self
+    fn project(&self) -> Option<&T> {
+        self.data.project()
+    }
+
+    fn project_mut(&mut self) -> Option<&mut T> {
+        self.data.project_mut()
+    }
+}
+
+impl<T, I, N> crate::Lens<T, crate::peano::S<I>> for Changed<N>
+where
+    N: crate::Lens<T, I>,
+{
+    fn lens_read(&self) -> Option<crate::MappedRwLockReadGuard<T>> {
+        self.data.lens_read()
+    }
+
+    fn lens_write(&self) -> Option<crate::MappedRwLockWriteGuard<T>> {
+        self.data.lens_write()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use legion::{IntoQuery, World};
+
+    #[test]
+    fn tracker_observes_each_generation_once() {
+        let mut world = World::default();
+        let entity = world.push((Changed::new(0u32, true),));
+        let tracker = ChangeTracker::default();
+        let changed = <&Changed<u32>>::query().get(&world, entity).unwrap();
+
+        assert!(tracker.has_changed(entity, changed));
+        assert!(tracker.observe(entity, changed));
+        assert!(!tracker.observe(entity, changed));
+
+        changed.set_changed(true);
+        assert!(tracker.observe(entity, changed));
+        assert!(!tracker.has_changed(entity, changed));
+    }
+
+    #[test]
+    fn tracker_sees_replaced_component_as_changed() {
+        let mut world = World::default();
+        let entity = world.push((Changed::new(0u32, true),));
+        let tracker = ChangeTracker::default();
+
+        let changed = <&Changed<u32>>::query().get(&world, entity).unwrap();
+        assert!(tracker.observe(entity, changed));
+
+        world
+            .entry(entity)
+            .unwrap()
+            .add_component(Changed::new(1u32, true));
+        let changed = <&Changed<u32>>::query().get(&world, entity).unwrap();
+        assert!(tracker.observe(entity, changed));
+    }
+
+    #[test]
+    fn tracker_prunes_despawned_entities() {
+        let mut world = World::default();
+        let tracker = ChangeTracker::default();
+
+        for _ in 0..64 {
+            let entity = world.push((Changed::new(0u32, true),));
+            let changed = <&Changed<u32>>::query().get(&world, entity).unwrap();
+            tracker.observe(entity, changed);
+            world.remove(entity);
         }
+
+        assert!(tracker.len() <= 16);
     }
 }
@@ -0,0 +1,340 @@
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+};
+
+use legion::{Entity, World};
+use parking_lot::RwLock;
+
+use crate::{App, Stage};
+
+/// Events are shared so that readers can release the lock before handling them
+struct EventBuffers<E> {
+    previous: Vec<Arc<E>>,
+    current: Vec<Arc<E>>,
+    /// Number of events sent before the first event in `previous`
+    start: usize,
+    /// Cursors of every reader that has read from this queue
+    readers: Vec<Weak<AtomicUsize>>,
+}
+
+impl<E> EventBuffers<E> {
+    /// Number of events sent over the queue's lifetime
+    fn end(&self) -> usize {
+        self.start + self.previous.len() + self.current.len()
+    }
+
+    /// Retained events with their ids, oldest first
+    fn iter(&self) -> impl Iterator<Item = (usize, &Arc<E>)> {
+        self.previous
+            .iter()
+            .chain(self.current.iter())
+            .enumerate()
+            .map(move |(i, event)| (self.start + i, event))
+    }
+}
+
+/// Double-buffered event queue, shared between any number of senders and [`EventReader`]s
+///
+/// Events are retained for at least two calls to [`Events::update`],
+/// and beyond that until every live reader has read them, so readers
+/// on a slower thread or schedule than the one calling `update` never miss events.
+/// A reader is tracked from its first read until it is dropped,
+/// so readers that stop reading without being dropped will hold events indefinitely.
+///
+/// Events dropped via [`Events::clear`] are skipped, and reported as missed via `tracing`.
+///
+/// ```ignore
+/// #[legion::system]
+/// #[read_component(Events<CollisionEvent>)]
+/// fn play_sounds(world: &SubWorld, #[state] reader: &EventReader<CollisionEvent>) {
+///     let events = <&Events<CollisionEvent>>::query().iter(world).next().unwrap();
+///     reader.read(events, |collision| play_impact(collision));
+/// }
+/// ```
+pub struct Events<E>(RwLock<EventBuffers<E>>);
+
+impl<E> Default for Events<E> {
+    fn default() -> Self {
+        Events(RwLock::new(EventBuffers {
+            previous: Default::default(),
+            current: Default::default(),
+            start: 0,
+            readers: Default::default(),
+        }))
+    }
+}
+
+impl<E> Events<E> {
+    pub fn send(&self, event: E) {
+        self.0.write().current.push(Arc::new(event))
+    }
+
+    pub fn send_batch(&self, events: impl IntoIterator<Item = E>) {
+        self.0
+            .write()
+            .current
+            .extend(events.into_iter().map(Arc::new))
+    }
+
+    /// Drop events from before the previous update that every live reader has read,
+    /// and begin a new buffer
+    pub fn update(&self) {
+        let mut buffers = self.0.write();
+        buffers.readers.retain(|reader| reader.strong_count() > 0);
+
+        let oldest_unread = buffers
+            .readers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|cursor| cursor.load(Ordering::Relaxed))
+            .min();
+
+        let EventBuffers {
+            previous,
+            current,
+            start,
+            ..
+        } = &mut *buffers;
+
+        let expired = match oldest_unread {
+            Some(cursor) => previous.len().min(cursor.saturating_sub(*start)),
+            None => previous.len(),
+        };
+
+        previous.drain(..expired);
+        *start += expired;
+        previous.append(current);
+    }
+
+    /// Drop all retained events
+    ///
+    /// Readers will treat the dropped events as missed.
+    pub fn clear(&self) {
+        let mut buffers = self.0.write();
+        buffers.start = buffers.end();
+        buffers.previous.clear();
+        buffers.current.clear();
+    }
+
+    /// Number of retained events
+    pub fn len(&self) -> usize {
+        let buffers = self.0.read();
+        buffers.previous.len() + buffers.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Create a reader that will only observe events sent after this call
+    pub fn reader(&self) -> EventReader<E> {
+        let mut buffers = self.0.write();
+        let reader = EventReader::new(buffers.end());
+        reader.register(&mut buffers);
+        reader
+    }
+}
+
+/// Independent cursor into an [`Events`] queue
+///
+/// Typically held as system `#[state]`, one per consuming system.
+/// A default reader starts at the oldest retained event.
+/// Each reader should only be used with a single queue.
+pub struct EventReader<E> {
+    cursor: Arc<AtomicUsize>,
+    registered: AtomicBool,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for EventReader<E> {
+    fn default() -> Self {
+        EventReader::new(0)
+    }
+}
+
+impl<E> EventReader<E> {
+    fn new(cursor: usize) -> Self {
+        EventReader {
+            cursor: Arc::new(AtomicUsize::new(cursor)),
+            registered: AtomicBool::new(false),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Have `buffers` retain events until this reader has read them
+    fn register(&self, buffers: &mut EventBuffers<E>) {
+        if !self.registered.swap(true, Ordering::Relaxed) {
+            buffers.readers.push(Arc::downgrade(&self.cursor));
+        }
+    }
+
+    /// Call `f` with each event sent since the previous read, oldest first
+    ///
+    /// `f` runs after the queue is unlocked, so it may send events of its own;
+    /// those are left for the next read.
+    pub fn read(&self, events: &Events<E>, mut f: impl FnMut(&E)) {
+        // Events dropped before the first read were never owed to this reader
+        let first_read = !self.registered.load(Ordering::Relaxed);
+        if first_read {
+            self.register(&mut events.0.write());
+        }
+
+        let pending = {
+            let buffers = events.0.read();
+            let cursor = self.cursor.swap(buffers.end(), Ordering::Relaxed);
+
+            if cursor < buffers.start && !first_read {
+                tracing::warn!(
+                    "{} reader missed {} events",
+                    std::any::type_name::<E>(),
+                    buffers.start - cursor
+                );
+            }
+
+            buffers
+                .iter()
+                .filter(|(id, _)| *id >= cursor)
+                .map(|(_, event)| event.clone())
+                .collect::<Vec<_>>()
+        };
+
+        for event in pending {
+            f(&event)
+        }
+    }
+
+    /// Collect each event sent since the previous read, oldest first
+    pub fn read_cloned(&self, events: &Events<E>) -> Vec<E>
+    where
+        E: Clone,
+    {
+        let mut out = vec![];
+        self.read(events, |event| out.push(event.clone()));
+        out
+    }
+}
+
+pub fn assemble_events<E: Send + Sync + 'static>(world: &mut World) -> Entity {
+    world.push((Events::<E>::default(),))
+}
+
+/// Advance every [`Events<E>`] queue to a new buffer
+///
+/// Scheduled by [`App::add_events`]; readers elsewhere rely on the retention guarantee of [`Events`].
+#[legion::system(par_for_each)]
+pub fn update_events<E: Send + Sync + 'static>(events: &Events<E>) {
+    events.update()
+}
+
+impl<T> App<T> {
+    /// Assemble an [`Events<E>`] queue, and advance it during [`Stage::PostUpdate`]
+    pub fn add_events<E: Send + Sync + 'static>(self) -> Self {
+        self.add_assembler(|world| {
+            assemble_events::<E>(world);
+        })
+        .add_system(Stage::PostUpdate, update_events_system::<E>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readers_have_independent_cursors() {
+        let events = Events::default();
+        let first = events.reader();
+        events.send(1);
+
+        let second = events.reader();
+        events.send_batch([2, 3]);
+
+        assert_eq!(first.read_cloned(&events), vec![1, 2, 3]);
+        assert_eq!(second.read_cloned(&events), vec![2, 3]);
+        assert_eq!(first.read_cloned(&events), Vec::<i32>::new());
+
+        events.send(4);
+        assert_eq!(first.read_cloned(&events), vec![4]);
+        assert_eq!(second.read_cloned(&events), vec![4]);
+    }
+
+    #[test]
+    fn default_reader_starts_at_oldest_retained_event() {
+        let events = Events::default();
+        events.send(1);
+        events.update();
+        events.send(2);
+
+        assert_eq!(EventReader::default().read_cloned(&events), vec![1, 2]);
+
+        events.update();
+        events.update();
+        assert_eq!(
+            EventReader::default().read_cloned(&events),
+            Vec::<i32>::new()
+        );
+    }
+
+    #[test]
+    fn unread_events_outlive_two_updates() {
+        let events = Events::default();
+        let reader = events.reader();
+        events.send(1);
+
+        events.update();
+        events.send(2);
+        events.update();
+        events.update();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(reader.read_cloned(&events), vec![1, 2]);
+
+        events.update();
+        events.update();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn dropped_readers_release_events() {
+        let events = Events::default();
+        let reader = events.reader();
+        events.send(1);
+
+        drop(reader);
+        events.update();
+        events.update();
+
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn readers_can_send_while_reading() {
+        let events = Events::default();
+        let reader = events.reader();
+        events.send(1);
+
+        let mut read = vec![];
+        reader.read(&events, |event| {
+            read.push(*event);
+            events.send(event + 1);
+        });
+
+        assert_eq!(read, vec![1]);
+        assert_eq!(reader.read_cloned(&events), vec![2]);
+    }
+
+    #[test]
+    fn cleared_events_are_skipped() {
+        let events = Events::default();
+        let reader = events.reader();
+        events.send(1);
+        events.clear();
+        events.send(2);
+
+        assert_eq!(reader.read_cloned(&events), vec![2]);
+    }
+}
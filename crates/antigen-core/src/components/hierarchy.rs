@@ -0,0 +1,279 @@
+use std::collections::{HashMap, HashSet};
+
+use legion::{
+    query::component,
+    systems::CommandBuffer,
+    world::{EntityAccessError, SubWorld},
+    Entity, EntityStore, IntoQuery, World,
+};
+
+use crate::{
+    App, CascadePolicy, Construct, DebugEntity, IndirectComponent, Plugin, ReadWriteLock, RwLock,
+    Stage, Usage, WorldCommandQueue,
+};
+
+pub enum Children {}
+
+/// The entity this entity is parented to
+///
+/// Reparenting is done by assembling a new parent via [`assemble_parent`];
+/// [`sync_children`](sync_children_system) will update the corresponding [`ChildrenComponent`]s.
+pub type ParentComponent = IndirectComponent<ChildrenComponent>;
+
+/// The entities parented to this entity, in the order they were first parented
+///
+/// Maintained by [`sync_children`](sync_children_system), and should be treated as read-only.
+pub type ChildrenComponent = Usage<Children, RwLock<Vec<Entity>>>;
+
+/// Parent `entity` to `parent`, replacing any existing parent
+///
+/// `parent` is given an empty [`ChildrenComponent`] if it has none,
+/// so that the reference resolves before [`sync_children`](sync_children_system) next runs.
+pub fn assemble_parent(cmd: &mut CommandBuffer, entity: Entity, parent: Entity) {
+    cmd.add_component(entity, ParentComponent::new(parent));
+    cmd.exec_mut(move |world, _| {
+        if let Some(mut entry) = world.entry(parent) {
+            if entry.get_component::<ChildrenComponent>().is_err() {
+                entry.add_component(ChildrenComponent::construct(vec![]));
+            }
+        }
+    });
+}
+
+/// Rebuild [`ChildrenComponent`]s from the current set of [`ParentComponent`]s
+///
+/// Existing children keep their order, new children are appended,
+/// and parents that lose all their children have their [`ChildrenComponent`] removed.
+///
+/// Children of missing entities are skipped, and reported by [`HierarchyPlugin`]'s validation.
+#[legion::system]
+#[read_component(ParentComponent)]
+#[read_component(ChildrenComponent)]
+pub fn sync_children(world: &SubWorld, cmd: &mut CommandBuffer) {
+    let mut parents = HashMap::<Entity, Vec<Entity>>::default();
+    for (entity, parent) in <(Entity, &ParentComponent)>::query().iter(world) {
+        let parent = parent.target();
+        if let Err(EntityAccessError::EntityNotFound) = world.entry_ref(parent) {
+            continue;
+        }
+        parents.entry(parent).or_default().push(*entity);
+    }
+
+    for (entity, children) in <(Entity, &ChildrenComponent)>::query().iter(world) {
+        let mut new_children = if let Some(new_children) = parents.remove(entity) {
+            new_children
+        } else {
+            cmd.remove_component::<ChildrenComponent>(*entity);
+            continue;
+        };
+
+        let mut children = children.write();
+        children.retain(|child| new_children.contains(child));
+        new_children.retain(|child| !children.contains(child));
+        children.extend(new_children);
+    }
+
+    for (parent, children) in parents {
+        cmd.add_component(parent, ChildrenComponent::construct(children));
+    }
+}
+
+/// Return the parent of `entity`, if any
+pub fn parent_of<W: EntityStore>(world: &W, entity: Entity) -> Option<Entity> {
+    <&ParentComponent>::query()
+        .get(world, entity)
+        .ok()
+        .map(|parent| parent.target())
+}
+
+/// Return the children of `entity` as of the last [`sync_children`](sync_children_system)
+pub fn children_of<W: EntityStore>(world: &W, entity: Entity) -> Vec<Entity> {
+    <&ChildrenComponent>::query()
+        .get(world, entity)
+        .map(|children| children.read().clone())
+        .unwrap_or_default()
+}
+
+/// Return the ancestors of `entity`, nearest first
+pub fn ancestors<W: EntityStore>(world: &W, entity: Entity) -> Vec<Entity> {
+    let mut ancestors = vec![];
+    let mut visited = HashSet::from([entity]);
+
+    let mut current = entity;
+    while let Some(parent) = parent_of(world, current) {
+        if !visited.insert(parent) {
+            tracing::warn!("Parent cycle detected at {:?}", DebugEntity(parent));
+            break;
+        }
+        ancestors.push(parent);
+        current = parent;
+    }
+
+    ancestors
+}
+
+/// Return the descendants of `entity` in depth-first order, excluding `entity` itself
+pub fn descendants<W: EntityStore>(world: &W, entity: Entity) -> Vec<Entity> {
+    let mut descendants = vec![];
+    let mut visited = HashSet::from([entity]);
+
+    let mut stack = children_of(world, entity);
+    stack.reverse();
+    while let Some(child) = stack.pop() {
+        if !visited.insert(child) {
+            continue;
+        }
+        descendants.push(child);
+
+        let mut children = children_of(world, child);
+        children.reverse();
+        stack.extend(children);
+    }
+
+    descendants
+}
+
+/// Return every entity in the hierarchy in depth-first order, starting from each root
+///
+/// A root is an entity with children, but no parent.
+pub fn depth_first<W: EntityStore>(world: &W) -> Vec<Entity> {
+    let roots = <Entity>::query()
+        .filter(component::<ChildrenComponent>() & !component::<ParentComponent>())
+        .iter(world)
+        .copied()
+        .collect::<Vec<_>>();
+
+    roots
+        .into_iter()
+        .flat_map(|root| std::iter::once(root).chain(descendants(world, root)))
+        .collect()
+}
+
+/// Despawn `entity` and all of its descendants, removing it from its parent's children
+pub fn despawn_recursive(world: &mut World, entity: Entity) {
+    if let Some(parent) = parent_of(&*world, entity) {
+        if let Ok(children) = <&ChildrenComponent>::query().get(&*world, parent) {
+            children.write().retain(|child| *child != entity);
+        }
+    }
+
+    for descendant in descendants(&*world, entity) {
+        world.remove(descendant);
+    }
+
+    world.remove(entity);
+}
+
+/// Keeps [`ChildrenComponent`]s in sync with [`ParentComponent`]s at the end of each event loop update
+///
+/// Also registers [`ParentComponent`] with the [`IndirectComponentRegistry`](crate::IndirectComponentRegistry),
+/// reporting missing parents and despawning children along with their parent on
+/// [`despawn_cascade`](crate::IndirectComponentRegistry::despawn_cascade).
+pub struct HierarchyPlugin;
+
+impl<E> Plugin<E> for HierarchyPlugin {
+    fn build(&self, app: App<E>) -> App<E> {
+        app.add_indirect_component::<ChildrenComponent>(CascadePolicy::Despawn)
+            .add_system(Stage::PostUpdate, sync_children_system())
+    }
+}
+
+impl WorldCommandQueue {
+    /// Despawn an entity and all of its descendants at the next flush
+    pub fn despawn_recursive(&self, entity: Entity) {
+        self.record(move |cmd| cmd.exec_mut(move |world, _| despawn_recursive(world, entity)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use legion::{Resources, Schedule};
+
+    fn sync(world: &mut World) {
+        Schedule::builder()
+            .add_system(sync_children_system())
+            .build()
+            .execute(world, &mut Resources::default());
+    }
+
+    /// `root` parenting `left` and `right`, with `left` parenting `leaf`
+    fn tree(world: &mut World) -> [Entity; 4] {
+        let root = world.push(());
+        let left = world.push((ParentComponent::new(root),));
+        let right = world.push((ParentComponent::new(root),));
+        let leaf = world.push((ParentComponent::new(left),));
+        sync(world);
+        [root, left, right, leaf]
+    }
+
+    #[test]
+    fn sync_children_keeps_order_and_removes_empty() {
+        let mut world = World::default();
+        let parent = world.push(());
+        let first = world.push((ParentComponent::new(parent),));
+        let second = world.push((ParentComponent::new(parent),));
+
+        sync(&mut world);
+        assert_eq!(children_of(&world, parent), vec![first, second]);
+
+        world
+            .entry(first)
+            .unwrap()
+            .remove_component::<ParentComponent>();
+        let third = world.push((ParentComponent::new(parent),));
+
+        sync(&mut world);
+        assert_eq!(children_of(&world, parent), vec![second, third]);
+
+        world.remove(second);
+        world.remove(third);
+
+        sync(&mut world);
+        assert!(<&ChildrenComponent>::query().get(&world, parent).is_err());
+    }
+
+    #[test]
+    fn ancestors_are_nearest_first() {
+        let mut world = World::default();
+        let [root, left, _, leaf] = tree(&mut world);
+
+        assert_eq!(ancestors(&world, leaf), vec![left, root]);
+        assert!(ancestors(&world, root).is_empty());
+    }
+
+    #[test]
+    fn ancestors_stop_at_cycles() {
+        let mut world = World::default();
+        let first = world.push(());
+        let second = world.push((ParentComponent::new(first),));
+        world
+            .entry(first)
+            .unwrap()
+            .add_component(ParentComponent::new(second));
+
+        assert_eq!(ancestors(&world, first), vec![second]);
+        assert_eq!(ancestors(&world, second), vec![first]);
+    }
+
+    #[test]
+    fn depth_first_visits_children_before_siblings() {
+        let mut world = World::default();
+        let [root, left, right, leaf] = tree(&mut world);
+
+        assert_eq!(depth_first(&world), vec![root, left, leaf, right]);
+    }
+
+    #[test]
+    fn despawn_recursive_removes_descendants_and_detaches() {
+        let mut world = World::default();
+        let [root, left, right, leaf] = tree(&mut world);
+
+        despawn_recursive(&mut world, left);
+
+        assert!(!world.contains(left));
+        assert!(!world.contains(leaf));
+        assert!(world.contains(right));
+        assert_eq!(children_of(&world, root), vec![right]);
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+};
+
+use legion::{storage::Component, systems::CommandBuffer, Entity, IntoQuery, World};
+use parking_lot::RwLock;
+
+use super::lifecycle::{register_lifecycle_tracker, LifecycleTracker};
+use crate::{ChangedTrait, DebugEntity, ImmutableResources, ImmutableWorld, ReadWriteLock};
+
+struct IndexMaps<K> {
+    /// Key -> entities holding it, in the order they were indexed; the last is the indexed entity
+    entities: BTreeMap<K, Vec<Entity>>,
+    /// Entity -> key, including entities shadowed by a duplicate key
+    keys: HashMap<Entity, K>,
+}
+
+impl<K: Ord + Clone> IndexMaps<K> {
+    fn insert<C>(&mut self, entity: Entity, key: K) {
+        if self.keys.get(&entity) == Some(&key) {
+            return;
+        }
+
+        self.remove(entity);
+        self.keys.insert(entity, key.clone());
+
+        let entities = self.entities.entry(key).or_default();
+        if let Some(existing) = entities.last() {
+            tracing::warn!(
+                "{} index has a duplicate key for {:?} and {:?}",
+                std::any::type_name::<C>(),
+                DebugEntity(*existing),
+                DebugEntity(entity)
+            );
+        }
+        entities.push(entity);
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        let key = if let Some(key) = self.keys.remove(&entity) {
+            key
+        } else {
+            return;
+        };
+
+        // Hands the key back to the most recently indexed remaining duplicate, if any
+        if let Some(entities) = self.entities.get_mut(&key) {
+            entities.retain(|candidate| *candidate != entity);
+            if entities.is_empty() {
+                self.entities.remove(&key);
+            }
+        }
+    }
+}
+
+impl<K: Ord> IndexMaps<K> {
+    fn get<Q>(&self, key: &Q) -> Option<Entity>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entities.get(key)?.last().copied()
+    }
+}
+
+/// Key -> entity lookup derived from the value of each entity's `C`
+///
+/// Kept up to date at sync points by [`run_lifecycle_hooks`](crate::run_lifecycle_hooks),
+/// covering insertion, change and removal of `C`, as well as entities whose key becomes `None`.
+/// Only added, removed and changed entities are applied, with [`assemble_changed_index`]
+/// further skipping key derivation for components whose generation hasn't moved.
+///
+/// ```ignore
+/// assemble_index(world, |window: &WindowComponent| window.read_ready().map(|window| window.id()));
+/// ...
+/// let window_entity_map = resources.get::<Index<WindowId, WindowComponent>>()?;
+/// let entity = window_entity_map.get(&window_id);
+/// ```
+///
+/// If several entities share a key, the most recently indexed is used,
+/// and the others are omitted from both [`Index::get`] and [`Index::key`]
+/// until it loses the key.
+pub struct Index<K, C> {
+    maps: RwLock<IndexMaps<K>>,
+    _phantom: PhantomData<fn() -> C>,
+}
+
+impl<K, C> Default for Index<K, C> {
+    fn default() -> Self {
+        Index {
+            maps: RwLock::new(IndexMaps {
+                entities: Default::default(),
+                keys: Default::default(),
+            }),
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<K: Ord, C> Index<K, C> {
+    /// Return the entity indexed under `key`
+    pub fn get<Q>(&self, key: &Q) -> Option<Entity>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.maps.read().get(key)
+    }
+
+    /// Return the key `entity` is indexed under
+    pub fn key(&self, entity: Entity) -> Option<K>
+    where
+        K: Clone,
+    {
+        let maps = self.maps.read();
+        let key = maps.keys.get(&entity)?;
+        if maps.get(key) == Some(entity) {
+            Some(key.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Number of indexed entities
+    pub fn len(&self) -> usize {
+        self.maps.read().entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Applies added, removed and changed keys to an [`Index<K, C>`] at each sync point
+struct IndexTracker<K, C, F> {
+    key: F,
+    /// Generation of a component, if it tracks one, used to skip unchanged entities
+    generation: fn(&C) -> Option<usize>,
+    /// Generation of each entity's component as of its last key derivation
+    tracked: HashMap<Entity, Option<usize>>,
+    resources: ImmutableResources,
+    _phantom: PhantomData<fn() -> (K, C)>,
+}
+
+impl<K, C, F> LifecycleTracker for IndexTracker<K, C, F>
+where
+    K: Ord + Clone + Send + Sync + 'static,
+    C: Component,
+    F: Fn(&C) -> Option<K> + Send,
+{
+    fn update(&mut self, world: &World, _: &mut CommandBuffer) {
+        let index = if let Ok(index) = self.resources.get::<Index<K, C>>() {
+            index
+        } else {
+            return;
+        };
+
+        let mut current = HashMap::default();
+        let mut derived = vec![];
+        for (entity, component) in <(Entity, &C)>::query().iter(world) {
+            let generation = (self.generation)(component);
+            current.insert(*entity, generation);
+
+            let unchanged = generation.is_some() && self.tracked.get(entity) == Some(&generation);
+            if !unchanged {
+                derived.push((*entity, (self.key)(component)));
+            }
+        }
+
+        let removed = self
+            .tracked
+            .keys()
+            .filter(|entity| !current.contains_key(entity))
+            .copied()
+            .collect::<Vec<_>>();
+
+        self.tracked = current;
+
+        let changed = {
+            let maps = index.maps.read();
+            derived
+                .into_iter()
+                .filter(|(entity, key)| maps.keys.get(entity) != key.as_ref())
+                .collect::<Vec<_>>()
+        };
+
+        if removed.is_empty() && changed.is_empty() {
+            return;
+        }
+
+        let mut maps = index.maps.write();
+        for entity in removed {
+            maps.remove(entity);
+        }
+
+        for (entity, key) in changed {
+            match key {
+                Some(key) => maps.insert::<C>(entity, key),
+                None => maps.remove(entity),
+            }
+        }
+    }
+}
+
+fn register_index<K, C>(
+    world: &ImmutableWorld,
+    key: impl Fn(&C) -> Option<K> + Send + Sync + 'static,
+    generation: fn(&C) -> Option<usize>,
+) where
+    K: Ord + Clone + Send + Sync + 'static,
+    C: Component,
+{
+    world.resources().insert(Index::<K, C>::default());
+
+    register_lifecycle_tracker(
+        &mut world.write(),
+        Box::new(IndexTracker {
+            key,
+            generation,
+            tracked: Default::default(),
+            resources: world.resources().clone(),
+            _phantom: PhantomData,
+        }),
+    );
+}
+
+/// Insert an [`Index<K, C>`] keyed by `key` into the world's resources, and register it for updates
+///
+/// Keys are derived for every entity at each sync point; see [`assemble_changed_index`]
+/// for components that track their own changes.
+pub fn assemble_index<K, C>(
+    world: &ImmutableWorld,
+    key: impl Fn(&C) -> Option<K> + Send + Sync + 'static,
+) where
+    K: Ord + Clone + Send + Sync + 'static,
+    C: Component,
+{
+    register_index(world, key, |_| None)
+}
+
+/// [`assemble_index`] for a [`ChangedTrait`] component,
+/// deriving keys only for entities whose component generation has moved
+pub fn assemble_changed_index<K, C>(
+    world: &ImmutableWorld,
+    key: impl Fn(&C) -> Option<K> + Send + Sync + 'static,
+) where
+    K: Ord + Clone + Send + Sync + 'static,
+    C: Component + ChangedTrait,
+{
+    register_index(world, key, |component| Some(component.generation()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_lifecycle_hooks, Changed};
+    use legion::EntityStore;
+
+    type Label = RwLock<String>;
+
+    fn label_key(label: &Label) -> Option<String> {
+        let label = label.read();
+        if label.is_empty() {
+            None
+        } else {
+            Some(label.clone())
+        }
+    }
+
+    fn set_label(world: &ImmutableWorld, entity: Entity, label: &str) {
+        let world = world.read();
+        let entry = world.entry_ref(entity).unwrap();
+        *entry.get_component::<Label>().unwrap().write() = label.to_string();
+    }
+
+    fn entities(count: usize) -> Vec<Entity> {
+        let mut world = World::default();
+        (0..count).map(|_| world.push(())).collect()
+    }
+
+    #[test]
+    fn duplicate_keys_index_latest_entity() {
+        let index = Index::<&str, ()>::default();
+        let entities = entities(2);
+
+        {
+            let mut maps = index.maps.write();
+            maps.insert::<()>(entities[0], "shared");
+            maps.insert::<()>(entities[1], "shared");
+        }
+
+        assert_eq!(index.get("shared"), Some(entities[1]));
+        assert_eq!(index.key(entities[1]), Some("shared"));
+        assert_eq!(index.key(entities[0]), None);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn removing_indexed_duplicate_promotes_previous() {
+        let index = Index::<&str, ()>::default();
+        let entities = entities(3);
+
+        {
+            let mut maps = index.maps.write();
+            for entity in &entities {
+                maps.insert::<()>(*entity, "shared");
+            }
+        }
+
+        index.maps.write().remove(entities[2]);
+
+        assert_eq!(index.get("shared"), Some(entities[1]));
+        assert_eq!(index.key(entities[1]), Some("shared"));
+        assert_eq!(index.key(entities[2]), None);
+
+        index.maps.write().remove(entities[1]);
+        index.maps.write().remove(entities[0]);
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn changing_key_releases_previous() {
+        let index = Index::<&str, ()>::default();
+        let entity = entities(1)[0];
+
+        index.maps.write().insert::<()>(entity, "before");
+        index.maps.write().insert::<()>(entity, "after");
+
+        assert_eq!(index.get("before"), None);
+        assert_eq!(index.get("after"), Some(entity));
+        assert_eq!(index.key(entity), Some("after"));
+    }
+
+    #[test]
+    fn sync_point_indexes_inserted_entities() {
+        let world = ImmutableWorld::default();
+        assemble_index(&world, label_key);
+
+        let first = world.write().push((Label::new("first".to_string()),));
+        let unlabeled = world.write().push((Label::new(String::new()),));
+        run_lifecycle_hooks(&world);
+
+        let index = world.resources().get::<Index<String, Label>>().unwrap();
+        assert_eq!(index.get("first"), Some(first));
+        assert_eq!(index.key(first).as_deref(), Some("first"));
+        assert_eq!(index.key(unlabeled), None);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn sync_point_applies_key_changes() {
+        let world = ImmutableWorld::default();
+        assemble_index(&world, label_key);
+
+        let entity = world.write().push((Label::new("before".to_string()),));
+        run_lifecycle_hooks(&world);
+
+        set_label(&world, entity, "after");
+        run_lifecycle_hooks(&world);
+
+        let index = world.resources().get::<Index<String, Label>>().unwrap();
+        assert_eq!(index.get("before"), None);
+        assert_eq!(index.get("after"), Some(entity));
+        assert_eq!(index.key(entity).as_deref(), Some("after"));
+    }
+
+    #[test]
+    fn sync_point_removes_entities_whose_key_becomes_none() {
+        let world = ImmutableWorld::default();
+        assemble_index(&world, label_key);
+
+        let entity = world.write().push((Label::new("label".to_string()),));
+        run_lifecycle_hooks(&world);
+
+        set_label(&world, entity, "");
+        run_lifecycle_hooks(&world);
+
+        let index = world.resources().get::<Index<String, Label>>().unwrap();
+        assert_eq!(index.get("label"), None);
+        assert_eq!(index.key(entity), None);
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn sync_point_removes_despawned_entities_and_promotes_duplicates() {
+        let world = ImmutableWorld::default();
+        assemble_index(&world, label_key);
+
+        let entities = (0..2)
+            .map(|_| world.write().push((Label::new("shared".to_string()),)))
+            .collect::<Vec<_>>();
+        run_lifecycle_hooks(&world);
+
+        let index = world.resources().get::<Index<String, Label>>().unwrap();
+        let indexed = index.get("shared").unwrap();
+        let shadowed = *entities.iter().find(|entity| **entity != indexed).unwrap();
+        assert_eq!(index.key(shadowed), None);
+
+        world.write().remove(indexed);
+        run_lifecycle_hooks(&world);
+
+        assert_eq!(index.get("shared"), Some(shadowed));
+        assert_eq!(index.key(indexed), None);
+        assert_eq!(index.key(shadowed).as_deref(), Some("shared"));
+    }
+
+    #[test]
+    fn changed_index_only_rederives_moved_generations() {
+        let world = ImmutableWorld::default();
+        assemble_changed_index(&world, |label: &Changed<Label>| label_key(label));
+
+        let entity = world
+            .write()
+            .push((Changed::new(Label::new("before".to_string()), false),));
+        run_lifecycle_hooks(&world);
+
+        let update = |label: &str, changed: bool| {
+            let world = world.read();
+            let entry = world.entry_ref(entity).unwrap();
+            let component = entry.get_component::<Changed<Label>>().unwrap();
+            *component.write() = label.to_string();
+            if changed {
+                component.set_changed(true);
+            }
+        };
+
+        // Unflagged writes are invisible to the index
+        update("unflagged", false);
+        run_lifecycle_hooks(&world);
+
+        let index = world
+            .resources()
+            .get::<Index<String, Changed<Label>>>()
+            .unwrap();
+        assert_eq!(index.get("before"), Some(entity));
+
+        update("after", true);
+        run_lifecycle_hooks(&world);
+
+        assert_eq!(index.get("before"), None);
+        assert_eq!(index.get("after"), Some(entity));
+    }
+}
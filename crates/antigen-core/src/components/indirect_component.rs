@@ -2,9 +2,14 @@ use std::marker::PhantomData;
 
 use legion::{Entity, IntoQuery, storage::Component, systems::CommandBuffer, world::EntityAccessError};
 
+use crate::DebugEntity;
+
 /// A component referenced by entity ID,
 /// which can be fetched given a reference to a World or SubWorld
-#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+///
+/// The target is not guaranteed to exist; register the type with an
+/// [`IndirectComponentRegistry`](crate::IndirectComponentRegistry) to detect dangling references.
+#[derive(Copy, Clone, PartialEq, Hash)]
 pub struct IndirectComponent<T> {
     target: Entity,
     _phantom: PhantomData<T>,
@@ -23,6 +28,31 @@ impl<T> IndirectComponent<T> {
     }
 }
 
+/// Shows the target's name via [`DebugEntity`]
+impl<T> std::fmt::Debug for IndirectComponent<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndirectComponent")
+            .field("component", &std::any::type_name::<T>())
+            .field("target", &DebugEntity(self.target))
+            .finish()
+    }
+}
+
+// Serde implementation, as the target entity
+#[cfg(feature = "serialize")]
+impl<T> serde::Serialize for IndirectComponent<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.target, serializer)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de, T> serde::Deserialize<'de> for IndirectComponent<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Entity as serde::Deserialize>::deserialize(deserializer).map(IndirectComponent::new)
+    }
+}
+
 pub trait GetIndirect<'a, T> {
     fn get_indirect(self, indirect: &IndirectComponent<T>) -> Result<&'a T, EntityAccessError>;
 }
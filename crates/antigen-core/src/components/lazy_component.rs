@@ -1,9 +1,33 @@
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
 
-/// A lazily-initialized component that can be pending, ready, or dropped 
+use legion::{storage::Component, world::EntityStore, Entity, IntoQuery};
+use parking_lot::Mutex;
+
+use crate::{
+    MappedRwLockReadGuard, MappedRwLockWriteGuard, ReadWriteLock, RwLockReadGuard,
+    RwLockWriteGuard,
+};
+
+/// Error stored by a [`LazyComponent`] whose creation failed
+pub type LazyError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A lazily-initialized component that can be pending, loading, ready, failed, or dropped
+///
+/// Creators should only act on `Pending` components,
+/// and record errors via [`LazyComponent::set_failed`] instead of panicking.
+/// Creators that wait on another lazy component mark theirs `Loading` in the meantime,
+/// and pass on its failures, or its return to `Pending` after a retry.
+/// Failed components can be returned to `Pending` by a [`RetryPolicy`].
 #[derive(Debug)]
 pub enum LazyComponent<T> {
     Pending,
+    Loading,
     Ready(T),
+    Failed(LazyError),
     Dropped,
 }
 
@@ -12,10 +36,18 @@ impl<T> LazyComponent<T> {
         matches!(self, LazyComponent::Pending)
     }
 
+    pub fn is_loading(&self) -> bool {
+        matches!(self, LazyComponent::Loading)
+    }
+
     pub fn is_ready(&self) -> bool {
         matches!(self, LazyComponent::Ready(_))
     }
 
+    pub fn is_failed(&self) -> bool {
+        matches!(self, LazyComponent::Failed(_))
+    }
+
     pub fn is_dropped(&self) -> bool {
         matches!(self, LazyComponent::Dropped)
     }
@@ -24,50 +56,237 @@ impl<T> LazyComponent<T> {
         *self = LazyComponent::Pending;
     }
 
+    pub fn set_loading(&mut self) {
+        *self = LazyComponent::Loading;
+    }
+
     pub fn set_ready(&mut self, inner: T) {
         *self = LazyComponent::Ready(inner);
     }
 
+    pub fn set_failed<E: Into<LazyError>>(&mut self, error: E) {
+        *self = LazyComponent::Failed(error.into());
+    }
+
     pub fn set_dropped(&mut self) {
         *self = LazyComponent::Dropped;
     }
+
+    /// Return the error that caused this component to fail, if any
+    pub fn error(&self) -> Option<&LazyError> {
+        match self {
+            LazyComponent::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Serialized form of a [`LazyComponent`]
+///
+/// Ready values are typically GPU or OS handles that can't outlive the process,
+/// so every state other than `Dropped` is saved as `Pending` and recreated by its creator on load.
+#[cfg(feature = "serialize")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "LazyComponent")]
+enum LazySnapshot {
+    Pending,
+    Dropped,
+}
+
+#[cfg(feature = "serialize")]
+impl<T> serde::Serialize for LazyComponent<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            LazyComponent::Dropped => LazySnapshot::Dropped,
+            _ => LazySnapshot::Pending,
+        }
+        .serialize(serializer)
+    }
 }
 
-/// Read a RwLock<LazyComponent<T>> and match against LazyComponent::Ready(T), else panic
-#[macro_export]
-macro_rules! lazy_read_ready_else_panic {
-    ($cmp:ident) => {
-        let $cmp = $crate::ReadWriteLock::read($cmp);
-        let $cmp = if let LazyComponent::Ready($cmp) = &*$cmp {
-            $cmp
-        } else {
-            panic!("LazyComponent is not ready");
-        };
-    };
+#[cfg(feature = "serialize")]
+impl<'de, T> serde::Deserialize<'de> for LazyComponent<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match LazySnapshot::deserialize(deserializer)? {
+            LazySnapshot::Pending => LazyComponent::Pending,
+            LazySnapshot::Dropped => LazyComponent::Dropped,
+        })
+    }
 }
 
-/// Read a RwLock<LazyComponent<T>> and match against LazyComponent::Ready(T), else continue
-#[macro_export]
-macro_rules! lazy_read_ready_else_continue {
-    ($cmp:ident) => {
-        let $cmp = $crate::ReadWriteLock::read($cmp);
-        let $cmp = if let LazyComponent::Ready($cmp) = &*$cmp {
-            $cmp
-        } else {
-            continue;
-        };
-    };
+/// Project implementation, reaching the inner value only when ready
+impl<T, I, N> crate::Project<T, crate::peano::S<I>> for LazyComponent<N>
+where
+    N: crate::Project<T, I>,
+{
+    fn project(&self) -> Option<&T> {
+        match self {
+            LazyComponent::Ready(inner) => inner.project(),
+            _ => None,
+        }
+    }
+
+    fn project_mut(&mut self) -> Option<&mut T> {
+        match self {
+            LazyComponent::Ready(inner) => inner.project_mut(),
+            _ => None,
+        }
+    }
 }
 
-/// Read a RwLock<LazyComponent<T>> and match against LazyComponent::Ready(T), else return
-#[macro_export]
-macro_rules! lazy_read_ready_else_return {
-    ($cmp:ident) => {
-        let $cmp = $cmp.read();
-        let $cmp = if let LazyComponent::Ready($cmp) = &*$cmp {
-            $cmp
-        } else {
-            return;
-        };
-    };
+/// Guarded access to the inner value of a locked [`LazyComponent`]
+///
+/// Implemented for any [`ReadWriteLock<LazyComponent<T>>`], including those wrapped in
+/// [`Usage`](crate::Usage) or [`Changed`](crate::Changed), so a single call reaches the ready value:
+/// ```ignore
+/// let buffer = if let Some(buffer) = buffer_component.read_ready() {
+///     buffer
+/// } else {
+///     return;
+/// };
+/// ```
+///
+/// Types that lock more than one kind of [`LazyComponent`] can disambiguate via
+/// `LazyReadWriteLock::<T>::read_ready(component)`.
+pub trait LazyReadWriteLock<T>: ReadWriteLock<LazyComponent<T>> {
+    /// Read-lock and return the inner value if ready
+    fn read_ready(&self) -> Option<MappedRwLockReadGuard<T>> {
+        RwLockReadGuard::try_map(self.read(), |lazy| match lazy {
+            LazyComponent::Ready(inner) => Some(inner),
+            _ => None,
+        })
+        .ok()
+    }
+
+    /// Write-lock and return the inner value if ready
+    fn write_ready(&self) -> Option<MappedRwLockWriteGuard<T>> {
+        RwLockWriteGuard::try_map(self.write(), |lazy| match lazy {
+            LazyComponent::Ready(inner) => Some(inner),
+            _ => None,
+        })
+        .ok()
+    }
+}
+
+impl<T, L> LazyReadWriteLock<T> for L where L: ReadWriteLock<LazyComponent<T>> {}
+
+/// Collect the entities and error messages of every failed `C` in `world`
+pub fn lazy_failures<C, T, W>(world: &W) -> Vec<(Entity, String)>
+where
+    C: Component + ReadWriteLock<LazyComponent<T>>,
+    W: EntityStore,
+{
+    <(Entity, &C)>::query()
+        .iter(world)
+        .flat_map(|(entity, component)| {
+            component
+                .read()
+                .error()
+                .map(|error| (*entity, error.to_string()))
+        })
+        .collect()
+}
+
+/// Delay before the second retry of a [`RetryPolicy`] created via [`RetryPolicy::new`]
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Retry policy for a lazy component of type `C`, used by [`retry_failed`](crate::retry_failed_system)
+///
+/// Each retry returns the component to `Pending` so that its creator will run again,
+/// and attempts are restored by [`reset_retries`](crate::reset_retries_system)
+/// once the end result of its chain becomes ready.
+///
+/// The first retry is immediate, after which the delay between retries starts at the backoff
+/// and doubles with each attempt.
+#[derive(Debug)]
+pub struct RetryPolicy<C> {
+    max_attempts: usize,
+    backoff: Duration,
+    attempts: AtomicUsize,
+    last_attempt: Mutex<Option<Instant>>,
+    _phantom: PhantomData<C>,
+}
+
+impl<C> RetryPolicy<C> {
+    pub fn new(max_attempts: usize) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff: DEFAULT_RETRY_BACKOFF,
+            attempts: Default::default(),
+            last_attempt: Default::default(),
+            _phantom: Default::default(),
+        }
+    }
+
+    pub fn with_backoff(self, backoff: Duration) -> Self {
+        RetryPolicy { backoff, ..self }
+    }
+
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.attempts.load(Ordering::Relaxed)
+    }
+
+    /// Consume an attempt, returning false if none remain or the backoff since the last hasn't elapsed
+    pub fn try_attempt(&self) -> bool {
+        let mut last_attempt = self.last_attempt.lock();
+        let attempts = self.attempts();
+        if attempts >= self.max_attempts {
+            return false;
+        }
+
+        if let Some(last_attempt) = *last_attempt {
+            let exponent = (attempts - 1).min(31) as u32;
+            let delay = self.backoff.saturating_mul(1 << exponent);
+            if last_attempt.elapsed() < delay {
+                return false;
+            }
+        }
+
+        self.attempts.store(attempts + 1, Ordering::Relaxed);
+        *last_attempt = Some(Instant::now());
+        true
+    }
+
+    /// Restore all attempts, ex. after a successful load
+    pub fn reset(&self) {
+        let mut last_attempt = self.last_attempt.lock();
+        self.attempts.store(0, Ordering::Relaxed);
+        *last_attempt = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_stop_at_max_attempts() {
+        let policy = RetryPolicy::<()>::new(2).with_backoff(Duration::ZERO);
+        assert!(policy.try_attempt());
+        assert!(policy.try_attempt());
+        assert!(!policy.try_attempt());
+        assert_eq!(policy.attempts(), 2);
+
+        policy.reset();
+        assert!(policy.try_attempt());
+    }
+
+    #[test]
+    fn retries_wait_for_backoff() {
+        let policy = RetryPolicy::<()>::new(4).with_backoff(Duration::from_secs(3600));
+        assert!(policy.try_attempt());
+        assert!(!policy.try_attempt());
+        assert_eq!(policy.attempts(), 1);
+
+        policy.reset();
+        assert!(policy.try_attempt());
+    }
 }
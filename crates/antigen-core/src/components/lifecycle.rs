@@ -0,0 +1,389 @@
+use std::{collections::HashMap, marker::PhantomData, thread::ThreadId};
+
+use legion::{
+    storage::Component,
+    systems::{CommandBuffer, Resources},
+    Entity, IntoQuery, World,
+};
+use parking_lot::Mutex;
+
+use crate::{ImmutableWorld, LazyComponent, ReadWriteLock};
+
+/// Callback run by [`run_lifecycle_hooks`] for an entity whose component changed state
+///
+/// Structural changes recorded into the [`CommandBuffer`] are applied once all hooks have run.
+pub type LifecycleHook = Box<dyn Fn(&World, &mut CommandBuffer, Entity) + Send + Sync>;
+
+/// Observed state of a tracked component
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LifecycleState {
+    Present,
+    Ready,
+    Dropped,
+}
+
+fn lazy_state<C, T>(component: &C) -> LifecycleState
+where
+    C: ReadWriteLock<LazyComponent<T>>,
+{
+    match *component.read() {
+        LazyComponent::Ready(_) => LifecycleState::Ready,
+        LazyComponent::Dropped => LifecycleState::Dropped,
+        _ => LifecycleState::Present,
+    }
+}
+
+/// Set of lifecycle hooks for component type `C`
+///
+/// ```ignore
+/// ComponentHooks::<WindowComponent>::default()
+///     .on_ready(|world, cmd, entity| ...)
+///     .on_drop(drop_window_surface)
+///     .on_remove(drop_window_surface)
+/// ```
+///
+/// Entities that already hold a `C` when the hooks are registered are treated as newly added.
+pub struct ComponentHooks<C> {
+    on_add: Vec<LifecycleHook>,
+    on_remove: Vec<LifecycleHook>,
+    on_ready: Vec<LifecycleHook>,
+    on_drop: Vec<LifecycleHook>,
+    lazy_state: Option<fn(&C) -> LifecycleState>,
+    /// State of each entity's `C`, and the update it was last seen in
+    tracked: HashMap<Entity, (LifecycleState, usize)>,
+    update: usize,
+    _phantom: PhantomData<fn() -> C>,
+}
+
+impl<C> Default for ComponentHooks<C> {
+    fn default() -> Self {
+        ComponentHooks {
+            on_add: Default::default(),
+            on_remove: Default::default(),
+            on_ready: Default::default(),
+            on_drop: Default::default(),
+            lazy_state: None,
+            tracked: Default::default(),
+            update: Default::default(),
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<C> ComponentHooks<C> {
+    /// Run `f` when a `C` is added to an entity
+    pub fn on_add(
+        mut self,
+        f: impl Fn(&World, &mut CommandBuffer, Entity) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_add.push(Box::new(f));
+        self
+    }
+
+    /// Run `f` when a `C` is removed from an entity, or its entity is removed
+    ///
+    /// The component is no longer accessible, though any other components of the entity may be.
+    pub fn on_remove(
+        mut self,
+        f: impl Fn(&World, &mut CommandBuffer, Entity) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_remove.push(Box::new(f));
+        self
+    }
+
+    /// Run `f` when a lazy `C` becomes [`LazyComponent::Ready`]
+    pub fn on_ready<T>(
+        mut self,
+        f: impl Fn(&World, &mut CommandBuffer, Entity) + Send + Sync + 'static,
+    ) -> Self
+    where
+        C: ReadWriteLock<LazyComponent<T>>,
+    {
+        self.lazy_state = Some(lazy_state::<C, T>);
+        self.on_ready.push(Box::new(f));
+        self
+    }
+
+    /// Run `f` when a lazy `C` becomes [`LazyComponent::Dropped`]
+    pub fn on_drop<T>(
+        mut self,
+        f: impl Fn(&World, &mut CommandBuffer, Entity) + Send + Sync + 'static,
+    ) -> Self
+    where
+        C: ReadWriteLock<LazyComponent<T>>,
+    {
+        self.lazy_state = Some(lazy_state::<C, T>);
+        self.on_drop.push(Box::new(f));
+        self
+    }
+}
+
+/// Type-erased [`ComponentHooks`], or other per-component state kept in sync at sync points
+pub(crate) trait LifecycleTracker: Send {
+    /// Compare the world against the previous update, and run hooks for any changes
+    fn update(&mut self, world: &World, cmd: &mut CommandBuffer);
+}
+
+impl<C: Component> LifecycleTracker for ComponentHooks<C> {
+    fn update(&mut self, world: &World, cmd: &mut CommandBuffer) {
+        self.update = self.update.wrapping_add(1);
+        let update = self.update;
+
+        let run = |hooks: &[LifecycleHook], cmd: &mut CommandBuffer, entity: Entity| {
+            for hook in hooks {
+                hook(world, cmd, entity)
+            }
+        };
+
+        for (entity, component) in <(Entity, &C)>::query().iter(world) {
+            let state = self
+                .lazy_state
+                .map(|lazy_state| lazy_state(component))
+                .unwrap_or(LifecycleState::Present);
+
+            let previous = self
+                .tracked
+                .insert(*entity, (state, update))
+                .map(|(state, _)| state);
+
+            if previous.is_none() {
+                run(&self.on_add, cmd, *entity);
+            }
+
+            if previous != Some(state) {
+                match state {
+                    LifecycleState::Ready => run(&self.on_ready, cmd, *entity),
+                    LifecycleState::Dropped => run(&self.on_drop, cmd, *entity),
+                    LifecycleState::Present => (),
+                }
+            }
+        }
+
+        // Anything not seen this update has been removed
+        let on_remove = &self.on_remove;
+        self.tracked.retain(|entity, (_, seen)| {
+            let present = *seen == update;
+            if !present {
+                run(on_remove, cmd, *entity);
+            }
+            present
+        });
+    }
+}
+
+/// Registry of [`ComponentHooks`] and [`Index`](crate::Index) updates, run at sync points by [`run_lifecycle_hooks`]
+///
+/// Changes are detected by comparing the world against the previous sync point,
+/// so a component that changes state and back between sync points will not trigger hooks.
+///
+/// Hooks are pinned to the thread of the first [`run_lifecycle_hooks`] call.
+#[derive(Default)]
+pub struct LifecycleHooks {
+    trackers: Mutex<Vec<Box<dyn LifecycleTracker>>>,
+    thread: Mutex<Option<ThreadId>>,
+}
+
+impl LifecycleHooks {
+    pub fn register<C: Component>(&self, hooks: ComponentHooks<C>) {
+        self.register_tracker(Box::new(hooks))
+    }
+
+    pub(crate) fn register_tracker(&self, tracker: Box<dyn LifecycleTracker>) {
+        self.trackers.lock().push(tracker)
+    }
+
+    /// Pin hooks to the calling thread if unpinned, and panic if pinned to another
+    fn assert_thread(&self) {
+        let current = std::thread::current().id();
+        let pinned = *self.thread.lock().get_or_insert(current);
+        assert!(
+            pinned == current,
+            "Lifecycle hooks must run on a single thread, as they may own thread-local resources"
+        );
+    }
+}
+
+pub fn assemble_lifecycle_hooks(world: &mut World) -> Entity {
+    world.push((LifecycleHooks::default(),))
+}
+
+/// Register `hooks` with the world's [`LifecycleHooks`], assembling it if not present
+pub fn register_component_hooks<C: Component>(world: &mut World, hooks: ComponentHooks<C>) {
+    register_lifecycle_tracker(world, Box::new(hooks))
+}
+
+pub(crate) fn register_lifecycle_tracker(world: &mut World, tracker: Box<dyn LifecycleTracker>) {
+    if <&LifecycleHooks>::query().iter(world).next().is_none() {
+        assemble_lifecycle_hooks(world);
+    }
+
+    <&LifecycleHooks>::query()
+        .iter(world)
+        .next()
+        .unwrap()
+        .register_tracker(tracker)
+}
+
+/// Run hooks for components added, removed, readied or dropped since the previous call,
+/// and bring any [`Index`](crate::Index)es up to date
+///
+/// Hooks run under a world read lock, after which their recorded commands are applied under a write lock.
+///
+/// This is the world's sync point, and should be called once per frame from the thread
+/// that owns any thread-local resources hooks may touch, ex. the event loop thread for windows and surfaces.
+/// Calling it from any other thread will panic.
+pub fn run_lifecycle_hooks(world: &ImmutableWorld) {
+    let mut command_buffer = {
+        let world_read = world.read();
+        let hooks = if let Some(hooks) = <&LifecycleHooks>::query().iter(&*world_read).next() {
+            hooks
+        } else {
+            return;
+        };
+
+        hooks.assert_thread();
+
+        let mut command_buffer = CommandBuffer::new(&world_read);
+        for tracker in hooks.trackers.lock().iter_mut() {
+            tracker.update(&world_read, &mut command_buffer);
+        }
+        command_buffer
+    };
+
+    if command_buffer.is_empty() {
+        return;
+    }
+
+    command_buffer.flush(&mut world.write(), &mut Resources::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::RwLock;
+    use legion::EntityStore;
+
+    type LazyString = RwLock<LazyComponent<String>>;
+
+    type Log = Arc<Mutex<Vec<(&'static str, Entity)>>>;
+
+    fn logging_hooks(log: &Log) -> ComponentHooks<LazyString> {
+        let hook = |name: &'static str| {
+            let log = log.clone();
+            move |_: &World, _: &mut CommandBuffer, entity: Entity| log.lock().push((name, entity))
+        };
+
+        ComponentHooks::<LazyString>::default()
+            .on_add(hook("add"))
+            .on_remove(hook("remove"))
+            .on_ready(hook("ready"))
+            .on_drop(hook("drop"))
+    }
+
+    fn set_state(
+        world: &ImmutableWorld,
+        entity: Entity,
+        f: impl FnOnce(&mut LazyComponent<String>),
+    ) {
+        let world = world.read();
+        let entry = world.entry_ref(entity).unwrap();
+        f(&mut entry.get_component::<LazyString>().unwrap().write());
+    }
+
+    #[test]
+    fn detects_add_ready_drop_and_remove() {
+        let world = ImmutableWorld::default();
+        let log = Log::default();
+        register_component_hooks(&mut world.write(), logging_hooks(&log));
+
+        let entity = world
+            .write()
+            .push((LazyString::new(LazyComponent::Pending),));
+        run_lifecycle_hooks(&world);
+        assert_eq!(*log.lock(), vec![("add", entity)]);
+
+        // No changes, no hooks
+        run_lifecycle_hooks(&world);
+        assert_eq!(log.lock().len(), 1);
+
+        set_state(&world, entity, |lazy| lazy.set_ready("ready".to_string()));
+        run_lifecycle_hooks(&world);
+        assert_eq!(log.lock()[1..], [("ready", entity)]);
+
+        set_state(&world, entity, LazyComponent::set_dropped);
+        run_lifecycle_hooks(&world);
+        assert_eq!(log.lock()[2..], [("drop", entity)]);
+
+        world.write().remove(entity);
+        run_lifecycle_hooks(&world);
+        assert_eq!(log.lock()[3..], [("remove", entity)]);
+    }
+
+    #[test]
+    fn detects_entities_present_at_registration_and_ready_on_add() {
+        let world = ImmutableWorld::default();
+        let entity = world
+            .write()
+            .push((LazyString::new(LazyComponent::Ready("ready".to_string())),));
+
+        let log = Log::default();
+        register_component_hooks(&mut world.write(), logging_hooks(&log));
+
+        run_lifecycle_hooks(&world);
+        assert_eq!(*log.lock(), vec![("add", entity), ("ready", entity)]);
+    }
+
+    #[test]
+    fn detects_component_removal_from_surviving_entity() {
+        let world = ImmutableWorld::default();
+        let log = Log::default();
+        register_component_hooks(&mut world.write(), logging_hooks(&log));
+
+        let entity = world
+            .write()
+            .push((LazyString::new(LazyComponent::Pending), 0u32));
+        run_lifecycle_hooks(&world);
+
+        world
+            .write()
+            .entry(entity)
+            .unwrap()
+            .remove_component::<LazyString>();
+        run_lifecycle_hooks(&world);
+
+        assert_eq!(*log.lock(), vec![("add", entity), ("remove", entity)]);
+    }
+
+    #[test]
+    fn applies_hook_commands() {
+        let world = ImmutableWorld::default();
+        register_component_hooks(
+            &mut world.write(),
+            ComponentHooks::<LazyString>::default().on_add(|_, cmd, entity| {
+                cmd.add_component(entity, 1u32);
+            }),
+        );
+
+        let entity = world
+            .write()
+            .push((LazyString::new(LazyComponent::Pending),));
+        run_lifecycle_hooks(&world);
+
+        let world = world.read();
+        let entry = world.entry_ref(entity).unwrap();
+        assert_eq!(*entry.get_component::<u32>().unwrap(), 1);
+    }
+
+    #[test]
+    fn panics_when_run_from_another_thread() {
+        let world = ImmutableWorld::default();
+        assemble_lifecycle_hooks(&mut world.write());
+        run_lifecycle_hooks(&world);
+
+        let other = world.clone();
+        let result = std::thread::spawn(move || run_lifecycle_hooks(&other)).join();
+        assert!(result.is_err());
+    }
+}
@@ -1,11 +1,25 @@
 mod changed;
+mod events;
+mod hierarchy;
+mod index;
 mod indirect_component;
 mod lazy_component;
+mod lifecycle;
+mod name;
 mod usage;
 mod args;
+mod time;
+mod world_command_queue;
 
 pub use changed::*;
+pub use events::*;
+pub use hierarchy::*;
+pub use index::*;
 pub use indirect_component::*;
 pub use lazy_component::*;
+pub use lifecycle::*;
+pub use name::*;
 pub use usage::*;
 pub use args::*;
+pub use time::*;
+pub use world_command_queue::*;
@@ -0,0 +1,295 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use legion::{query::component, systems::CommandBuffer, Entity, EntityStore, IntoQuery, World};
+use parking_lot::{const_rwlock, RwLock};
+
+use super::lifecycle::{register_lifecycle_tracker, LifecycleTracker};
+use crate::{
+    ancestors, assemble_changed_index, children_of, Changed, ChangedTrait, Construct,
+    ImmutableWorld, Index, ParentComponent, ReadWriteLock, Usage,
+};
+
+pub enum Name {}
+
+/// Human-readable entity name, shown by [`DebugEntity`] and used for name and path lookup
+///
+/// Names needn't be unique, though only unique names can be reliably looked up.
+/// Set the changed flag after renaming, so that the [`NameIndex`] and [`DebugEntity`] pick it up.
+pub type NameComponent = Usage<Name, Changed<RwLock<String>>>;
+
+/// Name -> entity lookup, inserted into the world's resources by [`assemble_names`]
+pub type NameIndex = Index<String, NameComponent>;
+
+/// Names of every entity in any world with a [`NameIndex`], for use in [`Debug`] output
+///
+/// Process-wide so that [`DebugEntity`] works wherever an entity is formatted, without access to its world.
+/// Formatting takes a read lock, and a world's sync point only takes the write lock when its names have changed.
+/// Each world's entries are removed when it is dropped.
+///
+/// Entities are keyed by ID alone, which relies on legion's default allocator handing out IDs that are unique
+/// across worlds. Worlds created with a custom entity allocator via `WorldOptions` may reuse IDs,
+/// in which case their names will overwrite, or be removed along with, those of another world.
+static DEBUG_NAMES: RwLock<Option<HashMap<Entity, String>>> = const_rwlock(None);
+
+pub fn assemble_name(cmd: &mut CommandBuffer, entity: Entity, name: impl Into<String>) {
+    cmd.add_component(entity, NameComponent::construct(name.into()));
+}
+
+/// Insert a [`NameIndex`] into the world's resources, and register names for [`DebugEntity`] output
+pub fn assemble_names(world: &ImmutableWorld) {
+    register_lifecycle_tracker(&mut world.write(), Box::new(DebugNameTracker::default()));
+    assemble_changed_index(world, |name: &NameComponent| Some(name.read().clone()))
+}
+
+/// Mirrors a world's names into [`DEBUG_NAMES`] at each sync point,
+/// removing them again when the world is dropped
+#[derive(Default)]
+struct DebugNameTracker {
+    /// Generation of each entity's name as last mirrored, and the update it was last seen in
+    tracked: HashMap<Entity, (usize, usize)>,
+    update: usize,
+}
+
+impl LifecycleTracker for DebugNameTracker {
+    fn update(&mut self, world: &World, _: &mut CommandBuffer) {
+        self.update = self.update.wrapping_add(1);
+        let update = self.update;
+
+        let mut changed = vec![];
+        for (entity, name) in <(Entity, &NameComponent)>::query().iter(world) {
+            let generation = name.generation();
+            let previous = self.tracked.insert(*entity, (generation, update));
+            if previous.map(|(generation, _)| generation) != Some(generation) {
+                changed.push((*entity, name.read().clone()));
+            }
+        }
+
+        let mut removed = vec![];
+        self.tracked.retain(|entity, (_, seen)| {
+            let present = *seen == update;
+            if !present {
+                removed.push(*entity);
+            }
+            present
+        });
+
+        if changed.is_empty() && removed.is_empty() {
+            return;
+        }
+
+        let mut names = DEBUG_NAMES.write();
+        let names = names.get_or_insert_with(Default::default);
+        for entity in removed {
+            names.remove(&entity);
+        }
+        names.extend(changed);
+    }
+}
+
+impl Drop for DebugNameTracker {
+    fn drop(&mut self) {
+        if self.tracked.is_empty() {
+            return;
+        }
+
+        let mut names = DEBUG_NAMES.write();
+        let names = names.get_or_insert_with(Default::default);
+        for entity in self.tracked.keys() {
+            names.remove(entity);
+        }
+    }
+}
+
+/// [`Debug`] adapter that shows an entity's name alongside its ID
+///
+/// ex. `tracing::warn!("{:?} has no mesh", DebugEntity(entity))` logs `"renderer" Entity(3) has no mesh`.
+/// Names are picked up at sync points once [`assemble_names`] has been called,
+/// from a process-wide table keyed by entity ID. Worlds with a custom entity allocator may reuse IDs,
+/// in which case one world's names can show up for another's entities.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct DebugEntity(pub Entity);
+
+impl Debug for DebugEntity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = DEBUG_NAMES.read();
+        match names.as_ref().and_then(|names| names.get(&self.0)) {
+            Some(name) => write!(f, "{:?} {:?}", name, self.0),
+            None => write!(f, "{:?}", self.0),
+        }
+    }
+}
+
+/// Return the name of `entity`, if any
+pub fn name_of<W: EntityStore>(world: &W, entity: Entity) -> Option<String> {
+    <&NameComponent>::query()
+        .get(world, entity)
+        .ok()
+        .map(|name| name.read().clone())
+}
+
+/// Return an entity named `name`
+///
+/// Scans every named entity; repeated lookups should use the [`NameIndex`] resource instead.
+pub fn find_by_name<W: EntityStore>(world: &W, name: &str) -> Option<Entity> {
+    <(Entity, &NameComponent)>::query()
+        .iter(world)
+        .find(|(_, candidate)| *candidate.read() == name)
+        .map(|(entity, _)| *entity)
+}
+
+/// Return the `/`-separated names of `entity` and its ancestors, root first, ex. `"renderer/mesh"`
+///
+/// Unnamed entities are written as their ID.
+pub fn path_of<W: EntityStore>(world: &W, entity: Entity) -> String {
+    let mut path = ancestors(world, entity);
+    path.reverse();
+    path.push(entity);
+
+    path.into_iter()
+        .map(|entity| name_of(world, entity).unwrap_or_else(|| format!("{:?}", entity)))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Return the entity at a `/`-separated path of names, starting from an unparented entity
+pub fn find_by_path<W: EntityStore>(world: &W, path: &str) -> Option<Entity> {
+    let mut segments = path.split('/');
+
+    let root = segments.next()?;
+    let mut current = <(Entity, &NameComponent)>::query()
+        .filter(!component::<ParentComponent>())
+        .iter(world)
+        .find(|(_, name)| *name.read() == root)
+        .map(|(entity, _)| *entity)?;
+
+    for segment in segments {
+        current = children_of(world, current)
+            .into_iter()
+            .find(|child| name_of(world, *child).as_deref() == Some(segment))?;
+    }
+
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_lifecycle_hooks, sync_children_system};
+    use legion::{Resources, Schedule};
+
+    fn sync(world: &ImmutableWorld) {
+        Schedule::builder()
+            .add_system(sync_children_system())
+            .build()
+            .execute(&mut world.write(), &mut Resources::default());
+        run_lifecycle_hooks(world);
+    }
+
+    fn rename(world: &ImmutableWorld, entity: Entity, name: &str) {
+        let world = world.read();
+        let entry = world.entry_ref(entity).unwrap();
+        let component = entry.get_component::<NameComponent>().unwrap();
+        *component.write() = name.to_string();
+        component.set_changed(true);
+    }
+
+    /// Named `renderer` parenting a `mesh`, which parents an unnamed entity
+    fn tree(world: &ImmutableWorld) -> [Entity; 3] {
+        let mut world = world.write();
+        let renderer = world.push((NameComponent::construct("renderer".to_string()),));
+        let mesh = world.push((
+            NameComponent::construct("mesh".to_string()),
+            ParentComponent::new(renderer),
+        ));
+        let unnamed = world.push((ParentComponent::new(mesh),));
+        [renderer, mesh, unnamed]
+    }
+
+    #[test]
+    fn path_of_joins_ancestor_names() {
+        let world = ImmutableWorld::default();
+        let [renderer, mesh, unnamed] = tree(&world);
+        sync(&world);
+
+        let world = world.read();
+        assert_eq!(path_of(&*world, renderer), "renderer");
+        assert_eq!(path_of(&*world, mesh), "renderer/mesh");
+        assert_eq!(
+            path_of(&*world, unnamed),
+            format!("renderer/mesh/{:?}", unnamed)
+        );
+    }
+
+    #[test]
+    fn find_by_path_walks_children_from_a_root() {
+        let world = ImmutableWorld::default();
+        let [renderer, mesh, _] = tree(&world);
+        sync(&world);
+
+        let world = world.read();
+        assert_eq!(find_by_path(&*world, "renderer"), Some(renderer));
+        assert_eq!(find_by_path(&*world, "renderer/mesh"), Some(mesh));
+        assert_eq!(find_by_path(&*world, "renderer/missing"), None);
+
+        // Parented entities aren't roots
+        assert_eq!(find_by_path(&*world, "mesh"), None);
+    }
+
+    #[test]
+    fn renames_update_index_and_debug_names() {
+        let world = ImmutableWorld::default();
+        assemble_names(&world);
+
+        let entity = world
+            .write()
+            .push((NameComponent::construct("before".to_string()),));
+        sync(&world);
+
+        let index = world.resources().get::<NameIndex>().unwrap();
+        assert_eq!(index.get("before"), Some(entity));
+        assert_eq!(
+            format!("{:?}", DebugEntity(entity)),
+            format!("\"before\" {:?}", entity)
+        );
+
+        rename(&world, entity, "after");
+        sync(&world);
+
+        assert_eq!(index.get("before"), None);
+        assert_eq!(index.get("after"), Some(entity));
+        assert_eq!(
+            format!("{:?}", DebugEntity(entity)),
+            format!("\"after\" {:?}", entity)
+        );
+
+        world.write().remove(entity);
+        sync(&world);
+
+        assert!(index.is_empty());
+        assert_eq!(
+            format!("{:?}", DebugEntity(entity)),
+            format!("{:?}", entity)
+        );
+    }
+
+    #[test]
+    fn dropping_world_clears_debug_names() {
+        let world = ImmutableWorld::default();
+        assemble_names(&world);
+
+        let entity = world
+            .write()
+            .push((NameComponent::construct("dropped".to_string()),));
+        sync(&world);
+        assert_ne!(
+            format!("{:?}", DebugEntity(entity)),
+            format!("{:?}", entity)
+        );
+
+        drop(world);
+        assert_eq!(
+            format!("{:?}", DebugEntity(entity)),
+            format!("{:?}", entity)
+        );
+    }
+}
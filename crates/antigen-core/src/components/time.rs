@@ -0,0 +1,521 @@
+use std::time::{Duration, Instant};
+
+use legion::{systems::CommandBuffer, world::SubWorld, Entity, IntoQuery, World};
+use parking_lot::Mutex;
+
+use crate::{
+    assemble_world_command_queue, App, Changed, ChangedTrait, Construct, IntoScheduledSystem,
+    Plugin, ReadWriteLock, RwLock, Stage, Usage, WorldCommandQueue,
+};
+
+/// Wall-clock time, unaffected by pause or time scale
+pub enum RealTime {}
+
+/// Simulation time, advanced once per event loop update and subject to pause and time scale
+pub enum GameTime {}
+
+/// Simulation time, advanced by a fixed timestep once per game thread tick
+///
+/// Follows the pause state and time scale of the [`GameTime`] clock.
+pub enum FixedTime {}
+
+/// Accumulated elapsed time, and the delta applied by its most recent update
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Clock {
+    elapsed: Duration,
+    delta: Duration,
+    paused: bool,
+    scale: f32,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+            paused: false,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Clock {
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Multiplier applied to time passed to [`Clock::advance`]
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.0)
+    }
+
+    /// Advance by `delta` scaled by the clock's time scale, or not at all if paused
+    pub fn advance(&mut self, delta: Duration) {
+        self.delta = if self.paused {
+            Duration::ZERO
+        } else {
+            delta.mul_f32(self.scale)
+        };
+        self.elapsed += self.delta;
+    }
+}
+
+/// Singleton clock, with its changed flag set on every update
+pub type ClockComponent<U> = Usage<U, Changed<RwLock<Clock>>>;
+
+pub type RealClockComponent = ClockComponent<RealTime>;
+pub type GameClockComponent = ClockComponent<GameTime>;
+pub type FixedClockComponent = ClockComponent<FixedTime>;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimerMode {
+    /// Finish once, then stop ticking until reset
+    Once,
+    /// Finish every `duration`, carrying over any excess
+    Repeating,
+}
+
+/// Countdown driven by a [`Clock`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timer {
+    duration: Duration,
+    elapsed: Duration,
+    mode: TimerMode,
+    /// Whether a one-shot timer has reported finishing, which a zero duration has yet to on creation
+    finished: bool,
+    times_finished: u32,
+}
+
+impl Timer {
+    pub fn new(duration: Duration, mode: TimerMode) -> Self {
+        Timer {
+            duration,
+            elapsed: Duration::ZERO,
+            mode,
+            finished: false,
+            times_finished: 0,
+        }
+    }
+
+    pub fn once(duration: Duration) -> Self {
+        Timer::new(duration, TimerMode::Once)
+    }
+
+    pub fn repeating(duration: Duration) -> Self {
+        Timer::new(duration, TimerMode::Repeating)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Progress through the current period, in the range `0.0..=1.0`
+    pub fn fraction(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Whether a one-shot timer has run to completion
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Number of times the timer finished during the most recent [`Timer::tick`]
+    pub fn times_finished(&self) -> u32 {
+        self.times_finished
+    }
+
+    pub fn just_finished(&self) -> bool {
+        self.times_finished > 0
+    }
+
+    /// Advance by `delta`, returning the number of times the timer finished
+    pub fn tick(&mut self, delta: Duration) -> u32 {
+        self.times_finished = match self.mode {
+            TimerMode::Once => {
+                if self.finished {
+                    0
+                } else {
+                    self.elapsed = (self.elapsed + delta).min(self.duration);
+                    self.finished = self.elapsed >= self.duration;
+                    self.finished as u32
+                }
+            }
+            TimerMode::Repeating => {
+                self.elapsed += delta;
+                if self.duration.is_zero() {
+                    self.elapsed = Duration::ZERO;
+                    1
+                } else {
+                    let periods = self.elapsed.as_nanos() / self.duration.as_nanos();
+                    self.elapsed = Duration::from_nanos(
+                        (self.elapsed.as_nanos() % self.duration.as_nanos()) as u64,
+                    );
+                    periods as u32
+                }
+            }
+        };
+        self.times_finished
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+        self.times_finished = 0;
+    }
+}
+
+/// Timer driven by the `U` clock
+///
+/// [`update_timers`](update_timers_system) advances the changed generation whenever the timer
+/// finishes, and leaves the changed flag set only until the next update.
+/// Consumers observe finishes via their own [`ChangeTracker`](crate::ChangeTracker)
+/// rather than resetting the flag, so any number of them can act on the same timer:
+///
+/// ```ignore
+/// if tracker.observe(*entity, timer) {
+///     let times_finished = timer.read().times_finished();
+///     ...
+/// }
+/// ```
+pub type TimerComponent<U> = Usage<U, Changed<RwLock<Timer>>>;
+
+type Callback = Box<dyn FnOnce(&mut CommandBuffer) + Send>;
+
+/// Closures queued to run once their clock has advanced by a given delay
+///
+/// Due closures are recorded into the world's [`WorldCommandQueue`],
+/// and so take effect at its next flush.
+///
+/// ```ignore
+/// callbacks.after(Duration::from_secs(2), move |cmd| cmd.remove(entity));
+/// ```
+#[derive(Default)]
+pub struct ScheduledCallbacks(Mutex<Vec<(Duration, Callback)>>);
+
+impl ScheduledCallbacks {
+    /// Run `f` once `delay` has elapsed
+    pub fn after(&self, delay: Duration, f: impl FnOnce(&mut CommandBuffer) + Send + 'static) {
+        self.0.lock().push((delay, Box::new(f)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().is_empty()
+    }
+
+    /// Advance every pending delay by `delta`, returning the closures that are now due
+    fn take_due(&self, delta: Duration) -> Vec<Callback> {
+        let mut callbacks = self.0.lock();
+        let mut due = vec![];
+        let mut i = 0;
+        while i < callbacks.len() {
+            let remaining = &mut callbacks[i].0;
+            *remaining = remaining.saturating_sub(delta);
+            if remaining.is_zero() {
+                due.push(callbacks.remove(i).1);
+            } else {
+                i += 1;
+            }
+        }
+        due
+    }
+}
+
+pub type ScheduledCallbacksComponent<U> = Usage<U, ScheduledCallbacks>;
+
+/// Create the singleton entity holding the real, game and fixed clocks and their scheduled callbacks
+pub fn assemble_time(world: &mut World) -> Entity {
+    world.push((
+        RealClockComponent::construct(Clock::default()),
+        GameClockComponent::construct(Clock::default()),
+        FixedClockComponent::construct(Clock::default()),
+        ScheduledCallbacksComponent::<RealTime>::construct(ScheduledCallbacks::default()),
+        ScheduledCallbacksComponent::<GameTime>::construct(ScheduledCallbacks::default()),
+        ScheduledCallbacksComponent::<FixedTime>::construct(ScheduledCallbacks::default()),
+    ))
+}
+
+/// Advance the real and game clocks by the wall-clock time since the previous run
+#[legion::system(for_each)]
+pub fn update_clocks(
+    #[state] last_update: &mut Option<Instant>,
+    real_clock: &RealClockComponent,
+    game_clock: &GameClockComponent,
+) {
+    let now = Instant::now();
+    let delta = last_update.map(|last| now - last).unwrap_or_default();
+    *last_update = Some(now);
+
+    real_clock.write().advance(delta);
+    real_clock.set_changed(true);
+
+    game_clock.write().advance(delta);
+    game_clock.set_changed(true);
+}
+
+/// Advance the fixed clock by `timestep`, following the game clock's pause state and time scale
+#[legion::system(for_each)]
+pub fn update_fixed_clock(
+    #[state] timestep: &Duration,
+    fixed_clock: &FixedClockComponent,
+    game_clock: &GameClockComponent,
+) {
+    let (paused, scale) = {
+        let game_clock = game_clock.read();
+        (game_clock.is_paused(), game_clock.scale())
+    };
+
+    let mut fixed = fixed_clock.write();
+    if paused {
+        fixed.pause();
+    } else {
+        fixed.resume();
+    }
+    fixed.set_scale(scale);
+    fixed.advance(*timestep);
+    drop(fixed);
+
+    fixed_clock.set_changed(true);
+}
+
+/// Tick every [`TimerComponent<U>`] by the delta of the `U` clock
+#[legion::system]
+#[read_component(ClockComponent<U>)]
+#[read_component(TimerComponent<U>)]
+pub fn update_timers<U: Send + Sync + 'static>(world: &SubWorld) {
+    let delta = if let Some(clock) = <&ClockComponent<U>>::query().iter(world).next() {
+        clock.read().delta()
+    } else {
+        return;
+    };
+
+    <&TimerComponent<U>>::query().par_for_each(world, |timer| {
+        let finished = timer.write().tick(delta) > 0;
+        timer.set_changed(finished);
+    });
+}
+
+/// Queue due [`ScheduledCallbacksComponent<U>`] closures into the world's [`WorldCommandQueue`]
+#[legion::system]
+#[read_component(ClockComponent<U>)]
+#[read_component(ScheduledCallbacksComponent<U>)]
+#[read_component(WorldCommandQueue)]
+pub fn run_scheduled_callbacks<U: Send + Sync + 'static>(world: &SubWorld) {
+    let queue = if let Some(queue) = <&WorldCommandQueue>::query().iter(world).next() {
+        queue
+    } else {
+        return;
+    };
+
+    for (clock, callbacks) in
+        <(&ClockComponent<U>, &ScheduledCallbacksComponent<U>)>::query().iter(world)
+    {
+        for callback in callbacks.take_due(clock.read().delta()) {
+            queue.record(callback);
+        }
+    }
+}
+
+/// Assembles the time singleton, and keeps its clocks, timers and scheduled callbacks up to date
+///
+/// Also assembles a [`WorldCommandQueue`] for scheduled callbacks to record into, if the world has none.
+///
+/// Real and game time advance at the start of each event loop update,
+/// and fixed time advances by `timestep` at the start of each game thread tick.
+pub struct TimePlugin {
+    pub timestep: Duration,
+}
+
+impl<E> Plugin<E> for TimePlugin {
+    fn build(&self, app: App<E>) -> App<E> {
+        app.add_assembler(|world| {
+            assemble_time(world);
+
+            if <&WorldCommandQueue>::query().iter(world).next().is_none() {
+                assemble_world_command_queue(world);
+            }
+        })
+        .add_system(
+            Stage::PreUpdate,
+            update_clocks_system(None).label("update_clocks"),
+        )
+        .add_system(
+            Stage::PreUpdate,
+            update_timers_system::<RealTime>().after("update_clocks"),
+        )
+        .add_system(
+            Stage::PreUpdate,
+            update_timers_system::<GameTime>().after("update_clocks"),
+        )
+        .add_system(
+            Stage::PreUpdate,
+            run_scheduled_callbacks_system::<RealTime>().after("update_clocks"),
+        )
+        .add_system(
+            Stage::PreUpdate,
+            run_scheduled_callbacks_system::<GameTime>().after("update_clocks"),
+        )
+        .add_system(
+            Stage::Tick,
+            update_fixed_clock_system(self.timestep).label("update_fixed_clock"),
+        )
+        .add_system(
+            Stage::Tick,
+            update_timers_system::<FixedTime>().after("update_fixed_clock"),
+        )
+        .add_system(
+            Stage::Tick,
+            run_scheduled_callbacks_system::<FixedTime>().after("update_fixed_clock"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{flush_world_command_queues, AppSchedules, ImmutableWorld};
+
+    fn secs(secs: f32) -> Duration {
+        Duration::from_secs_f32(secs)
+    }
+
+    #[test]
+    fn clock_advance_applies_pause_and_scale() {
+        let mut clock = Clock::default();
+        clock.advance(secs(1.0));
+        assert_eq!(clock.delta(), secs(1.0));
+
+        clock.set_scale(0.5);
+        clock.advance(secs(1.0));
+        assert_eq!(clock.delta(), secs(0.5));
+        assert_eq!(clock.elapsed(), secs(1.5));
+
+        clock.pause();
+        clock.advance(secs(1.0));
+        assert_eq!(clock.delta(), Duration::ZERO);
+        assert_eq!(clock.elapsed(), secs(1.5));
+
+        clock.resume();
+        clock.set_scale(-1.0);
+        clock.advance(secs(1.0));
+        assert_eq!(clock.delta(), Duration::ZERO);
+    }
+
+    #[test]
+    fn once_timer_finishes_once() {
+        let mut timer = Timer::once(secs(1.0));
+        assert_eq!(timer.tick(secs(0.5)), 0);
+        assert!(!timer.is_finished());
+
+        assert_eq!(timer.tick(secs(1.0)), 1);
+        assert!(timer.is_finished());
+        assert_eq!(timer.elapsed(), secs(1.0));
+
+        assert_eq!(timer.tick(secs(1.0)), 0);
+        assert!(!timer.just_finished());
+
+        timer.reset();
+        assert!(!timer.is_finished());
+        assert_eq!(timer.tick(secs(1.0)), 1);
+    }
+
+    #[test]
+    fn zero_duration_once_timer_finishes_on_first_tick() {
+        let mut timer = Timer::once(Duration::ZERO);
+        assert!(!timer.is_finished());
+
+        assert_eq!(timer.tick(Duration::ZERO), 1);
+        assert!(timer.is_finished());
+        assert_eq!(timer.tick(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn repeating_timer_carries_over_excess() {
+        let mut timer = Timer::repeating(secs(1.0));
+        assert_eq!(timer.tick(secs(2.5)), 2);
+        assert_eq!(timer.elapsed(), secs(0.5));
+
+        assert_eq!(timer.tick(secs(0.25)), 0);
+        assert!(!timer.just_finished());
+
+        assert_eq!(timer.tick(secs(0.25)), 1);
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+        assert!(!timer.is_finished());
+    }
+
+    #[test]
+    fn plugin_runs_scheduled_callbacks_on_a_bare_world() {
+        let world = ImmutableWorld::default();
+        let AppSchedules {
+            mut event_loop_schedules,
+            ..
+        } = App::<()>::new(&world)
+            .add_plugin(TimePlugin {
+                timestep: secs(1.0),
+            })
+            .build(&world)
+            .unwrap();
+
+        <&ScheduledCallbacksComponent<GameTime>>::query()
+            .iter(&*world.read())
+            .next()
+            .unwrap()
+            .after(Duration::ZERO, |cmd| {
+                cmd.push((1u32,));
+            });
+
+        event_loop_schedules.execute_stage(Stage::PreUpdate, &world);
+        flush_world_command_queues(&world);
+
+        assert_eq!(<&u32>::query().iter(&*world.read()).count(), 1);
+    }
+
+    #[test]
+    fn take_due_returns_callbacks_once_delay_elapses() {
+        let callbacks = ScheduledCallbacks::default();
+        callbacks.after(secs(1.0), |_| ());
+        callbacks.after(secs(2.0), |_| ());
+        callbacks.after(Duration::ZERO, |_| ());
+
+        assert_eq!(callbacks.take_due(Duration::ZERO).len(), 1);
+        assert_eq!(callbacks.take_due(secs(0.5)).len(), 0);
+        assert_eq!(callbacks.take_due(secs(0.5)).len(), 1);
+        assert!(!callbacks.is_empty());
+
+        assert_eq!(callbacks.take_due(secs(5.0)).len(), 1);
+        assert!(callbacks.is_empty());
+    }
+}
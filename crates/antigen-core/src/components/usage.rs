@@ -4,7 +4,7 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use crate::ReadWriteLock;
+use crate::{Construct, ReadWriteLock, With};
 
 /// Wrapper type for creating several distinct types out of the same underlying type
 ///
@@ -23,8 +23,22 @@ use crate::ReadWriteLock;
 /// type SurfaceSizeComponent = Usage<SurfaceSize, SizeComponent>;
 /// type TextureSizeComponent = Usage<TextureSize, SizeComponent>;
 /// ```
-#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    ReadWriteLock,
+    Construct,
+    With,
+)]
 pub struct Usage<U, T> {
+    #[antigen(inner)]
     pub data: T,
     _phantom: PhantomData<U>,
 }
@@ -68,17 +82,40 @@ impl<U, T> DerefMut for Usage<U, T> {
     }
 }
 
-// ReadWriteLock implementation
-impl<U, T, V> ReadWriteLock<V> for Usage<U, T>
+// Serde implementation, transparent over the inner type
+#[cfg(feature = "serialize")]
+impl<U, T: serde::Serialize> serde::Serialize for Usage<U, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de, U, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Usage<U, T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Usage::from)
+    }
+}
+
+// ChangedTrait implementation
+impl<U, T> crate::ChangedTrait for Usage<U, T>
 where
-    T: ReadWriteLock<V>,
+    T: crate::ChangedTrait,
 {
-    fn read(&self) -> parking_lot::RwLockReadGuard<V> {
-        self.data.read()
+    fn get_changed(&self) -> bool {
+        self.data.get_changed()
     }
 
-    fn write(&self) -> parking_lot::RwLockWriteGuard<V> {
-        self.data.write()
+    fn set_changed(&self, dirty: bool) {
+        self.data.set_changed(dirty)
+    }
+
+    fn generation(&self) -> usize {
+        self.data.generation()
+    }
+
+    fn liveness(&self) -> std::sync::Weak<()> {
+        self.data.liveness()
     }
 }
 
@@ -94,37 +131,29 @@ pub trait AsUsage: Sized {
 
 impl<T> AsUsage for T {}
 
-/// Construct implementation
-impl<U, T> crate::Construct<T, crate::peano::Z> for Usage<U, T> {
-    fn construct(t: T) -> Self {
-        Usage {
-            data: t,
-            _phantom: Default::default(),
-        }
-    }
-}
-
-impl<T, I, U, N> crate::Construct<T, crate::peano::S<I>> for Usage<U, N>
+/// Project and Lens implementations
+impl<T, I, U, N> crate::Project<T, crate::peano::S<I>> for Usage<U, N>
 where
-    N: crate::Construct<T, I>,
+    N: crate::Project<T, I>,
 {
-    fn construct(t: T) -> Self {
-        Usage {
-            data: N::construct(t),
-            _phantom: Default::default(),
-        }
+    fn project(&self) -> Option<&T> {
+        self.data.project()
+    }
+
+    fn project_mut(&mut self) -> Option<&mut T> {
+        self.data.project_mut()
     }
 }
 
-/// With implementation
-impl<T, I, U, N> crate::With<T, crate::peano::S<I>> for Usage<U, N>
+impl<T, I, U, N> crate::Lens<T, crate::peano::S<I>> for Usage<U, N>
 where
-    N: crate::With<T, I>,
+    N: crate::Lens<T, I>,
 {
-    fn with(self, t: T) -> Self {
-        Usage {
-            data: self.data.with(t),
-            ..self
-        }
+    fn lens_read(&self) -> Option<crate::MappedRwLockReadGuard<T>> {
+        self.data.lens_read()
+    }
+
+    fn lens_write(&self) -> Option<crate::MappedRwLockWriteGuard<T>> {
+        self.data.lens_write()
     }
 }
@@ -0,0 +1,185 @@
+use legion::{
+    storage::Component,
+    systems::{CommandBuffer, Resources},
+    Entity, IntoQuery, World,
+};
+use parking_lot::Mutex;
+
+use crate::{ImmutableWorld, ReadWriteLock};
+
+/// Thread-safe queue of structural world changes
+///
+/// Systems on any thread can enqueue entity creation and removal,
+/// or component insertion and removal, through a shared reference.
+///
+/// Entity IDs are reserved at enqueue time, so callers can wire up references
+/// to new entities immediately. The queued commands are applied at a designated
+/// sync point by [`flush_world_command_queues`], which holds the world write lock
+/// only for as long as it takes to apply them.
+pub struct WorldCommandQueue(Mutex<CommandBuffer>);
+
+impl WorldCommandQueue {
+    pub fn new(world: &World) -> Self {
+        WorldCommandQueue(Mutex::new(CommandBuffer::new(world)))
+    }
+
+    /// Record arbitrary commands against the underlying [`CommandBuffer`]
+    pub fn record<R>(&self, f: impl FnOnce(&mut CommandBuffer) -> R) -> R {
+        f(&mut self.0.lock())
+    }
+
+    /// Reserve a new, empty entity
+    pub fn spawn(&self) -> Entity {
+        self.record(|cmd| cmd.push(()))
+    }
+
+    /// Remove an entity and all of its components
+    pub fn despawn(&self, entity: Entity) {
+        self.record(|cmd| cmd.remove(entity))
+    }
+
+    pub fn add_component<C: Component>(&self, entity: Entity, component: C) {
+        self.record(|cmd| cmd.add_component(entity, component))
+    }
+
+    pub fn remove_component<C: Component>(&self, entity: Entity) {
+        self.record(|cmd| cmd.remove_component::<C>(entity))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().is_empty()
+    }
+
+    /// Take the queued commands, leaving an empty buffer in their place
+    fn take(&self, world: &World) -> CommandBuffer {
+        std::mem::replace(&mut *self.0.lock(), CommandBuffer::new(world))
+    }
+}
+
+pub fn assemble_world_command_queue(world: &mut World) -> Entity {
+    let queue = WorldCommandQueue::new(world);
+    world.push((queue,))
+}
+
+/// Apply the queued commands of every [`WorldCommandQueue`] in the world
+///
+/// Queues are drained under a read lock, then applied under a single write lock.
+pub fn flush_world_command_queues(world: &ImmutableWorld) {
+    let mut command_buffers = {
+        let world_read = world.read();
+        <&WorldCommandQueue>::query()
+            .iter(&*world_read)
+            .filter(|queue| !queue.is_empty())
+            .map(|queue| queue.take(&world_read))
+            .collect::<Vec<_>>()
+    };
+
+    if command_buffers.is_empty() {
+        return;
+    }
+
+    let mut world_write = world.write();
+    let mut resources = Resources::default();
+    for command_buffer in &mut command_buffers {
+        command_buffer.flush(&mut world_write, &mut resources);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use legion::EntityStore;
+
+    use super::*;
+
+    fn queue_world() -> (ImmutableWorld, Entity) {
+        let world = ImmutableWorld::default();
+        let queue = assemble_world_command_queue(&mut world.write());
+        (world, queue)
+    }
+
+    fn with_queue<R>(
+        world: &ImmutableWorld,
+        queue: Entity,
+        f: impl FnOnce(&WorldCommandQueue) -> R,
+    ) -> R {
+        let world = world.read();
+        f(<&WorldCommandQueue>::query().get(&*world, queue).unwrap())
+    }
+
+    #[test]
+    fn spawned_entities_exist_after_flush() {
+        let (world, queue) = queue_world();
+        let entity = with_queue(&world, queue, |queue| queue.spawn());
+        assert!(!world.read().contains(entity));
+
+        flush_world_command_queues(&world);
+        assert!(world.read().contains(entity));
+    }
+
+    #[test]
+    fn component_changes_and_despawns_are_applied() {
+        let (world, queue) = queue_world();
+        let (kept, despawned) = with_queue(&world, queue, |queue| {
+            let kept = queue.spawn();
+            let despawned = queue.spawn();
+            queue.add_component(kept, 1u32);
+            queue.add_component(kept, 2u64);
+            queue.add_component(despawned, 3u32);
+            (kept, despawned)
+        });
+        flush_world_command_queues(&world);
+
+        with_queue(&world, queue, |queue| {
+            queue.remove_component::<u64>(kept);
+            queue.despawn(despawned);
+        });
+        flush_world_command_queues(&world);
+
+        let world = world.read();
+        let entry = world.entry_ref(kept).unwrap();
+        assert_eq!(entry.get_component::<u32>().ok(), Some(&1));
+        assert!(entry.get_component::<u64>().is_err());
+        assert!(!world.contains(despawned));
+    }
+
+    #[test]
+    fn enqueues_from_other_threads_under_a_read_lock() {
+        let (world, queue) = queue_world();
+
+        let entity = with_queue(&world, queue, |queue| {
+            std::thread::scope(|scope| {
+                scope
+                    .spawn(|| {
+                        let entity = queue.spawn();
+                        queue.add_component(entity, 1u32);
+                        entity
+                    })
+                    .join()
+                    .unwrap()
+            })
+        });
+        flush_world_command_queues(&world);
+
+        let world = world.read();
+        let entry = world.entry_ref(entity).unwrap();
+        assert_eq!(entry.get_component::<u32>().ok(), Some(&1));
+    }
+
+    #[test]
+    fn empty_queues_skip_the_write_lock() {
+        let (world, _) = queue_world();
+
+        // A write lock would block on this read guard
+        let _read = world.read();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let flush_world = world.clone();
+        std::thread::spawn(move || {
+            flush_world_command_queues(&flush_world);
+            sender.send(()).unwrap();
+        });
+
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use parking_lot::{Mutex, RwLock};
+
+/// Error returned when accessing an [`ImmutableResources`] store
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResourceError {
+    /// No resource of the named type has been inserted
+    Missing(&'static str),
+}
+
+impl Display for ResourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceError::Missing(type_name) => write!(f, "Missing resource {}", type_name),
+        }
+    }
+}
+
+impl std::error::Error for ResourceError {}
+
+struct ResourceEntry {
+    type_name: &'static str,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+/// Thread-safe typed resource map
+///
+/// Unlike legion's [`Resources`](legion::systems::Resources), this is Send + Sync
+/// and only requires shared access, so it can be used from [`ImmutableSchedule`](crate::ImmutableSchedule) systems.
+///
+/// Cloning produces another handle to the same store, which allows systems to take it as state:
+/// ```ignore
+/// #[legion::system]
+/// fn my_system(#[state] resources: &ImmutableResources) {
+///     let device = if let Some(device) = resources.get_or_warn::<Device>() {
+///         device
+///     } else {
+///         return;
+///     };
+/// }
+///
+/// single![my_system_system(world.resources().clone())]
+/// ```
+///
+/// Resources are handed out behind an [`Arc`], so mutable state should use interior mutability.
+/// Systems that access resources can declare as much via
+/// [`IntoScheduledSystem::reads_resource`](crate::IntoScheduledSystem::reads_resource) for ordering purposes.
+#[derive(Default, Clone)]
+pub struct ImmutableResources {
+    entries: Arc<RwLock<BTreeMap<TypeId, ResourceEntry>>>,
+    /// Missing types already reported by [`ImmutableResources::get_or_warn`]
+    warned: Arc<Mutex<BTreeSet<TypeId>>>,
+}
+
+impl ImmutableResources {
+    /// Insert a resource, replacing any existing resource of the same type
+    pub fn insert<T: Send + Sync + 'static>(&self, resource: T) {
+        self.warned.lock().remove(&TypeId::of::<T>());
+        self.entries.write().insert(
+            TypeId::of::<T>(),
+            ResourceEntry {
+                type_name: std::any::type_name::<T>(),
+                value: Arc::new(resource),
+            },
+        );
+    }
+
+    /// Remove a resource, returning it if present
+    pub fn remove<T: Send + Sync + 'static>(&self) -> Result<Arc<T>, ResourceError> {
+        self.entries
+            .write()
+            .remove(&TypeId::of::<T>())
+            .map(|entry| downcast(entry.value))
+            .ok_or(ResourceError::Missing(std::any::type_name::<T>()))
+    }
+
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.entries.read().contains_key(&TypeId::of::<T>())
+    }
+
+    /// Fetch a resource by type
+    pub fn get<T: Send + Sync + 'static>(&self) -> Result<Arc<T>, ResourceError> {
+        self.entries
+            .read()
+            .get(&TypeId::of::<T>())
+            .map(|entry| downcast(entry.value.clone()))
+            .ok_or(ResourceError::Missing(std::any::type_name::<T>()))
+    }
+
+    /// Fetch a resource by type, logging a warning the first time it's found missing
+    ///
+    /// Intended for systems that skip their work until a resource is available;
+    /// the warning is re-armed if the resource is inserted and later goes missing again.
+    pub fn get_or_warn<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        match self.get::<T>() {
+            Ok(resource) => Some(resource),
+            Err(e) => {
+                if self.warned.lock().insert(TypeId::of::<T>()) {
+                    tracing::warn!("{}", e);
+                }
+                None
+            }
+        }
+    }
+}
+
+impl Debug for ImmutableResources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.entries.read().values().map(|entry| entry.type_name))
+            .finish()
+    }
+}
+
+fn downcast<T: Send + Sync + 'static>(value: Arc<dyn Any + Send + Sync>) -> Arc<T> {
+    match value.downcast::<T>() {
+        Ok(value) => value,
+        Err(_) => unreachable!("Resource stored under mismatched TypeId"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Counter(usize);
+
+    #[test]
+    fn insert_get_contains() {
+        let resources = ImmutableResources::default();
+        assert!(!resources.contains::<Counter>());
+
+        resources.insert(Counter(1));
+        assert!(resources.contains::<Counter>());
+        assert_eq!(*resources.get::<Counter>().unwrap(), Counter(1));
+    }
+
+    #[test]
+    fn insert_replaces_existing() {
+        let resources = ImmutableResources::default();
+        resources.insert(Counter(1));
+        let previous = resources.get::<Counter>().unwrap();
+
+        resources.insert(Counter(2));
+        assert_eq!(*resources.get::<Counter>().unwrap(), Counter(2));
+
+        // Handles fetched before the replacement keep the old value
+        assert_eq!(*previous, Counter(1));
+    }
+
+    #[test]
+    fn remove_returns_resource() {
+        let resources = ImmutableResources::default();
+        resources.insert(Counter(1));
+
+        assert_eq!(*resources.remove::<Counter>().unwrap(), Counter(1));
+        assert!(!resources.contains::<Counter>());
+        assert!(resources.remove::<Counter>().is_err());
+    }
+
+    #[test]
+    fn missing_names_type() {
+        let resources = ImmutableResources::default();
+
+        let error = resources.get::<Counter>().unwrap_err();
+        assert_eq!(
+            error,
+            ResourceError::Missing(std::any::type_name::<Counter>())
+        );
+        assert!(error.to_string().contains("Counter"));
+
+        assert!(resources.get_or_warn::<Counter>().is_none());
+    }
+
+    #[test]
+    fn clones_share_store() {
+        let resources = ImmutableResources::default();
+        let clone = resources.clone();
+
+        clone.insert(Counter(1));
+        assert_eq!(*resources.get::<Counter>().unwrap(), Counter(1));
+
+        resources.remove::<Counter>().unwrap();
+        assert!(!clone.contains::<Counter>());
+    }
+
+    #[test]
+    fn get_or_warn_rearms_after_insert() {
+        let resources = ImmutableResources::default();
+        let type_id = TypeId::of::<Counter>();
+
+        assert!(resources.get_or_warn::<Counter>().is_none());
+        assert!(resources.warned.lock().contains(&type_id));
+
+        resources.insert(Counter(1));
+        assert!(!resources.warned.lock().contains(&type_id));
+        assert_eq!(*resources.get_or_warn::<Counter>().unwrap(), Counter(1));
+    }
+}
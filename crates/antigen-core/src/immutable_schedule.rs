@@ -1,62 +1,182 @@
-use std::marker::PhantomData;
+use std::{any::Any, marker::PhantomData, sync::Arc};
 
 use legion::World;
 use legion::{
-    storage::ComponentTypeId,
-    systems::{ParallelRunnable, Runnable, SystemId, UnsafeResources},
-    world::ArchetypeAccess,
+    storage::{Component, ComponentTypeId},
+    systems::{ParallelRunnable, Resource, Resources, Runnable, SystemId, UnsafeResources},
+    world::{ArchetypeAccess, WorldId},
 };
 
-use crate::{ImmutableWorld, ReadWriteLock};
+use crate::{
+    flush_world_command_queues, ImmutableWorld, LockAccess, LockConflict, Profiler, ReadWriteLock,
+    RunCriteria, ScheduleDescription, ScheduleMode, ScheduledSystem, SystemOrdering,
+};
 use rayon::prelude::*;
 
 pub enum Single {}
 pub enum Serial {}
 pub enum Parallel {}
 
+/// A [`ParallelRunnable`] that can be composed into an [`ImmutableSchedule`]
+pub trait ImmutableRunnable: ParallelRunnable {
+    /// Lock-level access of this runnable, including that of any children
+    fn lock_access(&self) -> &LockAccess;
+
+    /// Criteria that must all be met for this runnable to run on a given frame
+    fn run_criteria(&self) -> &[RunCriteria];
+
+    /// Labels and ordering constraints used by [`ScheduleBuilder`](crate::ScheduleBuilder)
+    fn ordering(&self) -> &SystemOrdering;
+
+    /// Whether this runnable or any of its children hold unflushed commands for the given world
+    fn has_pending_commands(&mut self, world: WorldId) -> bool;
+
+    /// Flush the command buffers of this runnable and its children
+    ///
+    /// Buffers are flushed depth-first in declaration order,
+    /// so structural changes apply deterministically regardless of schedule mode.
+    fn flush_command_buffers(&mut self, world: &mut World, resources: &mut Resources);
+
+    /// Describe this runnable and any children for introspection
+    fn describe(&self) -> ScheduleDescription;
+
+    /// Name of this runnable as listed in the derived name of an unlabeled parent schedule
+    ///
+    /// Unlabeled schedules abbreviate themselves to `serial[..]` and the like,
+    /// keeping derived names to a single level regardless of nesting depth.
+    fn short_name(&self) -> String;
+
+    /// Attach or detach a profiler, propagating to any children that time their own runnables
+    fn set_profiler(&mut self, profiler: Option<Arc<Profiler>>);
+}
+
 pub trait RunSchedule {
+    const MODE: ScheduleMode;
+
+    /// Prefix of derived schedule names, matching the constructing macro
+    const NAME: &'static str;
+
+    /// Whether children run concurrently, and thus need to be batched by lock access
+    const CONCURRENT: bool;
+
     unsafe fn run_unsafe(
-        runnables: &mut Vec<Box<dyn ParallelRunnable>>,
+        runnables: &mut Vec<Box<dyn ImmutableRunnable>>,
+        batches: &[Vec<usize>],
+        profiler: Option<&Profiler>,
         world: &World,
         resources: &UnsafeResources,
     );
 }
 
 impl RunSchedule for Single {
+    const MODE: ScheduleMode = ScheduleMode::Single;
+    const NAME: &'static str = "single";
+    const CONCURRENT: bool = false;
+
     unsafe fn run_unsafe(
-        runnables: &mut Vec<Box<dyn ParallelRunnable>>,
+        runnables: &mut Vec<Box<dyn ImmutableRunnable>>,
+        _batches: &[Vec<usize>],
+        profiler: Option<&Profiler>,
         world: &World,
         resources: &UnsafeResources,
     ) {
-        runnables[0].run_unsafe(world, resources);
+        run_runnable(&mut *runnables[0], profiler, world, resources);
     }
 }
 
 impl RunSchedule for Serial {
+    const MODE: ScheduleMode = ScheduleMode::Serial;
+    const NAME: &'static str = "serial";
+    const CONCURRENT: bool = false;
+
     unsafe fn run_unsafe(
-        runnables: &mut Vec<Box<dyn ParallelRunnable>>,
+        runnables: &mut Vec<Box<dyn ImmutableRunnable>>,
+        _batches: &[Vec<usize>],
+        profiler: Option<&Profiler>,
         world: &World,
         resources: &UnsafeResources,
     ) {
         for runnable in runnables {
-            runnable.run_unsafe(world, resources);
+            run_runnable(&mut **runnable, profiler, world, resources);
         }
     }
 }
 
 impl RunSchedule for Parallel {
+    const MODE: ScheduleMode = ScheduleMode::Parallel;
+    const NAME: &'static str = "parallel";
+    const CONCURRENT: bool = true;
+
     unsafe fn run_unsafe(
-        runnables: &mut Vec<Box<dyn ParallelRunnable>>,
+        runnables: &mut Vec<Box<dyn ImmutableRunnable>>,
+        batches: &[Vec<usize>],
+        profiler: Option<&Profiler>,
         world: &World,
         resources: &UnsafeResources,
     ) {
-        runnables
-            .par_iter_mut()
-            .map(|system| system.run_unsafe(world, resources))
-            .for_each(drop);
+        for batch in batches {
+            runnables
+                .par_iter_mut()
+                .enumerate()
+                .filter(|(i, _)| batch.contains(i))
+                .map(|(_, system)| run_runnable(&mut **system, profiler, world, resources))
+                .for_each(drop);
+        }
     }
 }
 
+/// Run a child runnable inside a tracing span, recording a profiler span if one is attached and enabled
+///
+/// Runnables whose [`RunCriteria`] are not met are skipped, and recorded as such.
+unsafe fn run_runnable(
+    runnable: &mut dyn ImmutableRunnable,
+    profiler: Option<&Profiler>,
+    world: &World,
+    resources: &UnsafeResources,
+) {
+    let span = match runnable.name() {
+        Some(name) => tracing::debug_span!("system", name = %name),
+        None => tracing::debug_span!("system", name = "<unnamed>"),
+    };
+    let _enter = span.enter();
+
+    if !should_run(runnable, profiler, world) {
+        return;
+    }
+
+    let start = profiler.and_then(Profiler::begin);
+
+    runnable.run_unsafe(world, resources);
+
+    if let (Some(profiler), Some(start)) = (profiler, start) {
+        profiler.end(runnable_name(runnable), start);
+    }
+}
+
+/// Evaluate the [`RunCriteria`] of `runnable`, reporting the first that isn't met
+fn should_run(
+    runnable: &dyn ImmutableRunnable,
+    profiler: Option<&Profiler>,
+    world: &World,
+) -> bool {
+    let criteria = match runnable
+        .run_criteria()
+        .iter()
+        .find(|criteria| !criteria.should_run(world))
+    {
+        Some(criteria) => criteria,
+        None => return true,
+    };
+
+    tracing::trace!("Skipping, {} not met", criteria.name());
+
+    if let Some(profiler) = profiler {
+        profiler.skip(runnable_name(runnable));
+    }
+
+    false
+}
+
 /// A schedule designed for running systems against interior-mutable worlds.
 ///
 /// ImmutableSchedule<Single> runs a single system.
@@ -65,12 +185,21 @@ impl RunSchedule for Parallel {
 ///
 /// These all implement [`Runnable`] and [`ParallelRunnable`], so can be nested arbitrarily.
 ///
+/// Since mutation happens through [`RwLock`](crate::RwLock) guards rather than legion writes,
+/// systems can declare their lock-level access via [`IntoScheduledSystem`](crate::IntoScheduledSystem).
+/// ImmutableSchedule<Parallel> uses these declarations to split conflicting children
+/// into sequential batches, and records each conflict for inspection via [`ImmutableSchedule::lock_conflicts`].
+///
+/// Systems and nested schedules can be made conditional via `run_if` and [`RunCriteria`],
+/// which are evaluated each frame immediately before the runnable would run.
+///
 /// Limitations:
 /// * [`Resources`] cannot be used
 ///     * [`Resources`] is !Send + !Sync, and requires exclusive mutable access.
 ///     * [`ImmutableSchedule`] relies on [`Runnable::run_unsafe`], which requires [`UnsafeResources`].
 ///     * [`UnsafeResources`] has no methods for accessing or modifying its underlying data.
-///     * Instead, use singleton entities.
+///     * Instead, use singleton entities, or [`ImmutableResources`](crate::ImmutableResources)
+///       via [`ImmutableWorld::resources`] passed in as system state.
 ///     * If !Send / !Sync types are required:
 ///         * Write a system constructor that moves the thread-unsafe data
 ///           and manually queries against &World with it in scope
@@ -82,7 +211,7 @@ impl RunSchedule for Parallel {
 ///         
 ///         or
 ///         
-///         * Batch up commands inside a component that can be read and actioned 
+///         * Batch up commands inside a component that can be read and actioned
 ///           from the thread that owns the thread-unsafe data
 ///
 ///         or
@@ -95,15 +224,25 @@ impl RunSchedule for Parallel {
 ///     * [`CommandBuffer`] exposes no methods for accessing its underlying data.
 ///     * Thus, multiple nested [`CommandBuffer`]s can't be merged.
 ///     * To work around this, buffers are flushed manually via [`ImmutableSchedule::execute_and_flush`],
-///       which can only access the [`Runnable::command_buffer_mut`]s of its direct children.
-///     * In short, this means only top-level systems may add or remove components and entities
-///       from the world.
+///       which walks nested schedules via [`ImmutableRunnable::flush_command_buffers`]
+///       and flushes every system's [`Runnable::command_buffer_mut`] depth-first in declaration order.
+///     * Only schedules nested through [`ImmutableSchedule::add_system`] (i.e. the [`single`], [`serial`]
+///       and [`parallel`] macros) are visited; foreign [`Runnable`] containers are treated as leaves.
 
 pub struct ImmutableSchedule<T> {
     system_id: SystemId,
-    runnables: Vec<Box<dyn ParallelRunnable>>,
+    labeled: bool,
+    /// Whether `system_id` reflects the current children, or a label
+    named: bool,
+    runnables: Vec<Box<dyn ImmutableRunnable>>,
+    batches: Vec<Vec<usize>>,
     reads_ids: Vec<ComponentTypeId>,
     archetypes: ArchetypeAccess,
+    lock_access: LockAccess,
+    lock_conflicts: Vec<LockConflict>,
+    run_criteria: Vec<RunCriteria>,
+    ordering: SystemOrdering,
+    profiler: Option<Arc<Profiler>>,
     _phantom: PhantomData<T>,
 }
 
@@ -111,9 +250,17 @@ impl<T> Default for ImmutableSchedule<T> {
     fn default() -> Self {
         ImmutableSchedule {
             system_id: SystemId::from("ImmutableSchedule"),
+            labeled: false,
+            named: false,
             runnables: Default::default(),
+            batches: Default::default(),
             reads_ids: Default::default(),
             archetypes: ArchetypeAccess::Some(Default::default()),
+            lock_access: Default::default(),
+            lock_conflicts: Default::default(),
+            run_criteria: Default::default(),
+            ordering: Default::default(),
+            profiler: Default::default(),
             _phantom: Default::default(),
         }
     }
@@ -122,28 +269,32 @@ impl<T> Default for ImmutableSchedule<T> {
 impl ImmutableSchedule<()> {
     pub fn single() -> ImmutableSchedule<Single> {
         ImmutableSchedule {
-            system_id: SystemId::from("ImmutableSchedule<Single>"),
+            system_id: SystemId::from("single[]"),
             ..Default::default()
         }
     }
 
     pub fn serial() -> ImmutableSchedule<Serial> {
         ImmutableSchedule {
-            system_id: SystemId::from("ImmutableSchedule<Serial>"),
+            system_id: SystemId::from("serial[]"),
             ..Default::default()
         }
     }
 
     pub fn parallel() -> ImmutableSchedule<Parallel> {
         ImmutableSchedule {
-            system_id: SystemId::from("ImmutableSchedule<Parallel>"),
+            system_id: SystemId::from("parallel[]"),
             ..Default::default()
         }
     }
 }
 
-impl<T> ImmutableSchedule<T> {
-    pub fn add_system<S: ParallelRunnable + 'static>(mut self, system: S) -> Self {
+impl<T: RunSchedule> ImmutableSchedule<T> {
+    pub fn add_system<S: ParallelRunnable + 'static>(self, system: S) -> Self {
+        self.add_runnable(into_immutable_runnable(system))
+    }
+
+    pub(crate) fn add_runnable(mut self, system: Box<dyn ImmutableRunnable>) -> Self {
         let (writes_resources, writes_components) = system.writes();
 
         if writes_resources.len() > 0 {
@@ -162,34 +313,195 @@ impl<T> ImmutableSchedule<T> {
             },
         }
 
+        if T::CONCURRENT {
+            self.batch_system(&*system);
+        }
+
         self.reads_ids.extend(system.reads().1);
-        self.runnables.push(Box::new(system));
+        self.lock_access.union_with(system.lock_access());
+        self.runnables.push(system);
+        self.named = self.labeled;
+
+        if let Some(profiler) = &self.profiler {
+            self.runnables
+                .last_mut()
+                .unwrap()
+                .set_profiler(Some(profiler.clone()));
+        }
+
         self
     }
 
-    /// Flush system command buffers
-    fn flush(&mut self, world: &ImmutableWorld) {
-        let mut resources = legion::systems::Resources::default();
-        for system in &mut self.runnables {
-            let world_id = world.read().id();
-            if let Some(command_buffer) = system.command_buffer_mut(world_id) {
-                if !command_buffer.is_empty() {
-                    command_buffer.flush(&mut world.write(), &mut resources);
-                }
+    /// Declare that this schedule reads the lock(s) held by component `U`
+    pub fn reads_lock<U: Component>(mut self) -> Self {
+        self.lock_access = self.lock_access.reads::<U>();
+        self
+    }
+
+    /// Declare that this schedule writes the lock(s) held by component `U`
+    pub fn writes_lock<U: Component>(mut self) -> Self {
+        self.lock_access = self.lock_access.writes::<U>();
+        self
+    }
+
+    /// Declare that this schedule reads resource `U`
+    pub fn reads_resource<U: Resource>(mut self) -> Self {
+        self.lock_access = self.lock_access.reads_resource::<U>();
+        self
+    }
+
+    /// Declare that this schedule writes resource `U`
+    pub fn writes_resource<U: Resource>(mut self) -> Self {
+        self.lock_access = self.lock_access.writes_resource::<U>();
+        self
+    }
+
+    /// Only run this schedule on frames where `criteria` is met
+    pub fn run_if(mut self, criteria: RunCriteria) -> Self {
+        self.run_criteria.push(criteria);
+        self
+    }
+
+    /// Label this schedule for use in [`ScheduleBuilder`](crate::ScheduleBuilder) ordering constraints
+    ///
+    /// The first label also replaces the derived name used in tracing, profiling and descriptions.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        let label = label.into();
+        if !self.labeled {
+            self.system_id = SystemId::from(label.clone());
+            self.labeled = true;
+            self.named = true;
+        }
+        self.ordering = self.ordering.label(label);
+        self
+    }
+
+    /// Run this schedule before every runnable labeled `label`
+    pub fn before(mut self, label: impl Into<String>) -> Self {
+        self.ordering = self.ordering.before(label);
+        self
+    }
+
+    /// Run this schedule after every runnable labeled `label`
+    pub fn after(mut self, label: impl Into<String>) -> Self {
+        self.ordering = self.ordering.after(label);
+        self
+    }
+
+    /// Derive the name of an unlabeled schedule from the short names of its children,
+    /// ex. `serial[first, parallel[..]]`
+    ///
+    /// Deferred until the schedule is prepared, so building stays linear in the number of children.
+    fn update_name(&mut self) {
+        if self.named {
+            return;
+        }
+
+        let children = self
+            .runnables
+            .iter()
+            .map(|runnable| runnable.short_name())
+            .collect::<Vec<_>>();
+
+        self.system_id = SystemId::from(format!("{}[{}]", T::NAME, children.join(", ")));
+        self.named = true;
+    }
+
+    /// Lock conflicts detected between children while building this schedule
+    pub fn lock_conflicts(&self) -> &[LockConflict] {
+        &self.lock_conflicts
+    }
+
+    /// Place a system in the earliest batch that follows every system it conflicts with
+    fn batch_system(&mut self, system: &dyn ImmutableRunnable) {
+        let index = self.runnables.len();
+
+        let mut batch = 0;
+        for (i, existing) in self.runnables.iter().enumerate() {
+            let components = existing.lock_access().conflicts(system.lock_access());
+            let resources = existing
+                .lock_access()
+                .resource_conflicts(system.lock_access());
+
+            if components.is_empty() && resources.is_empty() {
+                continue;
             }
+
+            let existing_batch = self
+                .batches
+                .iter()
+                .position(|batch| batch.contains(&i))
+                .expect("Runnable is not batched");
+
+            batch = batch.max(existing_batch + 1);
+
+            let conflict = LockConflict {
+                first: existing.short_name(),
+                second: system.short_name(),
+                components,
+                resources,
+            };
+
+            tracing::debug!("{}, running sequentially", conflict);
+            self.lock_conflicts.push(conflict);
+        }
+
+        if batch == self.batches.len() {
+            self.batches.push(vec![]);
         }
+
+        self.batches[batch].push(index);
     }
 }
 
-impl<T: RunSchedule> ImmutableSchedule<T> {
+impl<T: RunSchedule + Send + Sync> ImmutableSchedule<T> {
+    /// Flush system command buffers, including those of nested schedules
+    fn flush(&mut self, world: &ImmutableWorld) {
+        let world_id = world.read().id();
+        if !self.has_pending_commands(world_id) {
+            return;
+        }
+
+        let mut resources = Resources::default();
+        self.flush_command_buffers(&mut world.write(), &mut resources);
+    }
+}
+
+impl<T: RunSchedule + Send + Sync> ImmutableSchedule<T> {
+    /// Record per-runnable timings into `profiler`, including those of nested schedules
+    pub fn with_profiler(mut self, profiler: Arc<Profiler>) -> Self {
+        self.set_profiler(Some(profiler));
+        self
+    }
+
     pub fn execute(&mut self, world: &ImmutableWorld) {
+        self.update_name();
+
+        let span = tracing::debug_span!("schedule", name = %self.system_id);
+        let _enter = span.enter();
+
+        let profiler = self.profiler.clone();
+        if !should_run(self, profiler.as_deref(), &world.read()) {
+            return;
+        }
+
+        let start = profiler.as_deref().and_then(Profiler::begin);
+
         self.prepare(&world.read());
         unsafe { self.run_unsafe(&world.read(), &mut Default::default()) };
+
+        if let (Some(profiler), Some(start)) = (profiler, start) {
+            profiler.end(self.system_id.to_string(), start);
+        }
     }
 
+    /// Execute, then flush system command buffers and any pending [`WorldCommandQueue`](crate::WorldCommandQueue)s
+    ///
+    /// [`LifecycleHooks`](crate::LifecycleHooks) are left to the once-per-frame [`run_lifecycle_hooks`](crate::run_lifecycle_hooks).
     pub fn execute_and_flush(&mut self, world: &ImmutableWorld) {
         self.execute(world);
         self.flush(world);
+        flush_world_command_queues(world);
     }
 }
 
@@ -217,6 +529,7 @@ impl<T: RunSchedule> Runnable for ImmutableSchedule<T> {
     }
 
     fn prepare(&mut self, world: &World) {
+        self.update_name();
         self.runnables
             .par_iter_mut()
             .map(|system| system.prepare(&world))
@@ -228,7 +541,13 @@ impl<T: RunSchedule> Runnable for ImmutableSchedule<T> {
     }
 
     unsafe fn run_unsafe(&mut self, world: &World, resources: &UnsafeResources) {
-        T::run_unsafe(&mut self.runnables, world, resources)
+        T::run_unsafe(
+            &mut self.runnables,
+            &self.batches,
+            self.profiler.as_deref(),
+            world,
+            resources,
+        )
     }
 
     fn command_buffer_mut(
@@ -239,11 +558,128 @@ impl<T: RunSchedule> Runnable for ImmutableSchedule<T> {
     }
 }
 
+impl<T: RunSchedule + Send + Sync> ImmutableRunnable for ImmutableSchedule<T> {
+    fn lock_access(&self) -> &LockAccess {
+        &self.lock_access
+    }
+
+    fn run_criteria(&self) -> &[RunCriteria] {
+        &self.run_criteria
+    }
+
+    fn ordering(&self) -> &SystemOrdering {
+        &self.ordering
+    }
+
+    fn has_pending_commands(&mut self, world: WorldId) -> bool {
+        self.runnables
+            .iter_mut()
+            .any(|runnable| runnable.has_pending_commands(world))
+    }
+
+    fn flush_command_buffers(&mut self, world: &mut World, resources: &mut Resources) {
+        for runnable in &mut self.runnables {
+            runnable.flush_command_buffers(world, resources);
+        }
+    }
+
+    fn describe(&self) -> ScheduleDescription {
+        let children = self
+            .runnables
+            .iter()
+            .map(|runnable| runnable.describe())
+            .collect::<Vec<_>>();
+
+        // Unlike the runtime name, describe the whole subtree
+        let name = if self.labeled {
+            self.system_id.to_string()
+        } else {
+            let names = children
+                .iter()
+                .map(|child| child.name.as_str())
+                .collect::<Vec<_>>();
+            format!("{}[{}]", T::NAME, names.join(", "))
+        };
+
+        ScheduleDescription {
+            name,
+            mode: Some(T::MODE),
+            reads: self.reads_ids.clone(),
+            lock_access: self.lock_access.clone(),
+            run_criteria: self
+                .run_criteria
+                .iter()
+                .map(|criteria| criteria.name().to_string())
+                .collect(),
+            batches: if T::CONCURRENT {
+                self.batches.clone()
+            } else {
+                vec![(0..self.runnables.len()).collect()]
+            },
+            children,
+        }
+    }
+
+    fn short_name(&self) -> String {
+        if self.labeled {
+            self.system_id.to_string()
+        } else {
+            format!("{}[..]", T::NAME)
+        }
+    }
+
+    fn set_profiler(&mut self, profiler: Option<Arc<Profiler>>) {
+        for runnable in &mut self.runnables {
+            runnable.set_profiler(profiler.clone());
+        }
+        self.profiler = profiler;
+    }
+}
+
+pub(crate) fn runnable_name(runnable: &dyn ImmutableRunnable) -> String {
+    runnable
+        .name()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "<unnamed>".into())
+}
+
+/// Box a system as an [`ImmutableRunnable`], preserving nested schedules and annotated systems
+pub(crate) fn into_immutable_runnable<S: ParallelRunnable + 'static>(
+    system: S,
+) -> Box<dyn ImmutableRunnable> {
+    let system: Box<dyn Any> = Box::new(system);
+
+    let system = match system.downcast::<ScheduledSystem>() {
+        Ok(system) => return system,
+        Err(system) => system,
+    };
+
+    let system = match system.downcast::<ImmutableSchedule<Single>>() {
+        Ok(system) => return system,
+        Err(system) => system,
+    };
+
+    let system = match system.downcast::<ImmutableSchedule<Serial>>() {
+        Ok(system) => return system,
+        Err(system) => system,
+    };
+
+    let system = match system.downcast::<ImmutableSchedule<Parallel>>() {
+        Ok(system) => return system,
+        Err(system) => system,
+    };
+
+    match system.downcast::<S>() {
+        Ok(system) => Box::new(ScheduledSystem::new(*system)),
+        Err(_) => unreachable!(),
+    }
+}
+
 /// Construct an [`ImmutableSchedule<Single>`] from a set of [`ParallelRunnable`] systems.
 #[macro_export]
 macro_rules! single {
     ($system:expr) => {
-        $crate::ImmutableSchedule::single().add_system($system);
+        $crate::ImmutableSchedule::single().add_system($system)
     };
 }
 
@@ -254,7 +690,7 @@ macro_rules ! serial {
         $crate::ImmutableSchedule::serial()
         $(
             .add_system($system)
-        )*;
+        )*
     };
 }
 
@@ -265,6 +701,63 @@ macro_rules ! parallel {
         $crate::ImmutableSchedule::parallel()
         $(
             .add_system($system)
-        )*;
+        )*
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use legion::systems::SystemBuilder;
+
+    use super::*;
+    use crate::IntoScheduledSystem;
+
+    fn system(name: &'static str) -> impl ParallelRunnable {
+        SystemBuilder::new(name).build(|_, _, _, _| ())
+    }
+
+    #[test]
+    fn non_conflicting_systems_share_a_batch() {
+        let schedule = ImmutableSchedule::parallel()
+            .add_system(system("a").reads_lock::<u8>())
+            .add_system(system("b").reads_lock::<u8>())
+            .add_system(system("c").writes_lock::<u16>());
+
+        assert_eq!(schedule.batches, vec![vec![0, 1, 2]]);
+        assert!(schedule.lock_conflicts().is_empty());
+    }
+
+    #[test]
+    fn conflicting_systems_follow_their_latest_conflict() {
+        let schedule = ImmutableSchedule::parallel()
+            .add_system(system("a").writes_lock::<u8>())
+            .add_system(system("b").writes_lock::<u16>())
+            .add_system(system("c").reads_lock::<u16>())
+            .add_system(system("d").reads_lock::<u8>().writes_lock::<u16>());
+
+        assert_eq!(schedule.batches, vec![vec![0, 1], vec![2], vec![3]]);
+        assert_eq!(schedule.lock_conflicts().len(), 4);
+    }
+
+    #[test]
+    fn later_systems_fill_earlier_free_batches() {
+        let schedule = ImmutableSchedule::parallel()
+            .add_system(system("a").writes_lock::<u8>())
+            .add_system(system("b").writes_lock::<u8>())
+            .add_system(system("c").writes_lock::<u16>())
+            .add_system(system("d").writes_resource::<u32>())
+            .add_system(system("e").reads_resource::<u32>());
+
+        assert_eq!(schedule.batches, vec![vec![0, 2, 3], vec![1, 4]]);
+    }
+
+    #[test]
+    fn serial_schedules_are_not_batched() {
+        let schedule = ImmutableSchedule::serial()
+            .add_system(system("a").writes_lock::<u8>())
+            .add_system(system("b").writes_lock::<u8>());
+
+        assert!(schedule.batches.is_empty());
+        assert!(schedule.lock_conflicts().is_empty());
+    }
+}
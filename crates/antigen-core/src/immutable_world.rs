@@ -3,14 +3,25 @@ use std::sync::Arc;
 use legion::World;
 use parking_lot::RwLock;
 
-use crate::impl_read_write_lock;
+use crate::{impl_read_write_lock, ImmutableResources};
 
 #[derive(Debug, Clone)]
-pub struct ImmutableWorld(Arc<RwLock<World>>);
+pub struct ImmutableWorld {
+    world: Arc<RwLock<World>>,
+    resources: ImmutableResources,
+}
 
 impl ImmutableWorld {
     pub fn new(world: World) -> Self {
-        ImmutableWorld(Arc::new(RwLock::new(world)))
+        ImmutableWorld {
+            world: Arc::new(RwLock::new(world)),
+            resources: Default::default(),
+        }
+    }
+
+    /// Thread-safe resources shared by all handles to this world
+    pub fn resources(&self) -> &ImmutableResources {
+        &self.resources
     }
 }
 
@@ -20,4 +31,4 @@ impl Default for ImmutableWorld {
     }
 }
 
-impl_read_write_lock!(ImmutableWorld, 0, World);
+impl_read_write_lock!(ImmutableWorld, world, World);
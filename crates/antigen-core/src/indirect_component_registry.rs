@@ -0,0 +1,267 @@
+use std::{any::TypeId, collections::HashSet, fmt::Display, marker::PhantomData, sync::Arc};
+
+use legion::{
+    storage::Component,
+    world::{ComponentError, EntityAccessError, SubWorld},
+    Entity, EntityStore, IntoQuery, World,
+};
+use parking_lot::RwLock;
+
+use crate::{
+    App, DebugEntity, ImmutableWorld, IndirectComponent, ReadWriteLock, Stage, WorldCommandQueue,
+};
+
+/// What to do with the dependents of an entity despawned via
+/// [`IndirectComponentRegistry::despawn_cascade`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CascadePolicy {
+    /// Leave dependents untouched, allowing their references to dangle
+    Ignore,
+    /// Remove the [`IndirectComponent`] from each dependent
+    Remove,
+    /// Despawn each dependent, cascading further
+    Despawn,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndirectFaultKind {
+    /// The target entity does not exist
+    Dangling,
+    /// The target entity exists, but does not have the referenced component
+    Mistyped,
+}
+
+/// An [`IndirectComponent`] whose target cannot be resolved
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IndirectFault {
+    pub entity: Entity,
+    pub target: Entity,
+    pub component: &'static str,
+    pub kind: IndirectFaultKind,
+}
+
+impl Display for IndirectFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            IndirectFaultKind::Dangling => write!(
+                f,
+                "{:?} references {} on missing entity {:?}",
+                DebugEntity(self.entity),
+                self.component,
+                self.target
+            ),
+            IndirectFaultKind::Mistyped => write!(
+                f,
+                "{:?} references {} on {:?}, which does not have it",
+                DebugEntity(self.entity),
+                self.component,
+                DebugEntity(self.target)
+            ),
+        }
+    }
+}
+
+/// Collect every [`IndirectComponent<T>`] in `world` whose target cannot be resolved
+///
+/// Targets that `world` isn't permitted to access, as with a [`SubWorld`], are skipped.
+pub fn indirect_faults<T: Component, W: EntityStore>(world: &W) -> Vec<IndirectFault> {
+    <(Entity, &IndirectComponent<T>)>::query()
+        .iter(world)
+        .filter_map(|(entity, indirect)| {
+            let kind = match world.entry_ref(indirect.target()) {
+                Ok(entry) => match entry.get_component::<T>() {
+                    Err(ComponentError::NotFound { .. }) => IndirectFaultKind::Mistyped,
+                    _ => return None,
+                },
+                Err(EntityAccessError::EntityNotFound) => IndirectFaultKind::Dangling,
+                Err(EntityAccessError::AccessDenied) => return None,
+            };
+
+            Some(IndirectFault {
+                entity: *entity,
+                target: indirect.target(),
+                component: std::any::type_name::<T>(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Type-erased operations over a registered [`IndirectComponent<T>`]
+trait RegisteredIndirect: Send + Sync {
+    fn type_id(&self) -> TypeId;
+    fn policy(&self) -> CascadePolicy;
+    fn faults(&self, world: &World) -> Vec<IndirectFault>;
+    fn dependents(&self, world: &World, target: Entity) -> Vec<Entity>;
+    fn remove(&self, world: &mut World, entity: Entity);
+}
+
+struct Registration<T> {
+    policy: CascadePolicy,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Component> RegisteredIndirect for Registration<T> {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn policy(&self) -> CascadePolicy {
+        self.policy
+    }
+
+    fn faults(&self, world: &World) -> Vec<IndirectFault> {
+        indirect_faults::<T, _>(world)
+    }
+
+    fn dependents(&self, world: &World, target: Entity) -> Vec<Entity> {
+        <(Entity, &IndirectComponent<T>)>::query()
+            .iter(world)
+            .filter(|(entity, indirect)| **entity != target && indirect.target() == target)
+            .map(|(entity, _)| *entity)
+            .collect()
+    }
+
+    fn remove(&self, world: &mut World, entity: Entity) {
+        if let Some(mut entry) = world.entry(entity) {
+            entry.remove_component::<IndirectComponent<T>>();
+        }
+    }
+}
+
+/// Registry of [`IndirectComponent`] types, used to find dangling references and cascade despawns
+///
+/// Stored in [`ImmutableResources`](crate::ImmutableResources), and usually populated via
+/// [`App::add_indirect_component`], which also schedules validation:
+/// ```ignore
+/// App::new(&world).add_indirect_component::<BufferComponent>(CascadePolicy::Remove)
+/// ```
+#[derive(Default)]
+pub struct IndirectComponentRegistry(RwLock<Vec<Box<dyn RegisteredIndirect>>>);
+
+impl IndirectComponentRegistry {
+    /// Register [`IndirectComponent<T>`] with the given cascade policy
+    ///
+    /// Returns false without changing the policy if `T` is already registered.
+    pub fn register<T: Component>(&self, policy: CascadePolicy) -> bool {
+        let mut registrations = self.0.write();
+        if registrations
+            .iter()
+            .any(|registration| registration.type_id() == TypeId::of::<T>())
+        {
+            return false;
+        }
+
+        registrations.push(Box::new(Registration::<T> {
+            policy,
+            _phantom: Default::default(),
+        }));
+        true
+    }
+
+    /// Collect every registered indirect component whose target cannot be resolved
+    pub fn validate(&self, world: &World) -> Vec<IndirectFault> {
+        self.0
+            .read()
+            .iter()
+            .flat_map(|registration| registration.faults(world))
+            .collect()
+    }
+
+    /// Despawn `entity`, applying each registered type's [`CascadePolicy`] to its dependents
+    pub fn despawn_cascade(&self, world: &mut World, entity: Entity) {
+        let registrations = self.0.read();
+
+        let mut pending = vec![entity];
+        let mut despawned = HashSet::<Entity>::default();
+        while let Some(target) = pending.pop() {
+            if !despawned.insert(target) {
+                continue;
+            }
+
+            for registration in registrations.iter() {
+                match registration.policy() {
+                    CascadePolicy::Ignore => (),
+                    CascadePolicy::Remove => {
+                        for dependent in registration.dependents(world, target) {
+                            registration.remove(world, dependent);
+                        }
+                    }
+                    CascadePolicy::Despawn => {
+                        pending.extend(registration.dependents(world, target))
+                    }
+                }
+            }
+
+            world.remove(target);
+        }
+    }
+}
+
+/// Log any faults found by the [`IndirectComponentRegistry`] stored in `world`'s resources
+///
+/// Returns the faults so callers can react to them.
+pub fn report_indirect_faults(world: &ImmutableWorld) -> Vec<IndirectFault> {
+    let registry = match world.resources().get::<IndirectComponentRegistry>() {
+        Ok(registry) => registry,
+        Err(e) => {
+            tracing::warn!("Can't validate indirect components: {}", e);
+            return vec![];
+        }
+    };
+
+    let faults = registry.validate(&world.read());
+    for fault in &faults {
+        tracing::warn!("Indirect component fault: {}", fault);
+    }
+    faults
+}
+
+/// Report [`IndirectComponent<T>`]s whose target cannot be resolved
+///
+/// Each fault is logged once when it appears, rather than on every run.
+#[legion::system]
+#[read_component(IndirectComponent<T>)]
+#[read_component(T)]
+pub fn validate_indirect<T: Component>(
+    #[state] reported: &mut Vec<IndirectFault>,
+    world: &SubWorld,
+) {
+    let faults = indirect_faults::<T, _>(world);
+    for fault in &faults {
+        if !reported.contains(fault) {
+            tracing::warn!("Indirect component fault: {}", fault);
+        }
+    }
+    *reported = faults;
+}
+
+impl<E> App<E> {
+    /// Register [`IndirectComponent<T>`] with the world's [`IndirectComponentRegistry`],
+    /// and validate it during [`Stage::PostUpdate`]
+    ///
+    /// Inserts the registry if not present. Registering a type more than once has no further effect.
+    pub fn add_indirect_component<T: Component>(self, policy: CascadePolicy) -> Self {
+        if !self.resources().contains::<IndirectComponentRegistry>() {
+            self.resources()
+                .insert(IndirectComponentRegistry::default());
+        }
+
+        let registry = self.resources().get::<IndirectComponentRegistry>().unwrap();
+
+        if !registry.register::<T>(policy) {
+            return self;
+        }
+
+        self.add_system(Stage::PostUpdate, validate_indirect_system::<T>(vec![]))
+    }
+}
+
+impl WorldCommandQueue {
+    /// Despawn an entity at the next flush, cascading to its dependents via `registry`
+    pub fn despawn_cascade(&self, registry: Arc<IndirectComponentRegistry>, entity: Entity) {
+        self.record(move |cmd| {
+            cmd.exec_mut(move |world, _| registry.despawn_cascade(world, entity))
+        })
+    }
+}
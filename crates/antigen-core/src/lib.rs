@@ -1,11 +1,44 @@
+// Allows antigen-derive output, which refers to ::antigen_core, to be used within this crate
+extern crate self as antigen_core;
+
+mod app;
 mod components;
+mod immutable_resources;
 mod immutable_schedule;
 mod immutable_world;
+mod indirect_component_registry;
+mod lock_access;
+mod profiler;
+mod run_criteria;
+#[cfg(feature = "serialize")]
+mod scene;
+mod schedule_builder;
+mod schedule_description;
+mod scheduled_system;
+#[cfg(feature = "serialize")]
+mod snapshot;
+mod systems;
 mod traits;
 
 pub mod peano;
 
+pub use antigen_derive::{Construct, ReadWriteLock, With};
+
+pub use app::*;
 pub use components::*;
+pub use immutable_resources::*;
 pub use immutable_schedule::*;
 pub use immutable_world::*;
+pub use indirect_component_registry::*;
+pub use lock_access::*;
+pub use profiler::*;
+pub use run_criteria::*;
+#[cfg(feature = "serialize")]
+pub use scene::*;
+pub use schedule_builder::*;
+pub use schedule_description::*;
+pub use scheduled_system::*;
+#[cfg(feature = "serialize")]
+pub use snapshot::*;
+pub use systems::*;
 pub use traits::*;
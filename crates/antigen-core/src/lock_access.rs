@@ -0,0 +1,238 @@
+use std::fmt::Display;
+
+use legion::{
+    storage::{Component, ComponentTypeId},
+    systems::{Resource, ResourceTypeId},
+};
+
+/// Lock-level access declaration for a system running inside an [`ImmutableSchedule`](crate::ImmutableSchedule)
+///
+/// [`ImmutableSchedule`](crate::ImmutableSchedule) systems only ever read components at the legion level,
+/// and mutate them through [`RwLock`](crate::RwLock) guards instead.
+/// This type records which of those locks a system actually reads or writes,
+/// so that [`Parallel`](crate::Parallel) schedules can keep conflicting systems apart.
+///
+/// Access to [`ImmutableResources`](crate::ImmutableResources) can be declared in the same way.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LockAccess {
+    reads: Vec<ComponentTypeId>,
+    writes: Vec<ComponentTypeId>,
+    resource_reads: Vec<ResourceTypeId>,
+    resource_writes: Vec<ResourceTypeId>,
+}
+
+impl LockAccess {
+    /// Declare read access to the lock(s) held by component `T`
+    pub fn reads<T: Component>(mut self) -> Self {
+        self.add_read(ComponentTypeId::of::<T>());
+        self
+    }
+
+    /// Declare write access to the lock(s) held by component `T`
+    pub fn writes<T: Component>(mut self) -> Self {
+        self.add_write(ComponentTypeId::of::<T>());
+        self
+    }
+
+    /// Declare read access to resource `T`
+    pub fn reads_resource<T: Resource>(mut self) -> Self {
+        push_unique(&mut self.resource_reads, ResourceTypeId::of::<T>());
+        self
+    }
+
+    /// Declare write access to resource `T`
+    pub fn writes_resource<T: Resource>(mut self) -> Self {
+        push_unique(&mut self.resource_writes, ResourceTypeId::of::<T>());
+        self
+    }
+
+    pub fn read_ids(&self) -> &[ComponentTypeId] {
+        &self.reads
+    }
+
+    pub fn write_ids(&self) -> &[ComponentTypeId] {
+        &self.writes
+    }
+
+    pub fn resource_read_ids(&self) -> &[ResourceTypeId] {
+        &self.resource_reads
+    }
+
+    pub fn resource_write_ids(&self) -> &[ResourceTypeId] {
+        &self.resource_writes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty()
+            && self.writes.is_empty()
+            && self.resource_reads.is_empty()
+            && self.resource_writes.is_empty()
+    }
+
+    /// Merge another access declaration into this one
+    pub fn union_with(&mut self, other: &LockAccess) {
+        for id in &other.reads {
+            self.add_read(*id);
+        }
+
+        for id in &other.writes {
+            self.add_write(*id);
+        }
+
+        for id in &other.resource_reads {
+            push_unique(&mut self.resource_reads, *id);
+        }
+
+        for id in &other.resource_writes {
+            push_unique(&mut self.resource_writes, *id);
+        }
+    }
+
+    /// Return the components over which this access conflicts with `other`
+    ///
+    /// Two accesses conflict if either one writes a lock that the other reads or writes.
+    pub fn conflicts(&self, other: &LockAccess) -> Vec<ComponentTypeId> {
+        conflicts(&self.reads, &self.writes, &other.reads, &other.writes)
+    }
+
+    /// Return the resources over which this access conflicts with `other`
+    pub fn resource_conflicts(&self, other: &LockAccess) -> Vec<ResourceTypeId> {
+        conflicts(
+            &self.resource_reads,
+            &self.resource_writes,
+            &other.resource_reads,
+            &other.resource_writes,
+        )
+    }
+
+    fn add_read(&mut self, id: ComponentTypeId) {
+        push_unique(&mut self.reads, id);
+    }
+
+    fn add_write(&mut self, id: ComponentTypeId) {
+        push_unique(&mut self.writes, id);
+    }
+}
+
+fn push_unique<T: PartialEq>(ids: &mut Vec<T>, id: T) {
+    if !ids.contains(&id) {
+        ids.push(id);
+    }
+}
+
+fn conflicts<T: PartialEq + Copy>(
+    lhs_reads: &[T],
+    lhs_writes: &[T],
+    rhs_reads: &[T],
+    rhs_writes: &[T],
+) -> Vec<T> {
+    let mut conflicts = vec![];
+
+    for id in lhs_writes {
+        if rhs_reads.contains(id) || rhs_writes.contains(id) {
+            conflicts.push(*id);
+        }
+    }
+
+    for id in rhs_writes {
+        if lhs_reads.contains(id) && !conflicts.contains(id) {
+            conflicts.push(*id);
+        }
+    }
+
+    conflicts
+}
+
+/// A pair of systems whose lock access prevents them from running in parallel
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockConflict {
+    pub first: String,
+    pub second: String,
+    pub components: Vec<ComponentTypeId>,
+    pub resources: Vec<ResourceTypeId>,
+}
+
+impl Display for LockConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} conflicts with {} over ", self.first, self.second)?;
+
+        let components = self.components.iter().map(ToString::to_string);
+        let resources = self.resources.iter().map(ToString::to_string);
+        for (i, name) in components.chain(resources).enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", name)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct A;
+    struct B;
+    struct C;
+
+    #[test]
+    fn reads_do_not_conflict() {
+        let lhs = LockAccess::default().reads::<A>().reads::<B>();
+        let rhs = LockAccess::default().reads::<A>().reads::<B>();
+        assert!(lhs.conflicts(&rhs).is_empty());
+    }
+
+    #[test]
+    fn write_conflicts_with_read_and_write() {
+        let writer = LockAccess::default().writes::<A>();
+        let reader = LockAccess::default().reads::<A>();
+        let other_writer = LockAccess::default().writes::<A>();
+
+        assert_eq!(writer.conflicts(&reader), vec![ComponentTypeId::of::<A>()]);
+        assert_eq!(reader.conflicts(&writer), vec![ComponentTypeId::of::<A>()]);
+        assert_eq!(
+            writer.conflicts(&other_writer),
+            vec![ComponentTypeId::of::<A>()]
+        );
+    }
+
+    #[test]
+    fn conflicts_are_reported_once_per_component() {
+        let lhs = LockAccess::default()
+            .writes::<A>()
+            .reads::<B>()
+            .reads::<C>();
+        let rhs = LockAccess::default().writes::<A>().writes::<B>();
+
+        assert_eq!(
+            lhs.conflicts(&rhs),
+            vec![ComponentTypeId::of::<A>(), ComponentTypeId::of::<B>()]
+        );
+    }
+
+    #[test]
+    fn union_merges_without_duplicates() {
+        let mut lhs = LockAccess::default().reads::<A>().writes::<B>();
+        lhs.union_with(&LockAccess::default().reads::<A>().writes::<C>());
+
+        assert_eq!(lhs.read_ids(), &[ComponentTypeId::of::<A>()]);
+        assert_eq!(
+            lhs.write_ids(),
+            &[ComponentTypeId::of::<B>(), ComponentTypeId::of::<C>()]
+        );
+    }
+
+    #[test]
+    fn resource_conflicts_are_separate_from_components() {
+        let lhs = LockAccess::default().writes_resource::<A>();
+        let rhs = LockAccess::default().reads::<A>().reads_resource::<A>();
+
+        assert!(lhs.conflicts(&rhs).is_empty());
+        assert_eq!(
+            lhs.resource_conflicts(&rhs),
+            vec![ResourceTypeId::of::<A>()]
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+static THREAD_INDEX_HEAD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_INDEX: usize = THREAD_INDEX_HEAD.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Small, stable per-thread index used to identify threads in recorded spans
+pub fn profiler_thread_index() -> usize {
+    THREAD_INDEX.with(|index| *index)
+}
+
+/// A single timed run of a runnable
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileSpan {
+    pub name: String,
+    pub thread: usize,
+    /// Offset from the owning [`Profiler`]'s creation
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl ProfileSpan {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Aggregate timings for every span sharing a name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileStatistics {
+    pub name: String,
+    pub count: usize,
+    /// Number of times the runnable was skipped by its [`RunCriteria`](crate::RunCriteria)
+    pub skipped: usize,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl Display for ProfileStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} runs, {} skipped, mean {:?}, p95 {:?}, max {:?}",
+            self.name, self.count, self.skipped, self.mean, self.p95, self.max
+        )
+    }
+}
+
+/// Span recorder for [`ImmutableSchedule`](crate::ImmutableSchedule)
+///
+/// Attach via [`ImmutableSchedule::with_profiler`](crate::ImmutableSchedule::with_profiler),
+/// which propagates to nested schedules. Schedules without a profiler skip all timing.
+#[derive(Debug)]
+pub struct Profiler {
+    epoch: Instant,
+    enabled: AtomicBool,
+    spans: Mutex<Vec<ProfileSpan>>,
+    skips: Mutex<BTreeMap<String, usize>>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Profiler {
+            epoch: Instant::now(),
+            enabled: AtomicBool::new(true),
+            spans: Default::default(),
+            skips: Default::default(),
+        }
+    }
+}
+
+impl Profiler {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Pause or resume recording without detaching from any schedules
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed)
+    }
+
+    /// Begin timing a span, returning its start offset if recording is enabled
+    pub fn begin(&self) -> Option<Duration> {
+        if self.is_enabled() {
+            Some(self.epoch.elapsed())
+        } else {
+            None
+        }
+    }
+
+    /// Finish a span started by [`Profiler::begin`] on the current thread
+    pub fn end(&self, name: String, start: Duration) {
+        let end = self.epoch.elapsed();
+        self.spans.lock().push(ProfileSpan {
+            name,
+            thread: profiler_thread_index(),
+            start,
+            end,
+        });
+    }
+
+    /// Record that a runnable was skipped by its [`RunCriteria`](crate::RunCriteria)
+    pub fn skip(&self, name: String) {
+        if self.is_enabled() {
+            *self.skips.lock().entry(name).or_default() += 1;
+        }
+    }
+
+    /// Recorded spans, in order of completion
+    pub fn spans(&self) -> Vec<ProfileSpan> {
+        self.spans.lock().clone()
+    }
+
+    pub fn clear(&self) {
+        self.spans.lock().clear();
+        self.skips.lock().clear();
+    }
+
+    /// Per-name timing statistics, sorted by name
+    pub fn statistics(&self) -> Vec<ProfileStatistics> {
+        let mut durations = BTreeMap::<String, Vec<Duration>>::default();
+        for span in self.spans.lock().iter() {
+            durations
+                .entry(span.name.clone())
+                .or_default()
+                .push(span.duration());
+        }
+
+        let skips = self.skips.lock();
+        for name in skips.keys() {
+            durations.entry(name.clone()).or_default();
+        }
+
+        durations
+            .into_iter()
+            .map(|(name, mut durations)| {
+                durations.sort();
+                let count = durations.len();
+                let skipped = skips.get(&name).copied().unwrap_or_default();
+
+                if count == 0 {
+                    return ProfileStatistics {
+                        name,
+                        count,
+                        skipped,
+                        mean: Duration::ZERO,
+                        p95: Duration::ZERO,
+                        max: Duration::ZERO,
+                    };
+                }
+
+                let total: Duration = durations.iter().sum();
+                let p95_index = (count * 95).div_ceil(100).max(1) - 1;
+
+                ProfileStatistics {
+                    name,
+                    count,
+                    skipped,
+                    mean: total / count as u32,
+                    p95: durations[p95_index],
+                    max: durations[count - 1],
+                }
+            })
+            .collect()
+    }
+
+    /// Export recorded spans in the Chrome `trace_event` JSON format
+    ///
+    /// The result can be loaded in `chrome://tracing` or Perfetto.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut out = String::from("{\"traceEvents\":[");
+
+        for (i, span) in self.spans.lock().iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+
+            write!(
+                out,
+                "{{\"name\":\"{}\",\"cat\":\"system\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":{}}}",
+                escape_json(&span.name),
+                span.start.as_secs_f64() * 1_000_000.0,
+                span.duration().as_secs_f64() * 1_000_000.0,
+                span.thread
+            )
+            .unwrap();
+        }
+
+        out.push_str("],\"displayTimeUnit\":\"ms\"}");
+        out
+    }
+}
+
+fn escape_json(string: &str) -> String {
+    let mut out = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(name: &str, start_ms: u64, duration_ms: u64) -> ProfileSpan {
+        ProfileSpan {
+            name: name.to_string(),
+            thread: 0,
+            start: Duration::from_millis(start_ms),
+            end: Duration::from_millis(start_ms + duration_ms),
+        }
+    }
+
+    #[test]
+    fn statistics_aggregate_spans_and_skips_by_name() {
+        let profiler = Profiler::default();
+        profiler
+            .spans
+            .lock()
+            .extend((1..=20).map(|ms| span("system", ms * 100, ms)));
+        profiler.skip("system".to_string());
+        profiler.skip("skipped".to_string());
+        profiler.skip("skipped".to_string());
+
+        let statistics = profiler.statistics();
+        assert_eq!(
+            statistics,
+            vec![
+                ProfileStatistics {
+                    name: "skipped".to_string(),
+                    count: 0,
+                    skipped: 2,
+                    mean: Duration::ZERO,
+                    p95: Duration::ZERO,
+                    max: Duration::ZERO,
+                },
+                ProfileStatistics {
+                    name: "system".to_string(),
+                    count: 20,
+                    skipped: 1,
+                    mean: Duration::from_micros(10_500),
+                    p95: Duration::from_millis(19),
+                    max: Duration::from_millis(20),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn chrome_trace_lists_spans_in_microseconds() {
+        let profiler = Profiler::default();
+        profiler.spans.lock().push(span("a\"b", 1, 2));
+
+        assert_eq!(
+            profiler.to_chrome_trace(),
+            r#"{"traceEvents":[{"name":"a\"b","cat":"system","ph":"X","ts":1000,"dur":2000,"pid":1,"tid":0}],"displayTimeUnit":"ms"}"#
+        );
+    }
+
+    #[test]
+    fn escape_json_escapes_quotes_backslashes_and_controls() {
+        assert_eq!(escape_json("plain"), "plain");
+        assert_eq!(escape_json("\"\\\n\u{1}"), "\\\"\\\\\\u000a\\u0001");
+    }
+
+    #[test]
+    fn disabled_profiler_records_nothing() {
+        let profiler = Profiler::default();
+        profiler.set_enabled(false);
+
+        assert_eq!(profiler.begin(), None);
+        profiler.skip("system".to_string());
+        assert!(profiler.spans().is_empty());
+        assert!(profiler.statistics().is_empty());
+
+        profiler.set_enabled(true);
+        let start = profiler.begin().unwrap();
+        profiler.end("system".to_string(), start);
+        assert_eq!(profiler.spans().len(), 1);
+    }
+}
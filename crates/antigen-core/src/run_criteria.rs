@@ -0,0 +1,141 @@
+use std::{fmt::Debug, ops::Not, sync::Arc};
+
+use legion::{storage::Component, Entity, IntoQuery, World};
+
+use crate::{ChangeTracker, ChangedTrait, LazyComponent, ReadWriteLock};
+
+/// Predicate deciding whether a runnable in an [`ImmutableSchedule`](crate::ImmutableSchedule) runs this frame
+///
+/// Attached via `run_if` on systems or schedules, and evaluated by the owning schedule
+/// immediately before the runnable would run. Skipped runnables are reported via `tracing`
+/// and the attached [`Profiler`](crate::Profiler), if any.
+///
+/// ```ignore
+/// serial![
+///     reconfigure_surfaces_system().run_if(RunCriteria::any_changed::<SurfaceConfigurationComponent>()),
+///     create_pipelines_system().run_if(RunCriteria::any_pending::<PipelineComponent, RenderPipeline>()),
+///     render_system().run_if(RunCriteria::new("not_paused", |world| !is_paused(world))),
+/// ]
+/// ```
+#[derive(Clone)]
+pub struct RunCriteria {
+    name: String,
+    predicate: Arc<dyn Fn(&World) -> bool + Send + Sync>,
+}
+
+impl Debug for RunCriteria {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunCriteria")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl RunCriteria {
+    pub fn new<F>(name: impl Into<String>, predicate: F) -> Self
+    where
+        F: Fn(&World) -> bool + Send + Sync + 'static,
+    {
+        RunCriteria {
+            name: name.into(),
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn should_run(&self, world: &World) -> bool {
+        (self.predicate)(world)
+    }
+
+    /// Run if any `C` has changed generation since this criteria was last evaluated
+    ///
+    /// The criteria owns a [`ChangeTracker`], so is independent of when other systems clear changed flags.
+    /// Clones share the same tracker.
+    pub fn any_changed<C>() -> Self
+    where
+        C: Component + ChangedTrait,
+    {
+        let tracker = ChangeTracker::default();
+        RunCriteria::new(
+            format!("any_changed<{}>", std::any::type_name::<C>()),
+            move |world| {
+                <(Entity, &C)>::query()
+                    .iter(world)
+                    .filter(|(entity, c)| tracker.observe(**entity, *c))
+                    .count()
+                    > 0
+            },
+        )
+    }
+
+    /// Run if any `C` holds a pending [`LazyComponent`]
+    pub fn any_pending<C, T>() -> Self
+    where
+        C: Component + ReadWriteLock<LazyComponent<T>>,
+        T: Send + Sync + 'static,
+    {
+        RunCriteria::new(
+            format!("any_pending<{}>", std::any::type_name::<C>()),
+            |world| <&C>::query().iter(world).any(|c| c.read().is_pending()),
+        )
+    }
+
+    /// Run if any entity has a `C`
+    pub fn any_with<C: Component>() -> Self {
+        RunCriteria::new(
+            format!("any_with<{}>", std::any::type_name::<C>()),
+            |world| <&C>::query().iter(world).next().is_some(),
+        )
+    }
+
+    /// Run if both this and `other` are met
+    pub fn and(self, other: RunCriteria) -> Self {
+        RunCriteria::new(format!("({} && {})", self.name, other.name), move |world| {
+            self.should_run(world) && other.should_run(world)
+        })
+    }
+
+    /// Run if either this or `other` is met
+    pub fn or(self, other: RunCriteria) -> Self {
+        RunCriteria::new(format!("({} || {})", self.name, other.name), move |world| {
+            self.should_run(world) || other.should_run(world)
+        })
+    }
+}
+
+/// Run if the inner criteria is not met
+impl Not for RunCriteria {
+    type Output = RunCriteria;
+
+    fn not(self) -> Self::Output {
+        let RunCriteria { name, predicate } = self;
+        RunCriteria::new(format!("!{}", name), move |world| !predicate(world))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Changed;
+
+    #[test]
+    fn any_changed_observes_each_generation_once() {
+        let mut world = World::default();
+        let entity = world.push((Changed::new(0u32, true),));
+        let criteria = RunCriteria::any_changed::<Changed<u32>>();
+
+        assert!(criteria.should_run(&world));
+        assert!(!criteria.should_run(&world));
+
+        let changed = <&Changed<u32>>::query().get(&world, entity).unwrap();
+        changed.set_changed(false);
+        assert!(!criteria.should_run(&world));
+
+        changed.set_changed(true);
+        assert!(criteria.should_run(&world));
+        assert!(!criteria.should_run(&world));
+    }
+}
@@ -0,0 +1,446 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path, sync::Arc};
+
+use legion::{storage::Component, systems::CommandBuffer, Entity};
+use ron::Value;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{assemble_name, assemble_parent, Construct};
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Ron(ron::Error),
+    /// A template or entity extends a template that doesn't exist
+    MissingTemplate(String),
+    /// Template inheritance forms a cycle, listed from the first repeated template
+    TemplateCycle(Vec<String>),
+    /// A component name has no entry in the [`SceneRegistry`]
+    MissingComponent(String),
+    /// A parent refers to an entity name that doesn't exist
+    MissingEntity(String),
+    DuplicateEntity(String),
+    /// A component value doesn't match its registered type
+    Component {
+        component: String,
+        error: ron::Error,
+    },
+}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "Failed to read scene: {}", e),
+            SceneError::Ron(e) => write!(f, "Failed to parse scene: {}", e),
+            SceneError::MissingTemplate(name) => write!(f, "Missing template {}", name),
+            SceneError::TemplateCycle(names) => {
+                write!(f, "Template cycle: {}", names.join(" -> "))
+            }
+            SceneError::MissingComponent(name) => write!(f, "Unregistered component {}", name),
+            SceneError::MissingEntity(name) => write!(f, "Missing entity {}", name),
+            SceneError::DuplicateEntity(name) => write!(f, "Duplicate entity {}", name),
+            SceneError::Component { component, error } => {
+                write!(f, "Invalid value for component {}: {}", component, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(e: std::io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+impl From<ron::Error> for SceneError {
+    fn from(e: ron::Error) -> Self {
+        SceneError::Ron(e)
+    }
+}
+
+/// Records a deserialized component value onto an entity
+type SceneRecorder = Box<dyn FnOnce(&mut CommandBuffer, Entity)>;
+
+/// Deserializes a scene value, deferring its recording until every value has deserialized
+type SceneAssembler = Box<dyn Fn(Value) -> ron::Result<SceneRecorder> + Send + Sync>;
+
+/// Maps component names used in [`Scene`] files onto the code that assembles them
+#[derive(Default)]
+pub struct SceneRegistry {
+    assemblers: BTreeMap<String, SceneAssembler>,
+}
+
+impl SceneRegistry {
+    /// Register a component built from its scene value via [`Construct`]
+    ///
+    /// ex. `.register_construct::<TotalTimeComponent, f32, _>("total_time")`
+    pub fn register_construct<C, T, I>(self, name: impl Into<String>) -> Self
+    where
+        C: Component + Construct<T, I>,
+        T: DeserializeOwned + 'static,
+        I: 'static,
+    {
+        self.register(name, <C as Construct<T, I>>::construct)
+    }
+
+    /// Register a component built from its scene value by `f`
+    ///
+    /// Components implementing [`Construct`] can use [`register_construct`](Self::register_construct)
+    /// instead, ex. `.register_construct::<PositionComponent, (f32, f32, f32), _>("position")`;
+    /// `f` covers the rest, such as closures chaining [`With`](crate::With) calls.
+    pub fn register<T, C>(
+        self,
+        name: impl Into<String>,
+        f: impl Fn(T) -> C + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: DeserializeOwned + 'static,
+        C: Component,
+    {
+        self.register_assembler(name, move |cmd, entity, value| {
+            cmd.add_component(entity, f(value))
+        })
+    }
+
+    /// Register an assembly function, such as an `AssembleWgpu` or `AssembleWinit` method,
+    /// called with the scene value
+    ///
+    /// ex. `.register_assembler("winit_window", |cmd, entity, ()| cmd.assemble_winit_window(entity))`
+    pub fn register_assembler<T>(
+        mut self,
+        name: impl Into<String>,
+        f: impl Fn(&mut CommandBuffer, Entity, T) + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let f = Arc::new(f);
+        self.assemblers.insert(
+            name.into(),
+            Box::new(move |value: Value| {
+                let value = value.into_rust::<T>()?;
+                let f = f.clone();
+                let record: SceneRecorder = Box::new(move |cmd, entity| f(cmd, entity, value));
+                Ok(record)
+            }),
+        );
+        self
+    }
+}
+
+/// Named set of components that entities and other templates can extend
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct SceneTemplate {
+    pub extends: Option<String>,
+    pub components: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct SceneEntity {
+    /// Name other entities in the scene can use to refer to this one,
+    /// also assembled as a [`NameComponent`](crate::NameComponent)
+    pub name: Option<String>,
+    pub template: Option<String>,
+    /// Name of the entity to parent this one to
+    pub parent: Option<String>,
+    pub components: BTreeMap<String, Value>,
+}
+
+/// Data-driven entity assembly, loaded from RON
+///
+/// Components are listed by their [`SceneRegistry`] name. Entities and templates may extend a template,
+/// inheriting its components; their own components override inherited ones,
+/// with struct-like values merged field by field.
+///
+/// ```ron
+/// Scene(
+///     templates: {
+///         "body": (components: { "position": (0.0, 0.0, 0.0) }),
+///         "mover": (extends: Some("body"), components: { "velocity": (1.0, 0.0, 0.0) }),
+///     },
+///     entities: [
+///         (name: Some("player"), template: Some("mover")),
+///         (parent: Some("player"), template: Some("body"), components: { "position": (0.0, 1.0, 0.0) }),
+///     ],
+/// )
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Scene {
+    pub templates: BTreeMap<String, SceneTemplate>,
+    pub entities: Vec<SceneEntity>,
+}
+
+impl Scene {
+    pub fn from_ron(ron: &str) -> Result<Self, SceneError> {
+        Ok(ron::from_str(ron)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        Scene::from_ron(&std::fs::read_to_string(path)?)
+    }
+
+    /// Record the scene's entities into `cmd`, returning the named entities
+    ///
+    /// Templates, component names, entity references and component values
+    /// are all validated before anything is recorded.
+    pub fn spawn(
+        &self,
+        registry: &SceneRegistry,
+        cmd: &mut CommandBuffer,
+    ) -> Result<BTreeMap<String, Entity>, SceneError> {
+        let components = self
+            .entities
+            .iter()
+            .map(|entity| self.resolve(entity.template.as_deref(), &entity.components, &mut vec![]))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(name) = components
+            .iter()
+            .flat_map(BTreeMap::keys)
+            .find(|name| !registry.assemblers.contains_key(*name))
+        {
+            return Err(SceneError::MissingComponent(name.clone()));
+        }
+
+        let mut names = BTreeMap::<&str, usize>::default();
+        for (i, entity) in self.entities.iter().enumerate() {
+            if let Some(name) = &entity.name {
+                if names.insert(name.as_str(), i).is_some() {
+                    return Err(SceneError::DuplicateEntity(name.clone()));
+                }
+            }
+        }
+
+        for parent in self.entities.iter().flat_map(|entity| &entity.parent) {
+            if !names.contains_key(parent.as_str()) {
+                return Err(SceneError::MissingEntity(parent.clone()));
+            }
+        }
+
+        let recorders = components
+            .into_iter()
+            .map(|components| {
+                components
+                    .into_iter()
+                    .map(|(component, value)| {
+                        registry.assemblers[&component](value)
+                            .map_err(|error| SceneError::Component { component, error })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let entities = self
+            .entities
+            .iter()
+            .map(|_| cmd.push(()))
+            .collect::<Vec<_>>();
+
+        for ((scene_entity, entity), recorders) in
+            self.entities.iter().zip(entities.iter()).zip(recorders)
+        {
+            if let Some(name) = &scene_entity.name {
+                assemble_name(cmd, *entity, name.clone());
+            }
+
+            if let Some(parent) = &scene_entity.parent {
+                assemble_parent(cmd, *entity, entities[names[parent.as_str()]]);
+            }
+
+            for record in recorders {
+                record(cmd, *entity);
+            }
+        }
+
+        Ok(names
+            .into_iter()
+            .map(|(name, i)| (name.to_string(), entities[i]))
+            .collect())
+    }
+
+    /// Flatten template inheritance into a single component map
+    fn resolve(
+        &self,
+        extends: Option<&str>,
+        components: &BTreeMap<String, Value>,
+        stack: &mut Vec<String>,
+    ) -> Result<BTreeMap<String, Value>, SceneError> {
+        let mut resolved = if let Some(name) = extends {
+            if let Some(start) = stack.iter().position(|template| template == name) {
+                let mut cycle = stack.split_off(start);
+                cycle.push(name.to_string());
+                return Err(SceneError::TemplateCycle(cycle));
+            }
+
+            let template = self
+                .templates
+                .get(name)
+                .ok_or_else(|| SceneError::MissingTemplate(name.to_string()))?;
+
+            stack.push(name.to_string());
+            let resolved =
+                self.resolve(template.extends.as_deref(), &template.components, stack)?;
+            stack.pop();
+            resolved
+        } else {
+            BTreeMap::default()
+        };
+
+        for (name, value) in components {
+            let value = match resolved.remove(name) {
+                Some(base) => merge_value(base, value.clone()),
+                None => value.clone(),
+            };
+            resolved.insert(name.clone(), value);
+        }
+
+        Ok(resolved)
+    }
+}
+
+/// Overlay `value` onto `base`, recursing into maps so overrides can replace individual fields
+fn merge_value(base: Value, value: Value) -> Value {
+    match (base, value) {
+        (Value::Map(mut base), Value::Map(value)) => {
+            for (key, value) in value.iter() {
+                let merged = match base.remove(key) {
+                    Some(base) => merge_value(base, value.clone()),
+                    None => value.clone(),
+                };
+                base.insert(key.clone(), merged);
+            }
+            Value::Map(base)
+        }
+        (_, value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use legion::{IntoQuery, Resources, World};
+
+    use super::*;
+    use crate::{ParentComponent, ReadWriteLock, RwLock, Usage};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+    struct Point {
+        x: f32,
+        y: f32,
+    }
+
+    enum Position {}
+
+    type PositionComponent = Usage<Position, RwLock<Point>>;
+
+    struct Tag;
+
+    fn registry() -> SceneRegistry {
+        SceneRegistry::default()
+            .register_construct::<PositionComponent, Point, _>("position")
+            .register_assembler("tag", |cmd, entity, ()| cmd.add_component(entity, Tag))
+    }
+
+    fn spawn(scene: &str) -> (World, BTreeMap<String, Entity>) {
+        let mut world = World::default();
+        let mut cmd = CommandBuffer::new(&world);
+        let entities = Scene::from_ron(scene)
+            .unwrap()
+            .spawn(&registry(), &mut cmd)
+            .unwrap();
+        cmd.flush(&mut world, &mut Resources::default());
+        (world, entities)
+    }
+
+    /// Spawn a scene expected to fail, checking that nothing was recorded
+    fn spawn_error(scene: &str) -> SceneError {
+        let world = World::default();
+        let mut cmd = CommandBuffer::new(&world);
+        let error = Scene::from_ron(scene)
+            .unwrap()
+            .spawn(&registry(), &mut cmd)
+            .unwrap_err();
+        assert!(cmd.is_empty());
+        error
+    }
+
+    fn position(world: &World, entity: Entity) -> Point {
+        *<&PositionComponent>::query()
+            .get(world, entity)
+            .unwrap()
+            .read()
+    }
+
+    #[test]
+    fn templates_are_inherited_and_overridden_by_field() {
+        let (world, entities) = spawn(
+            r#"(
+                templates: {
+                    "body": (components: { "position": (x: 1.0, y: 2.0), "tag": () }),
+                    "raised": (extends: Some("body"), components: { "position": (y: 3.0) }),
+                },
+                entities: [
+                    (name: Some("raised"), template: Some("raised")),
+                    (name: Some("child"), parent: Some("raised"), components: { "position": (x: 5.0, y: 6.0) }),
+                ],
+            )"#,
+        );
+
+        let raised = entities["raised"];
+        assert_eq!(position(&world, raised), Point { x: 1.0, y: 3.0 });
+        assert!(<&Tag>::query().get(&world, raised).is_ok());
+
+        let child = entities["child"];
+        assert_eq!(position(&world, child), Point { x: 5.0, y: 6.0 });
+        assert!(<&Tag>::query().get(&world, child).is_err());
+
+        let parent = <&ParentComponent>::query().get(&world, child).unwrap();
+        assert_eq!(parent.target(), raised);
+    }
+
+    #[test]
+    fn template_cycles_are_rejected() {
+        let error = spawn_error(
+            r#"(
+                templates: {
+                    "first": (extends: Some("second")),
+                    "second": (extends: Some("first")),
+                },
+                entities: [(template: Some("first"))],
+            )"#,
+        );
+
+        assert!(
+            matches!(error, SceneError::TemplateCycle(cycle) if cycle == ["first", "second", "first"])
+        );
+    }
+
+    #[test]
+    fn missing_references_are_rejected() {
+        let error = spawn_error(r#"(entities: [(template: Some("missing"))])"#);
+        assert!(matches!(error, SceneError::MissingTemplate(name) if name == "missing"));
+
+        let error = spawn_error(r#"(entities: [(components: { "missing": () })])"#);
+        assert!(matches!(error, SceneError::MissingComponent(name) if name == "missing"));
+
+        let error = spawn_error(r#"(entities: [(parent: Some("missing"))])"#);
+        assert!(matches!(error, SceneError::MissingEntity(name) if name == "missing"));
+    }
+
+    #[test]
+    fn invalid_component_values_are_rejected() {
+        let error = spawn_error(
+            r#"(entities: [
+                (components: { "tag": () }),
+                (components: { "position": "origin" }),
+            ])"#,
+        );
+
+        assert!(
+            matches!(error, SceneError::Component { component, .. } if component == "position")
+        );
+    }
+}
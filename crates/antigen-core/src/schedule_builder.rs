@@ -0,0 +1,304 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use legion::systems::ParallelRunnable;
+
+use crate::{
+    immutable_schedule::{into_immutable_runnable, runnable_name},
+    ImmutableRunnable, ImmutableSchedule, Serial,
+};
+
+/// Labels and ordering constraints carried by a runnable for use with [`ScheduleBuilder`]
+///
+/// Every runnable is implicitly labeled with its name,
+/// so built-in systems can be referenced without explicit labels.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SystemOrdering {
+    pub labels: Vec<String>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+impl SystemOrdering {
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    pub fn before(mut self, label: impl Into<String>) -> Self {
+        self.before.push(label.into());
+        self
+    }
+
+    pub fn after(mut self, label: impl Into<String>) -> Self {
+        self.after.push(label.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleBuildError {
+    /// A `before` or `after` constraint references a label no runnable carries
+    MissingLabel { runnable: String, label: String },
+    /// Ordering constraints form a cycle, listed in run order
+    Cycle(Vec<String>),
+}
+
+impl Display for ScheduleBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleBuildError::MissingLabel { runnable, label } => {
+                write!(f, "{} is ordered against missing label {}", runnable, label)
+            }
+            ScheduleBuildError::Cycle(runnables) => {
+                write!(f, "Ordering cycle: {}", runnables.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleBuildError {}
+
+/// Builds an [`ImmutableSchedule`] from labeled runnables and `before` / `after` constraints
+///
+/// Runnables are grouped into layers, where each layer runs after every layer it depends on.
+/// Each layer becomes an [`ImmutableSchedule<Parallel>`](crate::Parallel) - which batches its children by lock access
+/// as usual - and the layers are run in sequence by the resulting [`ImmutableSchedule<Serial>`].
+///
+/// ```ignore
+/// let schedule = ScheduleBuilder::default()
+///     .add_system(surface_size_system(ChangeTracker::default()))
+///     .add_system(reconfigure_surfaces_system(ChangeTracker::default()).after("surface_size"))
+///     .add_system(
+///         clamp_surface_size_system()
+///             .after("surface_size")
+///             .before("reconfigure_surfaces"),
+///     )
+///     .build()?;
+/// ```
+#[derive(Default)]
+pub struct ScheduleBuilder {
+    runnables: Vec<Box<dyn ImmutableRunnable>>,
+}
+
+impl ScheduleBuilder {
+    pub fn add_system<S: ParallelRunnable + 'static>(mut self, system: S) -> Self {
+        self.runnables.push(into_immutable_runnable(system));
+        self
+    }
+
+    /// Resolve ordering constraints into a schedule tree
+    pub fn build(self) -> Result<ImmutableSchedule<Serial>, ScheduleBuildError> {
+        let layers = self.layers()?;
+
+        let mut runnables = self.runnables.into_iter().map(Some).collect::<Vec<_>>();
+
+        let mut schedule = ImmutableSchedule::serial();
+        for layer in layers {
+            if let [index] = layer[..] {
+                schedule = schedule.add_runnable(runnables[index].take().unwrap());
+                continue;
+            }
+
+            let mut parallel = ImmutableSchedule::parallel();
+            for index in layer {
+                parallel = parallel.add_runnable(runnables[index].take().unwrap());
+            }
+            schedule = schedule.add_system(parallel);
+        }
+
+        Ok(schedule)
+    }
+
+    /// Group runnable indices into dependency layers, preserving insertion order within each
+    fn layers(&self) -> Result<Vec<Vec<usize>>, ScheduleBuildError> {
+        let names = self
+            .runnables
+            .iter()
+            .map(|runnable| runnable_name(&**runnable))
+            .collect::<Vec<_>>();
+
+        let mut labels = BTreeMap::<&str, Vec<usize>>::default();
+        for (i, runnable) in self.runnables.iter().enumerate() {
+            labels.entry(names[i].as_str()).or_default().push(i);
+            for label in &runnable.ordering().labels {
+                labels.entry(label.as_str()).or_default().push(i);
+            }
+        }
+
+        let resolve = |i: usize, label: &String| {
+            labels
+                .get(label.as_str())
+                .ok_or_else(|| ScheduleBuildError::MissingLabel {
+                    runnable: names[i].clone(),
+                    label: label.clone(),
+                })
+        };
+
+        // Edges point from each runnable to those that must run after it
+        let mut successors = vec![BTreeSet::<usize>::default(); self.runnables.len()];
+        for (i, runnable) in self.runnables.iter().enumerate() {
+            let ordering = runnable.ordering();
+
+            for label in &ordering.before {
+                for j in resolve(i, label)? {
+                    if *j != i {
+                        successors[i].insert(*j);
+                    }
+                }
+            }
+
+            for label in &ordering.after {
+                for j in resolve(i, label)? {
+                    if *j != i {
+                        successors[*j].insert(i);
+                    }
+                }
+            }
+        }
+
+        let mut in_degree = vec![0usize; self.runnables.len()];
+        for successor in successors.iter().flatten() {
+            in_degree[*successor] += 1;
+        }
+
+        let mut layers = vec![];
+        let mut remaining = (0..self.runnables.len()).collect::<BTreeSet<_>>();
+        while !remaining.is_empty() {
+            let layer = remaining
+                .iter()
+                .copied()
+                .filter(|i| in_degree[*i] == 0)
+                .collect::<Vec<_>>();
+
+            if layer.is_empty() {
+                let cycle = find_cycle(&successors, &remaining);
+                return Err(ScheduleBuildError::Cycle(
+                    cycle.into_iter().map(|i| names[i].clone()).collect(),
+                ));
+            }
+
+            for i in &layer {
+                remaining.remove(i);
+                for successor in &successors[*i] {
+                    in_degree[*successor] -= 1;
+                }
+            }
+
+            layers.push(layer);
+        }
+
+        Ok(layers)
+    }
+}
+
+/// Find a cycle among `remaining`, every one of which has a predecessor in `remaining`
+fn find_cycle(successors: &[BTreeSet<usize>], remaining: &BTreeSet<usize>) -> Vec<usize> {
+    let predecessor = |node: usize| {
+        remaining
+            .iter()
+            .copied()
+            .find(|candidate| successors[*candidate].contains(&node))
+            .expect("Unresolved runnable has no unresolved predecessor")
+    };
+
+    // Walk backwards until a node repeats
+    let mut path = vec![*remaining.iter().next().unwrap()];
+    loop {
+        let previous = predecessor(*path.last().unwrap());
+
+        if let Some(start) = path.iter().position(|node| *node == previous) {
+            let mut cycle = path.split_off(start);
+            cycle.push(previous);
+            cycle.reverse();
+            return cycle;
+        }
+
+        path.push(previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use legion::systems::SystemBuilder;
+
+    use super::*;
+    use crate::IntoScheduledSystem;
+
+    fn system(name: &'static str) -> impl ParallelRunnable {
+        SystemBuilder::new(name).build(|_, _, _, _| ())
+    }
+
+    #[test]
+    fn find_cycle_lists_cycle_in_run_order() {
+        let successors = vec![
+            BTreeSet::from([1]),
+            BTreeSet::from([2]),
+            BTreeSet::from([0]),
+        ];
+        let remaining = BTreeSet::from([0, 1, 2]);
+
+        assert_eq!(find_cycle(&successors, &remaining), vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn cyclic_constraints_are_rejected() {
+        let error = ScheduleBuilder::default()
+            .add_system(system("a").after("c"))
+            .add_system(system("b").after("a"))
+            .add_system(system("c").after("b"))
+            .build()
+            .err();
+
+        assert_eq!(
+            error,
+            Some(ScheduleBuildError::Cycle(vec![
+                "a".into(),
+                "b".into(),
+                "c".into(),
+                "a".into()
+            ]))
+        );
+    }
+
+    #[test]
+    fn missing_label_is_rejected() {
+        let error = ScheduleBuilder::default()
+            .add_system(system("a").after("missing"))
+            .build()
+            .err();
+
+        assert_eq!(
+            error,
+            Some(ScheduleBuildError::MissingLabel {
+                runnable: "a".into(),
+                label: "missing".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn constraints_resolve_into_layers() {
+        let schedule = ScheduleBuilder::default()
+            .add_system(system("d").after("b").after("c"))
+            .add_system(system("b").after("a"))
+            .add_system(system("c").label("middle").after("a"))
+            .add_system(system("a").before("middle"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            schedule.describe().to_tree(),
+            "\
+[Serial] serial[a, parallel[b, c], d]
+  a
+  [Parallel] parallel[b, c]
+    batch 0: b
+    batch 0: c
+  d
+"
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::fmt::{Display, Write};
+
+use legion::storage::ComponentTypeId;
+
+use crate::LockAccess;
+
+/// Execution mode of an [`ImmutableSchedule`](crate::ImmutableSchedule)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScheduleMode {
+    Single,
+    Serial,
+    Parallel,
+}
+
+impl Display for ScheduleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleMode::Single => write!(f, "Single"),
+            ScheduleMode::Serial => write!(f, "Serial"),
+            ScheduleMode::Parallel => write!(f, "Parallel"),
+        }
+    }
+}
+
+/// Snapshot of a schedule tree, as returned by [`ImmutableRunnable::describe`](crate::ImmutableRunnable::describe)
+///
+/// Systems are leaves with no mode; schedules carry their mode, batches and children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleDescription {
+    pub name: String,
+    pub mode: Option<ScheduleMode>,
+    pub reads: Vec<ComponentTypeId>,
+    pub lock_access: LockAccess,
+    /// Names of the [`RunCriteria`](crate::RunCriteria) gating this runnable
+    pub run_criteria: Vec<String>,
+    /// Indices into `children` grouped by parallel batch, in execution order
+    pub batches: Vec<Vec<usize>>,
+    pub children: Vec<ScheduleDescription>,
+}
+
+impl ScheduleDescription {
+    pub fn is_schedule(&self) -> bool {
+        self.mode.is_some()
+    }
+
+    /// Leaf systems of this tree, depth-first in declaration order
+    pub fn systems(&self) -> Vec<&ScheduleDescription> {
+        if !self.is_schedule() {
+            return vec![self];
+        }
+
+        self.children
+            .iter()
+            .flat_map(ScheduleDescription::systems)
+            .collect()
+    }
+
+    /// Render as an indented plain-text tree
+    ///
+    /// ```text
+    /// [Parallel] parallel[create_window_surfaces, serial[surface_size]]
+    ///   batch 0: create_window_surfaces (reads wgpu::Device)
+    ///   batch 0: [Serial] serial[surface_size]
+    ///     surface_size (reads WindowSizeComponent) [if any_changed<WindowSizeComponent>]
+    /// ```
+    pub fn to_tree(&self) -> String {
+        let mut out = String::new();
+        self.write_tree(&mut out, 0, None);
+        out
+    }
+
+    fn write_tree(&self, out: &mut String, depth: usize, batch: Option<usize>) {
+        write!(out, "{:indent$}", "", indent = depth * 2).unwrap();
+
+        if let Some(batch) = batch {
+            write!(out, "batch {}: ", batch).unwrap();
+        }
+
+        out.push_str(&self.label());
+        out.push('\n');
+
+        for (i, child) in self.children.iter().enumerate() {
+            let batch = if self.mode == Some(ScheduleMode::Parallel) {
+                self.batch_of(i)
+            } else {
+                None
+            };
+            child.write_tree(out, depth + 1, batch);
+        }
+    }
+
+    /// Render as a Graphviz DOT digraph
+    ///
+    /// Dashed edges point from schedules to their children,
+    /// solid edges show the order in which siblings run.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph schedule {\n");
+        let mut next_id = 0;
+        self.write_dot(&mut out, &mut next_id);
+        out.push_str("}\n");
+        out
+    }
+
+    fn write_dot(&self, out: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        let shape = if self.is_schedule() { "ellipse" } else { "box" };
+        writeln!(
+            out,
+            "    n{} [label=\"{}\", shape={}];",
+            id,
+            escape_dot(&self.label()),
+            shape
+        )
+        .unwrap();
+
+        let child_ids = self
+            .children
+            .iter()
+            .map(|child| child.write_dot(out, next_id))
+            .collect::<Vec<_>>();
+
+        for child_id in &child_ids {
+            writeln!(out, "    n{} -> n{} [style=dashed];", id, child_id).unwrap();
+        }
+
+        match self.mode {
+            Some(ScheduleMode::Serial) => {
+                for pair in child_ids.windows(2) {
+                    writeln!(out, "    n{} -> n{};", pair[0], pair[1]).unwrap();
+                }
+            }
+            Some(ScheduleMode::Parallel) => {
+                for pair in self.batches.windows(2) {
+                    for from in &pair[0] {
+                        for to in &pair[1] {
+                            writeln!(out, "    n{} -> n{};", child_ids[*from], child_ids[*to])
+                                .unwrap();
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        id
+    }
+
+    fn label(&self) -> String {
+        let mut label = match self.mode {
+            Some(mode) => format!("[{}] {}", mode, self.name),
+            None => self.name.clone(),
+        };
+
+        if !self.is_schedule() && !self.reads.is_empty() {
+            let reads = self
+                .reads
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(label, " (reads {})", reads).unwrap();
+        }
+
+        if !self.run_criteria.is_empty() {
+            write!(label, " [if {}]", self.run_criteria.join(" && ")).unwrap();
+        }
+
+        label
+    }
+
+    fn batch_of(&self, index: usize) -> Option<usize> {
+        self.batches.iter().position(|batch| batch.contains(&index))
+    }
+}
+
+impl Display for ScheduleDescription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_tree())
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use legion::{
+        systems::{ParallelRunnable, Runnable, SystemBuilder},
+        World,
+    };
+
+    use super::*;
+    use crate::{parallel, serial, ImmutableRunnable, IntoScheduledSystem};
+
+    struct Lock;
+
+    fn system(name: &'static str) -> impl ParallelRunnable {
+        SystemBuilder::new(name).build(|_, _, _, _| ())
+    }
+
+    fn describe() -> ScheduleDescription {
+        let schedule = serial![
+            system("first"),
+            parallel![
+                system("left").writes_lock::<Lock>(),
+                system("right").writes_lock::<Lock>(),
+                system("other"),
+            ],
+            system("last"),
+        ];
+        schedule.describe()
+    }
+
+    #[test]
+    fn tree_shows_batches_of_parallel_children() {
+        assert_eq!(
+            describe().to_tree(),
+            "\
+[Serial] serial[first, parallel[left, right, other], last]
+  first
+  [Parallel] parallel[left, right, other]
+    batch 0: left
+    batch 1: right
+    batch 0: other
+  last
+"
+        );
+    }
+
+    #[test]
+    fn label_replaces_derived_name() {
+        let schedule = serial![system("first"), system("last")].label("setup");
+        assert_eq!(
+            schedule.describe().to_tree(),
+            "[Serial] setup\n  first\n  last\n"
+        );
+    }
+
+    #[test]
+    fn runtime_name_abbreviates_nested_schedules() {
+        let mut schedule = serial![
+            system("first"),
+            parallel![system("left"), system("right")],
+            serial![system("last")].label("teardown"),
+        ];
+        schedule.prepare(&World::default());
+
+        assert_eq!(
+            schedule.name().unwrap().to_string(),
+            "serial[first, parallel[..], teardown]"
+        );
+    }
+
+    #[test]
+    fn dot_orders_serial_children_and_parallel_batches() {
+        assert_eq!(
+            describe().to_dot(),
+            "\
+digraph schedule {
+    n0 [label=\"[Serial] serial[first, parallel[left, right, other], last]\", shape=ellipse];
+    n1 [label=\"first\", shape=box];
+    n2 [label=\"[Parallel] parallel[left, right, other]\", shape=ellipse];
+    n3 [label=\"left\", shape=box];
+    n4 [label=\"right\", shape=box];
+    n5 [label=\"other\", shape=box];
+    n2 -> n3 [style=dashed];
+    n2 -> n4 [style=dashed];
+    n2 -> n5 [style=dashed];
+    n3 -> n4;
+    n5 -> n4;
+    n6 [label=\"last\", shape=box];
+    n0 -> n1 [style=dashed];
+    n0 -> n2 [style=dashed];
+    n0 -> n6 [style=dashed];
+    n1 -> n2;
+    n2 -> n6;
+}
+"
+        );
+    }
+}
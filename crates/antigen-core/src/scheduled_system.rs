@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use legion::{
+    storage::{Component, ComponentTypeId},
+    systems::{
+        CommandBuffer, ParallelRunnable, Resource, ResourceTypeId, Resources, Runnable, SystemId,
+        UnsafeResources,
+    },
+    world::{ArchetypeAccess, WorldId},
+    World,
+};
+
+use crate::{
+    immutable_schedule::runnable_name, ImmutableRunnable, LockAccess, Profiler, RunCriteria,
+    ScheduleDescription, SystemOrdering,
+};
+
+/// A [`ParallelRunnable`] annotated with scheduling metadata for use in an [`ImmutableSchedule`](crate::ImmutableSchedule)
+///
+/// Plain systems added to a schedule are wrapped in one of these automatically.
+pub struct ScheduledSystem {
+    runnable: Box<dyn ParallelRunnable>,
+    lock_access: LockAccess,
+    run_criteria: Vec<RunCriteria>,
+    ordering: SystemOrdering,
+}
+
+impl ScheduledSystem {
+    pub fn new<S: ParallelRunnable + 'static>(system: S) -> Self {
+        ScheduledSystem {
+            runnable: Box::new(system),
+            lock_access: Default::default(),
+            run_criteria: Default::default(),
+            ordering: Default::default(),
+        }
+    }
+
+    /// Declare that this system reads the lock(s) held by component `T`
+    pub fn reads_lock<T: Component>(mut self) -> Self {
+        self.lock_access = self.lock_access.reads::<T>();
+        self
+    }
+
+    /// Declare that this system writes the lock(s) held by component `T`
+    pub fn writes_lock<T: Component>(mut self) -> Self {
+        self.lock_access = self.lock_access.writes::<T>();
+        self
+    }
+
+    /// Declare that this system reads resource `T`
+    pub fn reads_resource<T: Resource>(mut self) -> Self {
+        self.lock_access = self.lock_access.reads_resource::<T>();
+        self
+    }
+
+    /// Declare that this system writes resource `T`
+    pub fn writes_resource<T: Resource>(mut self) -> Self {
+        self.lock_access = self.lock_access.writes_resource::<T>();
+        self
+    }
+
+    /// Only run this system on frames where `criteria` is met
+    pub fn run_if(mut self, criteria: RunCriteria) -> Self {
+        self.run_criteria.push(criteria);
+        self
+    }
+
+    /// Label this system for use in [`ScheduleBuilder`](crate::ScheduleBuilder) ordering constraints
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.ordering = self.ordering.label(label);
+        self
+    }
+
+    /// Run this system before every runnable labeled `label`
+    pub fn before(mut self, label: impl Into<String>) -> Self {
+        self.ordering = self.ordering.before(label);
+        self
+    }
+
+    /// Run this system after every runnable labeled `label`
+    pub fn after(mut self, label: impl Into<String>) -> Self {
+        self.ordering = self.ordering.after(label);
+        self
+    }
+}
+
+impl Runnable for ScheduledSystem {
+    fn name(&self) -> Option<&SystemId> {
+        self.runnable.name()
+    }
+
+    fn reads(&self) -> (&[ResourceTypeId], &[ComponentTypeId]) {
+        self.runnable.reads()
+    }
+
+    fn writes(&self) -> (&[ResourceTypeId], &[ComponentTypeId]) {
+        self.runnable.writes()
+    }
+
+    fn prepare(&mut self, world: &World) {
+        self.runnable.prepare(world)
+    }
+
+    fn accesses_archetypes(&self) -> &ArchetypeAccess {
+        self.runnable.accesses_archetypes()
+    }
+
+    unsafe fn run_unsafe(&mut self, world: &World, resources: &UnsafeResources) {
+        self.runnable.run_unsafe(world, resources)
+    }
+
+    fn command_buffer_mut(&mut self, world: WorldId) -> Option<&mut CommandBuffer> {
+        self.runnable.command_buffer_mut(world)
+    }
+}
+
+impl ImmutableRunnable for ScheduledSystem {
+    fn lock_access(&self) -> &LockAccess {
+        &self.lock_access
+    }
+
+    fn run_criteria(&self) -> &[RunCriteria] {
+        &self.run_criteria
+    }
+
+    fn ordering(&self) -> &SystemOrdering {
+        &self.ordering
+    }
+
+    fn has_pending_commands(&mut self, world: WorldId) -> bool {
+        self.runnable
+            .command_buffer_mut(world)
+            .map(|command_buffer| !command_buffer.is_empty())
+            .unwrap_or(false)
+    }
+
+    fn flush_command_buffers(&mut self, world: &mut World, resources: &mut Resources) {
+        let world_id = world.id();
+        if let Some(command_buffer) = self.runnable.command_buffer_mut(world_id) {
+            if !command_buffer.is_empty() {
+                command_buffer.flush(world, resources);
+            }
+        }
+    }
+
+    fn describe(&self) -> ScheduleDescription {
+        ScheduleDescription {
+            name: runnable_name(self),
+            mode: None,
+            reads: self.reads().1.to_vec(),
+            lock_access: self.lock_access.clone(),
+            run_criteria: self
+                .run_criteria
+                .iter()
+                .map(|criteria| criteria.name().to_string())
+                .collect(),
+            batches: vec![],
+            children: vec![],
+        }
+    }
+
+    fn short_name(&self) -> String {
+        runnable_name(self)
+    }
+
+    fn set_profiler(&mut self, _profiler: Option<Arc<Profiler>>) {
+        // Timed by the owning schedule
+    }
+}
+
+/// Extension trait for annotating systems with scheduling metadata
+///
+/// ```ignore
+/// parallel![
+///     integrate_position_system().writes_lock::<Position>(),
+///     print_position_system().reads_lock::<Position>(),
+/// ]
+/// ```
+pub trait IntoScheduledSystem: ParallelRunnable + Sized + 'static {
+    fn reads_lock<T: Component>(self) -> ScheduledSystem {
+        ScheduledSystem::new(self).reads_lock::<T>()
+    }
+
+    fn writes_lock<T: Component>(self) -> ScheduledSystem {
+        ScheduledSystem::new(self).writes_lock::<T>()
+    }
+
+    fn reads_resource<T: Resource>(self) -> ScheduledSystem {
+        ScheduledSystem::new(self).reads_resource::<T>()
+    }
+
+    fn writes_resource<T: Resource>(self) -> ScheduledSystem {
+        ScheduledSystem::new(self).writes_resource::<T>()
+    }
+
+    fn run_if(self, criteria: RunCriteria) -> ScheduledSystem {
+        ScheduledSystem::new(self).run_if(criteria)
+    }
+
+    fn label(self, label: impl Into<String>) -> ScheduledSystem {
+        ScheduledSystem::new(self).label(label)
+    }
+
+    fn before(self, label: impl Into<String>) -> ScheduledSystem {
+        ScheduledSystem::new(self).before(label)
+    }
+
+    fn after(self, label: impl Into<String>) -> ScheduledSystem {
+        ScheduledSystem::new(self).after(label)
+    }
+}
+
+impl<S> IntoScheduledSystem for S where S: ParallelRunnable + 'static {}
@@ -0,0 +1,196 @@
+use std::fmt::Display;
+
+use bincode::Options;
+use legion::{serialize::Canon, storage::Component, Registry};
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+
+use crate::{
+    ChildrenComponent, FixedClockComponent, GameClockComponent, ImmutableWorld, NameComponent,
+    ParentComponent, ReadWriteLock, RealClockComponent,
+};
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Ron(ron::Error),
+    Bincode(bincode::Error),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Ron(e) => write!(f, "RON snapshot error: {}", e),
+            SnapshotError::Bincode(e) => write!(f, "Binary snapshot error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<ron::Error> for SnapshotError {
+    fn from(e: ron::Error) -> Self {
+        SnapshotError::Ron(e)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(e: bincode::Error) -> Self {
+        SnapshotError::Bincode(e)
+    }
+}
+
+/// Saves and loads the contents of an [`ImmutableWorld`] in RON or a compact binary format
+///
+/// Only registered component types are saved; all others are skipped.
+/// [`Changed`](crate::Changed), [`Usage`](crate::Usage), [`RwLock`](crate::RwLock)
+/// and [`LazyComponent`](crate::LazyComponent) round-trip transparently,
+/// with lazy components coming back as `Pending` to be recreated by their usual systems.
+///
+/// Entity references, ex. [`ParentComponent`], are preserved across a save and load.
+///
+/// ```ignore
+/// let snapshot = WorldSnapshot::default()
+///     .with_core_components()
+///     .register::<PositionComponent>("position");
+///
+/// let ron = snapshot.to_ron(&world)?;
+/// ...
+/// snapshot.load_ron(&ImmutableWorld::default(), &ron)?;
+/// ```
+#[derive(Default)]
+pub struct WorldSnapshot {
+    registry: Registry<String>,
+    canon: Canon,
+}
+
+impl WorldSnapshot {
+    /// Include `C` in snapshots under the stable name `name`
+    pub fn register<C>(mut self, name: impl Into<String>) -> Self
+    where
+        C: Component + Serialize + for<'de> Deserialize<'de>,
+    {
+        self.registry.register::<C>(name.into());
+        self
+    }
+
+    /// Register the name, hierarchy and clock components provided by antigen-core
+    pub fn with_core_components(self) -> Self {
+        self.register::<NameComponent>("antigen_core::name")
+            .register::<ParentComponent>("antigen_core::parent")
+            .register::<ChildrenComponent>("antigen_core::children")
+            .register::<RealClockComponent>("antigen_core::real_clock")
+            .register::<GameClockComponent>("antigen_core::game_clock")
+            .register::<FixedClockComponent>("antigen_core::fixed_clock")
+    }
+
+    pub fn to_ron(&self, world: &ImmutableWorld) -> Result<String, SnapshotError> {
+        let world = world.read();
+        let serializable = world.as_serializable(legion::any(), &self.registry, &self.canon);
+        Ok(ron::ser::to_string_pretty(
+            &serializable,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn to_bincode(&self, world: &ImmutableWorld) -> Result<Vec<u8>, SnapshotError> {
+        let world = world.read();
+        let serializable = world.as_serializable(legion::any(), &self.registry, &self.canon);
+        Ok(bincode::options().serialize(&serializable)?)
+    }
+
+    /// Spawn the entities saved in `ron` into `world`
+    ///
+    /// Entities keep their saved IDs, so `world` should not already contain them.
+    pub fn load_ron(&self, world: &ImmutableWorld, ron: &str) -> Result<(), SnapshotError> {
+        let mut world = world.write();
+        let mut deserializer = ron::de::Deserializer::from_str(ron)?;
+        self.registry
+            .as_deserialize_into_world(&mut world, &self.canon)
+            .deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(())
+    }
+
+    /// Spawn the entities saved in `bytes` into `world`
+    ///
+    /// Entities keep their saved IDs, so `world` should not already contain them.
+    pub fn load_bincode(&self, world: &ImmutableWorld, bytes: &[u8]) -> Result<(), SnapshotError> {
+        let mut world = world.write();
+        let seed = self
+            .registry
+            .as_deserialize_into_world(&mut world, &self.canon);
+        bincode::options().deserialize_seed(seed, bytes)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use legion::{Entity, IntoQuery};
+
+    use super::*;
+    use crate::{Changed, ChangedTrait, LazyComponent, RwLock, Usage};
+
+    enum Position {}
+
+    type PositionComponent = Usage<Position, Changed<RwLock<(f32, f32)>>>;
+    type LazyValueComponent = RwLock<LazyComponent<u32>>;
+
+    fn snapshot() -> WorldSnapshot {
+        WorldSnapshot::default()
+            .with_core_components()
+            .register::<PositionComponent>("position")
+            .register::<LazyValueComponent>("lazy_value")
+    }
+
+    /// A parent with a position and a ready lazy value, and a child referencing it
+    fn populate(world: &ImmutableWorld) -> (Entity, Entity) {
+        let mut world = world.write();
+        let parent = world.push((
+            PositionComponent::from(Changed::new(RwLock::new((1.0, 2.0)), true)),
+            LazyValueComponent::new(LazyComponent::Ready(7)),
+        ));
+        let child = world.push((ParentComponent::new(parent),));
+        (parent, child)
+    }
+
+    fn assert_restored(world: &ImmutableWorld, parent: Entity, child: Entity) {
+        let world = world.read();
+
+        let (position, lazy_value) = <(&PositionComponent, &LazyValueComponent)>::query()
+            .get(&*world, parent)
+            .unwrap();
+        assert_eq!(*position.read(), (1.0, 2.0));
+        assert!(position.get_changed());
+        assert!(lazy_value.read().is_pending());
+
+        let parent_component = <&ParentComponent>::query().get(&*world, child).unwrap();
+        assert_eq!(parent_component.target(), parent);
+        assert!(world.contains(parent_component.target()));
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let snapshot = snapshot();
+        let world = ImmutableWorld::default();
+        let (parent, child) = populate(&world);
+
+        let ron = snapshot.to_ron(&world).unwrap();
+        let loaded = ImmutableWorld::default();
+        snapshot.load_ron(&loaded, &ron).unwrap();
+
+        assert_restored(&loaded, parent, child);
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let snapshot = snapshot();
+        let world = ImmutableWorld::default();
+        let (parent, child) = populate(&world);
+
+        let bytes = snapshot.to_bincode(&world).unwrap();
+        let loaded = ImmutableWorld::default();
+        snapshot.load_bincode(&loaded, &bytes).unwrap();
+
+        assert_restored(&loaded, parent, child);
+    }
+}
@@ -0,0 +1,62 @@
+use legion::{storage::Component, systems::CommandBuffer, Entity};
+
+use crate::{LazyComponent, ReadWriteLock, RetryPolicy};
+
+/// Attach a [`RetryPolicy`] allowing `max_attempts` retries to each `C` that doesn't have one
+#[legion::system(for_each)]
+pub fn attach_retry_policies<C>(
+    #[state] max_attempts: &usize,
+    cmd: &mut CommandBuffer,
+    entity: &Entity,
+    _component: &C,
+    policy: Option<&RetryPolicy<C>>,
+) where
+    C: Component,
+{
+    if policy.is_none() {
+        cmd.add_component(*entity, RetryPolicy::<C>::new(*max_attempts));
+    }
+}
+
+/// Return failed lazy components to pending while their [`RetryPolicy`] has attempts remaining
+///
+/// Attempts are only restored by [`reset_retries`](crate::reset_retries_system),
+/// so a component that keeps failing further down its chain stops retrying once they run out.
+#[legion::system(par_for_each)]
+pub fn retry_failed<C, T>(component: &C, policy: &RetryPolicy<C>)
+where
+    C: Component + ReadWriteLock<LazyComponent<T>>,
+    T: Send + Sync + 'static,
+{
+    let mut component = component.write();
+    let error = match &*component {
+        LazyComponent::Failed(error) => error,
+        _ => return,
+    };
+
+    if policy.try_attempt() {
+        tracing::warn!(
+            "Retrying {} ({}/{}) after error: {}",
+            std::any::type_name::<C>(),
+            policy.attempts(),
+            policy.max_attempts(),
+            error
+        );
+        component.set_pending();
+    }
+}
+
+/// Restore the attempts of `C`'s [`RetryPolicy`] once `R`, the end result of its chain, is ready
+///
+/// `R` may be `C` itself when nothing is derived from it.
+#[legion::system(par_for_each)]
+pub fn reset_retries<C, R, T>(policy: &RetryPolicy<C>, result: &R)
+where
+    C: Component,
+    R: Component + ReadWriteLock<LazyComponent<T>>,
+    T: Send + Sync + 'static,
+{
+    if result.read().is_ready() {
+        policy.reset();
+    }
+}
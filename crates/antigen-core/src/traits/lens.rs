@@ -0,0 +1,127 @@
+use crate::{peano::Z, ChangedTrait, MappedRwLockReadGuard, MappedRwLockWriteGuard};
+
+/// Lock-free access to a `T` nested inside `Self`
+///
+/// Implemented by transparent wrappers such as [`Usage`](crate::Usage), [`Changed`](crate::Changed)
+/// and [`LazyComponent`](crate::LazyComponent), the latter only reaching `T` when ready.
+/// `I` is a peano index encoding the path to `T`, and is inferred.
+pub trait Project<T, I> {
+    fn project(&self) -> Option<&T>;
+    fn project_mut(&mut self) -> Option<&mut T>;
+}
+
+impl<T> Project<T, Z> for T {
+    fn project(&self) -> Option<&T> {
+        Some(self)
+    }
+
+    fn project_mut(&mut self) -> Option<&mut T> {
+        Some(self)
+    }
+}
+
+/// Locked access to a `T` nested inside `Self` behind a single [`RwLock`](crate::RwLock)
+///
+/// `I` is a peano index encoding the path to `T`, and is inferred.
+/// Prefer calling via [`LensExt`].
+pub trait Lens<T, I> {
+    fn lens_read(&self) -> Option<MappedRwLockReadGuard<T>>;
+    fn lens_write(&self) -> Option<MappedRwLockWriteGuard<T>>;
+}
+
+/// Type-directed access through any stack of `Usage`, `Changed`, `RwLock` and `LazyComponent`
+///
+/// ```ignore
+/// // WindowSizeComponent = Usage<WindowSize, Changed<RwLock<PhysicalSize<u32>>>>
+/// let size = window_size.lens_get::<PhysicalSize<u32>, _>().unwrap();
+///
+/// // SurfaceComponent = RwLock<LazyComponent<Surface>>
+/// if let Some(surface) = surface_component.lens_get::<Surface, _>() {
+///     ...
+/// }
+///
+/// // Inferred from the value
+/// window_size.lens_set(PhysicalSize::new(640, 480));
+/// ```
+///
+/// Returns `None` if the path passes through a [`LazyComponent`](crate::LazyComponent) that isn't ready.
+///
+/// Implemented for every type, so methods are prefixed to avoid shadowing those reached through `Deref`,
+/// such as `get` on a `Changed<Vec<T>>`.
+pub trait LensExt {
+    /// Read-lock and return the nested `T`
+    fn lens_get<T, I>(&self) -> Option<MappedRwLockReadGuard<T>>
+    where
+        Self: Lens<T, I>,
+    {
+        self.lens_read()
+    }
+
+    /// Write-lock and return the nested `T`
+    fn lens_get_mut<T, I>(&self) -> Option<MappedRwLockWriteGuard<T>>
+    where
+        Self: Lens<T, I>,
+    {
+        self.lens_write()
+    }
+
+    /// Overwrite the nested `T` and set the changed flag, returning false if `T` couldn't be reached
+    fn lens_set<T, I>(&self, value: T) -> bool
+    where
+        Self: Lens<T, I> + ChangedTrait,
+    {
+        if let Some(mut target) = self.lens_write() {
+            *target = value;
+            drop(target);
+            self.set_changed(true);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<L> LensExt for L {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Changed, LazyComponent, RwLock, Usage};
+
+    enum Size {}
+
+    /// Shaped like `WindowSizeComponent`
+    type SizeComponent = Usage<Size, Changed<RwLock<(u32, u32)>>>;
+
+    /// Shaped like `SurfaceComponent`
+    type LazyStringComponent = RwLock<LazyComponent<String>>;
+
+    #[test]
+    fn infers_path_through_usage_changed_and_lock() {
+        let size = SizeComponent::from(Changed::new(RwLock::new((640, 480)), false));
+
+        assert_eq!(*size.lens_get::<(u32, u32), _>().unwrap(), (640, 480));
+
+        assert!(size.lens_set((800, 600)));
+        assert!(size.get_changed());
+        assert_eq!(*size.lens_get::<(u32, u32), _>().unwrap(), (800, 600));
+
+        *size.lens_get_mut::<(u32, u32), _>().unwrap() = (1024, 768);
+        assert_eq!(*size.lens_get::<(u32, u32), _>().unwrap(), (1024, 768));
+    }
+
+    #[test]
+    fn reaches_lazy_value_only_when_ready() {
+        let lazy = LazyStringComponent::new(LazyComponent::Pending);
+        assert!(lazy.lens_get::<String, _>().is_none());
+
+        lazy.write().set_ready("ready".to_string());
+        assert_eq!(lazy.lens_get::<String, _>().unwrap().as_str(), "ready");
+    }
+
+    #[test]
+    fn does_not_shadow_deref_methods() {
+        let values = Changed::new(vec![1, 2, 3], false);
+        assert_eq!(values.get(1), Some(&2));
+    }
+}
@@ -1,7 +1,9 @@
 mod read_write_lock;
 mod construct;
 mod with;
+mod lens;
 
 pub use read_write_lock::*;
 pub use construct::*;
 pub use with::*;
+pub use lens::*;
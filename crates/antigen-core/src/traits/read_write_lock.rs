@@ -1,6 +1,10 @@
 use std::{ops::Deref, sync::Arc};
 
-pub use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::{peano::S, Lens, Project};
+
+pub use parking_lot::{
+    MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
 
 /// Trait for newtypes that wrap a [`parking_lot::RwLock`]
 pub trait ReadWriteLock<T> {
@@ -66,7 +70,23 @@ where
     }
 }
 
+/// Lens implementation
+impl<T, I, N> Lens<T, S<I>> for RwLock<N>
+where
+    N: Project<T, I>,
+{
+    fn lens_read(&self) -> Option<MappedRwLockReadGuard<T>> {
+        RwLockReadGuard::try_map(self.read(), |inner| inner.project()).ok()
+    }
+
+    fn lens_write(&self) -> Option<MappedRwLockWriteGuard<T>> {
+        RwLockWriteGuard::try_map(self.write(), |inner| inner.project_mut()).ok()
+    }
+}
+
 /// Implement ReadWriteLock for a newtype struct
+///
+/// Prefer `#[derive(ReadWriteLock)]` for new code.
 #[macro_export]
 macro_rules! impl_read_write_lock {
     ($outer:ty, $field:tt, $inner:ty) => {
@@ -75,9 +95,17 @@ macro_rules! impl_read_write_lock {
                 self.$field.read()
             }
 
+            fn try_read(&self) -> Option<$crate::RwLockReadGuard<$inner>> {
+                self.$field.try_read()
+            }
+
             fn write(&self) -> $crate::RwLockWriteGuard<$inner> {
                 self.$field.write()
             }
+
+            fn try_write(&self) -> Option<$crate::RwLockWriteGuard<$inner>> {
+                self.$field.try_write()
+            }
         }
     };
 }
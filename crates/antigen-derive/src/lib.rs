@@ -0,0 +1,379 @@
+//! Derive macros for antigen-core newtypes
+//!
+//! Each derive forwards to a single inner field: the only field of a newtype,
+//! or the field marked `#[antigen(inner)]`. Any other fields are initialized via [`Default`].
+//!
+//! ```ignore
+//! #[derive(Debug, Default, ReadWriteLock, Construct, With)]
+//! pub struct Position(RwLock<(f32, f32, f32)>);
+//!
+//! let position = Position::construct((0.0, 1.0, 0.0));
+//! ```
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Generics, Ident, Member,
+    Type,
+};
+
+/// Implement `ReadWriteLock<V>` for every `V` the inner field locks,
+/// forwarding `read`, `write`, `try_read` and `try_write`
+#[proc_macro_derive(ReadWriteLock, attributes(antigen))]
+pub fn derive_read_write_lock(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, expand_read_write_lock)
+}
+
+/// Implement `Construct` from the inner field's type at `Z`,
+/// and from anything the inner field can be constructed from at `S<I>`
+#[proc_macro_derive(Construct, attributes(antigen))]
+pub fn derive_construct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, expand_construct)
+}
+
+/// Implement `With` at `S<I>` for anything the inner field accepts,
+/// leaving `Z` free for type-specific `With` impls
+#[proc_macro_derive(With, attributes(antigen))]
+pub fn derive_with(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, expand_with)
+}
+
+/// The field a derive forwards to, alongside the remaining fields
+struct InnerField {
+    member: Member,
+    ty: Type,
+    others: Vec<Member>,
+}
+
+fn expand(
+    input: &DeriveInput,
+    f: impl FnOnce(&DeriveInput, InnerField) -> TokenStream2,
+) -> TokenStream {
+    match inner_field(input) {
+        Ok(inner) => f(input, inner),
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
+fn inner_field(input: &DeriveInput) -> syn::Result<InnerField> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new_spanned(input, "Only structs are supported")),
+    };
+
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        })
+        .collect::<Vec<_>>();
+
+    let marked = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.attrs.iter().any(is_inner_attr))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let index = match (fields.len(), &marked[..]) {
+        (1, []) => 0,
+        (_, [index]) => *index,
+        (_, []) => {
+            return Err(Error::new_spanned(
+                input,
+                "Structs with several fields must mark one #[antigen(inner)]",
+            ))
+        }
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "Only one field may be marked #[antigen(inner)]",
+            ))
+        }
+    };
+
+    Ok(InnerField {
+        member: members[index].clone(),
+        ty: fields.iter().nth(index).unwrap().ty.clone(),
+        others: members
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, member)| member)
+            .collect(),
+    })
+}
+
+fn is_inner_attr(attr: &Attribute) -> bool {
+    attr.path.is_ident("antigen")
+        && attr
+            .parse_args::<Ident>()
+            .map(|ident| ident == "inner")
+            .unwrap_or(false)
+}
+
+/// Copy of `generics` extended with extra type parameters and where predicates
+fn extend_generics(
+    generics: &Generics,
+    params: &[Ident],
+    predicates: &[syn::WherePredicate],
+) -> Generics {
+    let mut generics = generics.clone();
+    for param in params {
+        generics.params.push(parse_quote!(#param));
+    }
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(predicates.iter().cloned());
+    generics
+}
+
+fn expand_read_write_lock(input: &DeriveInput, inner: InnerField) -> TokenStream2 {
+    let name = &input.ident;
+    let InnerField { member, ty, .. } = inner;
+
+    let v: Ident = parse_quote!(__AntigenV);
+    let generics = extend_generics(
+        &input.generics,
+        std::slice::from_ref(&v),
+        &[parse_quote!(#ty: ::antigen_core::ReadWriteLock<#v>)],
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::antigen_core::ReadWriteLock<#v> for #name #ty_generics #where_clause {
+            fn read(&self) -> ::antigen_core::RwLockReadGuard<#v> {
+                <#ty as ::antigen_core::ReadWriteLock<#v>>::read(&self.#member)
+            }
+
+            fn try_read(&self) -> ::core::option::Option<::antigen_core::RwLockReadGuard<#v>> {
+                <#ty as ::antigen_core::ReadWriteLock<#v>>::try_read(&self.#member)
+            }
+
+            fn write(&self) -> ::antigen_core::RwLockWriteGuard<#v> {
+                <#ty as ::antigen_core::ReadWriteLock<#v>>::write(&self.#member)
+            }
+
+            fn try_write(&self) -> ::core::option::Option<::antigen_core::RwLockWriteGuard<#v>> {
+                <#ty as ::antigen_core::ReadWriteLock<#v>>::try_write(&self.#member)
+            }
+        }
+    }
+}
+
+fn expand_construct(input: &DeriveInput, inner: InnerField) -> TokenStream2 {
+    let name = &input.ident;
+    let InnerField { member, ty, others } = inner;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (t, i): (Ident, Ident) = (parse_quote!(__AntigenT), parse_quote!(__AntigenI));
+    let nested_generics = extend_generics(
+        &input.generics,
+        &[t.clone(), i.clone()],
+        &[parse_quote!(#ty: ::antigen_core::Construct<#t, #i>)],
+    );
+    let (nested_impl_generics, _, nested_where_clause) = nested_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::antigen_core::Construct<#ty, ::antigen_core::peano::Z>
+            for #name #ty_generics #where_clause
+        {
+            fn construct(t: #ty) -> Self {
+                Self {
+                    #member: t,
+                    #(#others: ::core::default::Default::default(),)*
+                }
+            }
+        }
+
+        impl #nested_impl_generics ::antigen_core::Construct<#t, ::antigen_core::peano::S<#i>>
+            for #name #ty_generics #nested_where_clause
+        {
+            fn construct(t: #t) -> Self {
+                Self {
+                    #member: <#ty as ::antigen_core::Construct<#t, #i>>::construct(t),
+                    #(#others: ::core::default::Default::default(),)*
+                }
+            }
+        }
+    }
+}
+
+fn expand_with(input: &DeriveInput, inner: InnerField) -> TokenStream2 {
+    let name = &input.ident;
+    let InnerField { member, ty, others } = inner;
+
+    let (t, i): (Ident, Ident) = (parse_quote!(__AntigenT), parse_quote!(__AntigenI));
+    let generics = extend_generics(
+        &input.generics,
+        &[t.clone(), i.clone()],
+        &[parse_quote!(#ty: ::antigen_core::With<#t, #i>)],
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::antigen_core::With<#t, ::antigen_core::peano::S<#i>>
+            for #name #ty_generics #where_clause
+        {
+            fn with(self, t: #t) -> Self {
+                Self {
+                    #member: <#ty as ::antigen_core::With<#t, #i>>::with(self.#member, t),
+                    #(#others: self.#others,)*
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(member: &Member) -> String {
+        quote!(#member).to_string()
+    }
+
+    fn inner_error(input: DeriveInput) -> String {
+        inner_field(&input).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn newtype_forwards_to_only_field() {
+        let input = parse_quote!(
+            struct Position(RwLock<f32>);
+        );
+        let inner = inner_field(&input).unwrap();
+
+        assert_eq!(tokens(&inner.member), "0");
+        assert!(inner.others.is_empty());
+    }
+
+    #[test]
+    fn marked_field_is_inner() {
+        let input = parse_quote!(
+            struct Tracked {
+                generation: usize,
+                #[antigen(inner)]
+                data: RwLock<f32>,
+                flag: bool,
+            }
+        );
+        let inner = inner_field(&input).unwrap();
+
+        assert_eq!(tokens(&inner.member), "data");
+        assert_eq!(
+            inner.others.iter().map(tokens).collect::<Vec<_>>(),
+            ["generation", "flag"]
+        );
+    }
+
+    #[test]
+    fn inner_field_errors() {
+        assert_eq!(
+            inner_error(parse_quote!(
+                struct Unmarked {
+                    data: RwLock<f32>,
+                    flag: bool,
+                }
+            )),
+            "Structs with several fields must mark one #[antigen(inner)]"
+        );
+
+        assert_eq!(
+            inner_error(parse_quote!(
+                struct Ambiguous {
+                    #[antigen(inner)]
+                    data: RwLock<f32>,
+                    #[antigen(inner)]
+                    flag: bool,
+                }
+            )),
+            "Only one field may be marked #[antigen(inner)]"
+        );
+
+        assert_eq!(
+            inner_error(parse_quote!(
+                enum Position {
+                    Origin,
+                }
+            )),
+            "Only structs are supported"
+        );
+    }
+
+    #[test]
+    fn construct_defaults_other_fields() {
+        let input = parse_quote!(
+            struct Tracked<T> {
+                #[antigen(inner)]
+                data: RwLock<T>,
+                flag: bool,
+            }
+        );
+        let inner = inner_field(&input).unwrap();
+
+        let expected = quote! {
+            impl<T> ::antigen_core::Construct<RwLock<T>, ::antigen_core::peano::Z> for Tracked<T> {
+                fn construct(t: RwLock<T>) -> Self {
+                    Self {
+                        data: t,
+                        flag: ::core::default::Default::default(),
+                    }
+                }
+            }
+
+            impl<T, __AntigenT, __AntigenI>
+                ::antigen_core::Construct<__AntigenT, ::antigen_core::peano::S<__AntigenI>>
+                for Tracked<T>
+            where
+                RwLock<T>: ::antigen_core::Construct<__AntigenT, __AntigenI>
+            {
+                fn construct(t: __AntigenT) -> Self {
+                    Self {
+                        data: <RwLock<T> as ::antigen_core::Construct<__AntigenT, __AntigenI>>::construct(t),
+                        flag: ::core::default::Default::default(),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(
+            expand_construct(&input, inner).to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn with_forwards_to_inner_and_keeps_other_fields() {
+        let input = parse_quote!(
+            struct Position(RwLock<f32>);
+        );
+        let inner = inner_field(&input).unwrap();
+
+        let expected = quote! {
+            impl<__AntigenT, __AntigenI> ::antigen_core::With<__AntigenT, ::antigen_core::peano::S<__AntigenI>>
+                for Position
+            where
+                RwLock<f32>: ::antigen_core::With<__AntigenT, __AntigenI>
+            {
+                fn with(self, t: __AntigenT) -> Self {
+                    Self {
+                        0: <RwLock<f32> as ::antigen_core::With<__AntigenT, __AntigenI>>::with(self.0, t),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(expand_with(&input, inner).to_string(), expected.to_string());
+    }
+}
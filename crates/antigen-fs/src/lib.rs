@@ -1,6 +1,10 @@
-use antigen_core::{Construct, LazyComponent, ReadWriteLock, RwLock, Usage};
+use antigen_core::{
+    attach_retry_policies_system, reset_retries_system, retry_failed_system, serial, App,
+    Construct, ImmutableSchedule, LazyComponent, Plugin, ReadWriteLock, RwLock, Serial, Stage,
+    Usage,
+};
 use legion::Entity;
-use std::{fs::File, path::PathBuf};
+use std::{fs::File, marker::PhantomData, path::PathBuf};
 
 pub enum FileBytes {}
 pub enum FileString {}
@@ -51,47 +55,233 @@ pub fn load_files<U: Send + Sync + 'static>(
     path: &Usage<U, PathComponent>,
     file: &Usage<U, FileComponent>,
 ) {
-    let f = if let LazyComponent::Pending = &*file.read() {
-        std::fs::File::open(&*path.read()).unwrap()
-    } else {
+    if !file.read().is_pending() {
         return;
-    };
+    }
 
-    file.write().set_ready(f);
+    let path = path.read();
+    match std::fs::File::open(&*path) {
+        Ok(f) => file.write().set_ready(f),
+        Err(e) => {
+            tracing::error!("Failed to open file {:?}: {}", path, e);
+            file.write().set_failed(e);
+        }
+    }
 }
 
+/// Read opened files into bytes, waiting in `Loading` until the file is open
+///
+/// Failures of the file are passed on to its bytes, and a failure to read is recorded on both,
+/// so that retrying the file also retries the read.
 #[legion::system(par_for_each)]
 pub fn read_file_bytes<U: Send + Sync + 'static>(
     file: &Usage<U, FileComponent>,
     bytes: &Usage<U, FileBytesComponent>,
 ) {
-    let mut drop = false;
-    if let LazyComponent::Ready(f) = &mut *file.write() {
-        let mut buf = Vec::<u8>::default();
-        std::io::Read::read(f, &mut buf).unwrap();
-        bytes.write().set_ready(buf);
-        drop = true;
-    }
+    let error = match &mut *file.write() {
+        LazyComponent::Pending | LazyComponent::Loading => {
+            let mut bytes = bytes.write();
+            if bytes.is_pending() || bytes.is_failed() {
+                bytes.set_loading();
+            }
+            return;
+        }
+        LazyComponent::Failed(e) => {
+            let mut bytes = bytes.write();
+            if bytes.is_pending() || bytes.is_loading() {
+                bytes.set_failed(e.to_string());
+            }
+            return;
+        }
+        LazyComponent::Ready(f) => {
+            let mut buf = Vec::<u8>::default();
+            match std::io::Read::read_to_end(f, &mut buf) {
+                Ok(_) => {
+                    bytes.write().set_ready(buf);
+                    None
+                }
+                Err(e) => {
+                    tracing::error!("Failed to read file bytes: {}", e);
+                    bytes.write().set_failed(e.to_string());
+                    Some(e)
+                }
+            }
+        }
+        LazyComponent::Dropped => return,
+    };
 
-    if drop {
-        file.write().set_dropped();
+    match error {
+        Some(e) => file.write().set_failed(e),
+        None => file.write().set_dropped(),
     }
 }
 
+/// Read opened files into strings, waiting in `Loading` until the file is open
+///
+/// Failures of the file are passed on to its string, and a failure to read is recorded on both,
+/// so that retrying the file also retries the read.
 #[legion::system(par_for_each)]
 pub fn read_file_string<U: Send + Sync + 'static>(
     file: &Usage<U, FileComponent>,
     string: &Usage<U, FileStringComponent>,
 ) {
-    let mut drop = false;
-    if let LazyComponent::Ready(f) = &mut *file.write() {
-        let mut buf = String::default();
-        std::io::Read::read_to_string(f, &mut buf).unwrap();
-        string.write().set_ready(buf);
-        drop = true;
+    let error = match &mut *file.write() {
+        LazyComponent::Pending | LazyComponent::Loading => {
+            let mut string = string.write();
+            if string.is_pending() || string.is_failed() {
+                string.set_loading();
+            }
+            return;
+        }
+        LazyComponent::Failed(e) => {
+            let mut string = string.write();
+            if string.is_pending() || string.is_loading() {
+                string.set_failed(e.to_string());
+            }
+            return;
+        }
+        LazyComponent::Ready(f) => {
+            let mut buf = String::default();
+            match std::io::Read::read_to_string(f, &mut buf) {
+                Ok(_) => {
+                    string.write().set_ready(buf);
+                    None
+                }
+                Err(e) => {
+                    tracing::error!("Failed to read file string: {}", e);
+                    string.write().set_failed(e.to_string());
+                    Some(e)
+                }
+            }
+        }
+        LazyComponent::Dropped => return,
+    };
+
+    match error {
+        Some(e) => file.write().set_failed(e),
+        None => file.write().set_dropped(),
     }
+}
+
+/// Open and read `U`-tagged files, retrying each file up to `retry_attempts` times
+///
+/// The schedule is labeled `"load_files"` for ordering dependent systems against.
+pub struct FilePlugin<U> {
+    retry_attempts: usize,
+    _phantom: PhantomData<fn() -> U>,
+}
+
+impl<U> FilePlugin<U> {
+    pub fn new(retry_attempts: usize) -> Self {
+        FilePlugin {
+            retry_attempts,
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<U: Send + Sync + 'static, E> Plugin<E> for FilePlugin<U> {
+    fn build(&self, app: App<E>) -> App<E> {
+        app.add_system(
+            Stage::PreUpdate,
+            attach_retry_policies_system::<Usage<U, FileComponent>>(self.retry_attempts),
+        )
+        .add_system(Stage::PreUpdate, load_files_schedule::<U>())
+    }
+}
+
+/// Open, retry and read `U`-tagged files, restoring retry attempts once their bytes or string are read
+fn load_files_schedule<U: Send + Sync + 'static>() -> ImmutableSchedule<Serial> {
+    serial![
+        load_files_system::<U>(),
+        retry_failed_system::<Usage<U, FileComponent>, File>(),
+        read_file_bytes_system::<U>(),
+        read_file_string_system::<U>(),
+        reset_retries_system::<Usage<U, FileComponent>, Usage<U, FileBytesComponent>, Vec<u8>>(),
+        reset_retries_system::<Usage<U, FileComponent>, Usage<U, FileStringComponent>, String>(),
+    ]
+    .label("load_files")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use antigen_core::{ImmutableWorld, LazyReadWriteLock, RetryPolicy};
+    use legion::{systems::CommandBuffer, IntoQuery};
+    use std::time::Duration;
+
+    enum Test {}
+    enum TestString {}
+
+    fn load(world: &ImmutableWorld) {
+        let mut bytes = load_files_schedule::<Test>();
+        let mut string = load_files_schedule::<TestString>();
+        for _ in 0..4 {
+            bytes.execute_and_flush(world);
+            string.execute_and_flush(world);
+        }
+    }
+
+    #[test]
+    fn reads_file_contents() {
+        let contents = "antigen-fs test file\n".repeat(1024);
+        let path = std::env::temp_dir().join(format!("antigen-fs-{}", std::process::id()));
+        std::fs::write(&path, &contents).unwrap();
+
+        let world = ImmutableWorld::default();
+        let bytes_entity = world.write().push(());
+        let string_entity = world.write().push(());
+
+        let mut cmd = CommandBuffer::new(&world.read());
+        assemble_file_bytes::<Test>(&mut cmd, bytes_entity, path.clone());
+        assemble_file_string::<TestString>(&mut cmd, string_entity, path.clone());
+        cmd.flush(&mut world.write(), &mut Default::default());
+
+        load(&world);
+        std::fs::remove_file(&path).unwrap();
+
+        let world = world.read();
+        let bytes = <&Usage<Test, FileBytesComponent>>::query()
+            .get(&*world, bytes_entity)
+            .unwrap();
+        let string = <&Usage<TestString, FileStringComponent>>::query()
+            .get(&*world, string_entity)
+            .unwrap();
+
+        assert_eq!(*bytes.read_ready().unwrap(), contents.as_bytes());
+        assert_eq!(*string.read_ready().unwrap(), contents);
+    }
+
+    #[test]
+    fn failed_reads_stop_retrying_after_max_attempts() {
+        let world = ImmutableWorld::default();
+        let entity = world.write().push(());
+
+        // Directories open successfully, but fail to read
+        let mut cmd = CommandBuffer::new(&world.read());
+        assemble_file_bytes::<Test>(&mut cmd, entity, std::env::temp_dir());
+        cmd.add_component(
+            entity,
+            RetryPolicy::<Usage<Test, FileComponent>>::new(2).with_backoff(Duration::ZERO),
+        );
+        cmd.flush(&mut world.write(), &mut Default::default());
+
+        let mut schedule = load_files_schedule::<Test>();
+        for _ in 0..8 {
+            schedule.execute_and_flush(&world);
+        }
+
+        let world = world.read();
+        let (file, bytes, policy) = <(
+            &Usage<Test, FileComponent>,
+            &Usage<Test, FileBytesComponent>,
+            &RetryPolicy<Usage<Test, FileComponent>>,
+        )>::query()
+        .get(&*world, entity)
+        .unwrap();
 
-    if drop {
-        file.write().set_dropped();
+        assert!(file.read().is_failed());
+        assert!(bytes.read().is_failed());
+        assert_eq!(policy.attempts(), 2);
     }
 }
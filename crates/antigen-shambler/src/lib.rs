@@ -1,7 +1,10 @@
-use std::path::PathBuf;
+use std::{marker::PhantomData, path::PathBuf};
 
-use antigen_core::{Construct, LazyComponent, ReadWriteLock, RwLock, Usage};
-use antigen_fs::{assemble_file_string, FileStringComponent};
+use antigen_core::{
+    attach_retry_policies_system, reset_retries_system, retry_failed_system, serial, App,
+    Construct, LazyComponent, Plugin, ReadWriteLock, RwLock, Stage, Usage,
+};
+use antigen_fs::{assemble_file_string, FilePlugin, FileStringComponent};
 use legion::{systems::CommandBuffer, Entity};
 use shambler::GeoMap;
 
@@ -19,17 +22,84 @@ pub fn assemble_map_file<U: Send + Sync + 'static>(
     assemble_file_string::<U>(cmd, entity, path);
 }
 
+/// Parse loaded map files, waiting in `Loading` until the file string is read
+///
+/// Failures of the file string are passed on to its map,
+/// and a map that failed upstream returns to `Loading` once its file is retried.
 #[legion::system(par_for_each)]
 pub fn parse_map_file<U: Send + Sync + 'static>(
     file_string: &Usage<U, FileStringComponent>,
     map_file: &Usage<U, MapFileComponent>,
 ) {
-    let file_string = file_string.read();
-    if map_file.read().is_pending() {
-        if let LazyComponent::Ready(string) = &*file_string {
-            let map = string.parse::<shambler::shalrath::repr::Map>().unwrap();
-            let map = GeoMap::from(map);
-            map_file.write().set_ready(map)
+    match &*file_string.read() {
+        LazyComponent::Pending | LazyComponent::Loading => {
+            let mut map_file = map_file.write();
+            if map_file.is_pending() || map_file.is_failed() {
+                map_file.set_loading();
+            }
         }
+        LazyComponent::Ready(string) => {
+            let mut map_file = map_file.write();
+            if !(map_file.is_pending() || map_file.is_loading()) {
+                return;
+            }
+
+            match string.parse::<shambler::shalrath::repr::Map>() {
+                Ok(map) => map_file.set_ready(GeoMap::from(map)),
+                Err(e) => {
+                    tracing::error!("Failed to parse map file: {:?}", e);
+                    map_file.set_failed(format!("Failed to parse map file: {:?}", e));
+                }
+            }
+        }
+        LazyComponent::Failed(e) => {
+            let mut map_file = map_file.write();
+            if map_file.is_pending() || map_file.is_loading() {
+                map_file.set_failed(format!("Failed to load map file: {}", e));
+            }
+        }
+        LazyComponent::Dropped => (),
+    }
+}
+
+/// Load and parse `U`-tagged map files, retrying each file and parse up to `retry_attempts` times
+///
+/// Includes a [`FilePlugin<U>`]. The parse schedule is labeled `"parse_map_files"`.
+pub struct MapFilePlugin<U> {
+    retry_attempts: usize,
+    _phantom: PhantomData<fn() -> U>,
+}
+
+impl<U> MapFilePlugin<U> {
+    pub fn new(retry_attempts: usize) -> Self {
+        MapFilePlugin {
+            retry_attempts,
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<U: Send + Sync + 'static, E> Plugin<E> for MapFilePlugin<U> {
+    fn build(&self, app: App<E>) -> App<E> {
+        let retry_attempts = self.retry_attempts;
+        app.add_plugin(FilePlugin::<U>::new(retry_attempts))
+            .add_system(
+                Stage::PreUpdate,
+                attach_retry_policies_system::<Usage<U, MapFileComponent>>(retry_attempts),
+            )
+            .add_system(
+                Stage::PreUpdate,
+                serial![
+                    retry_failed_system::<Usage<U, MapFileComponent>, GeoMap>(),
+                    parse_map_file_system::<U>(),
+                    reset_retries_system::<
+                        Usage<U, MapFileComponent>,
+                        Usage<U, MapFileComponent>,
+                        GeoMap,
+                    >(),
+                ]
+                .label("parse_map_files")
+                .after("load_files"),
+            )
     }
 }
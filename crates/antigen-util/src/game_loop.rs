@@ -0,0 +1,278 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Cooperative shutdown flag shared between a [`GameLoop`] and the threads that control it
+#[derive(Debug, Default, Clone)]
+pub struct ShutdownToken(Arc<AtomicBool>);
+
+impl ShutdownToken {
+    /// Request shutdown; the loop exits once its current frame completes
+    pub fn shutdown(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress through the current timestep, in the range `0.0..1.0`
+///
+/// Updated by the [`GameLoop`] after each frame, and readable from any thread
+/// for interpolating between the previous and current tick's state.
+#[derive(Debug, Default, Clone)]
+pub struct InterpolationAlpha(Arc<AtomicU32>);
+
+impl InterpolationAlpha {
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, alpha: f32) {
+        self.0.store(alpha.to_bits(), Ordering::Relaxed)
+    }
+}
+
+/// Passed to the tick closure of a [`GameLoop`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TickInfo {
+    /// Number of ticks run before this one
+    pub index: u64,
+    /// Fixed duration simulated by this tick
+    pub timestep: Duration,
+    /// Whether this tick is catching up on time lost to a slow frame
+    pub catching_up: bool,
+}
+
+/// Tick counters and timing jitter measured over the lifetime of a [`GameLoop`]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct GameLoopStatistics {
+    pub ticks: u64,
+    pub frames: u64,
+    /// Ticks discarded after hitting the catch-up limit
+    pub dropped_ticks: u64,
+    /// Mean absolute deviation of frame intervals from the timestep
+    pub mean_jitter: Duration,
+    pub max_jitter: Duration,
+}
+
+/// Fixed-timestep loop driver
+///
+/// Elapsed time is accumulated and consumed in `timestep`-sized ticks,
+/// running at most `max_catch_up` ticks per frame before discarding the remainder.
+/// Between frames, the loop sleeps until `spin_threshold` before the next tick is due,
+/// then spins for the remainder to avoid oversleeping.
+///
+/// ```ignore
+/// let shutdown = ShutdownToken::default();
+/// let mut game_loop = GameLoop::new(GAME_TICK_DURATION).with_shutdown_token(shutdown.clone());
+/// std::thread::spawn(move || game_loop.run(|tick| tick_schedule.execute(&world)));
+/// ...
+/// shutdown.shutdown();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameLoop {
+    timestep: Duration,
+    max_catch_up: u32,
+    spin_threshold: Duration,
+    shutdown: ShutdownToken,
+    alpha: InterpolationAlpha,
+    statistics: GameLoopStatistics,
+}
+
+impl GameLoop {
+    /// Create a loop ticking every `timestep`, which must be non-zero
+    pub fn new(timestep: Duration) -> Self {
+        assert!(
+            !timestep.is_zero(),
+            "GameLoop timestep must be greater than zero"
+        );
+
+        GameLoop {
+            timestep,
+            max_catch_up: 5,
+            spin_threshold: Duration::from_millis(2),
+            shutdown: Default::default(),
+            alpha: Default::default(),
+            statistics: Default::default(),
+        }
+    }
+
+    /// Maximum number of ticks run in a single frame
+    pub fn with_max_catch_up(mut self, max_catch_up: u32) -> Self {
+        self.max_catch_up = max_catch_up.max(1);
+        self
+    }
+
+    /// Remaining wait below which the loop spins instead of sleeping
+    pub fn with_spin_threshold(mut self, spin_threshold: Duration) -> Self {
+        self.spin_threshold = spin_threshold;
+        self
+    }
+
+    pub fn with_shutdown_token(mut self, shutdown: ShutdownToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    pub fn shutdown_token(&self) -> ShutdownToken {
+        self.shutdown.clone()
+    }
+
+    pub fn alpha(&self) -> InterpolationAlpha {
+        self.alpha.clone()
+    }
+
+    pub fn statistics(&self) -> GameLoopStatistics {
+        self.statistics
+    }
+
+    /// Run `tick` at a fixed rate until shutdown is requested, returning the final statistics
+    pub fn run(&mut self, mut tick: impl FnMut(&TickInfo)) -> GameLoopStatistics {
+        let mut accumulator = self.timestep;
+        let mut last_frame = Instant::now();
+        let mut total_jitter = Duration::ZERO;
+
+        while !self.shutdown.is_shutdown() {
+            let now = Instant::now();
+            let interval = now - last_frame;
+            last_frame = now;
+
+            if self.statistics.frames > 0 {
+                let jitter = interval.max(self.timestep) - interval.min(self.timestep);
+                total_jitter += jitter;
+                self.statistics.max_jitter = self.statistics.max_jitter.max(jitter);
+                self.statistics.mean_jitter =
+                    total_jitter / u32::try_from(self.statistics.frames).unwrap_or(u32::MAX);
+            }
+
+            accumulator += interval;
+
+            let mut frame_ticks = 0;
+            while accumulator >= self.timestep {
+                if frame_ticks == self.max_catch_up {
+                    let dropped = accumulator.as_nanos() / self.timestep.as_nanos();
+                    self.statistics.dropped_ticks += dropped as u64;
+                    accumulator = Duration::from_nanos(
+                        (accumulator.as_nanos() % self.timestep.as_nanos()) as u64,
+                    );
+                    break;
+                }
+
+                tick(&TickInfo {
+                    index: self.statistics.ticks,
+                    timestep: self.timestep,
+                    catching_up: frame_ticks > 0,
+                });
+
+                accumulator -= self.timestep;
+                frame_ticks += 1;
+                self.statistics.ticks += 1;
+            }
+
+            self.statistics.frames += 1;
+            self.alpha
+                .set(accumulator.as_secs_f32() / self.timestep.as_secs_f32());
+
+            // Account for time spent ticking when waiting for the next
+            let elapsed = now.elapsed();
+            if let Some(remaining) = self.timestep.checked_sub(accumulator + elapsed) {
+                wait(remaining, self.spin_threshold);
+            }
+        }
+
+        self.statistics
+    }
+}
+
+/// Sleep for most of `duration`, then spin for the final `spin_threshold`
+pub fn wait(duration: Duration, spin_threshold: Duration) {
+    let deadline = Instant::now() + duration;
+
+    if let Some(sleep) = duration.checked_sub(spin_threshold) {
+        std::thread::sleep(sleep);
+    }
+
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "GameLoop timestep must be greater than zero")]
+    fn zero_timestep_is_rejected() {
+        GameLoop::new(Duration::ZERO);
+    }
+
+    #[test]
+    fn shutdown_before_run_skips_every_frame() {
+        let mut game_loop = GameLoop::new(Duration::from_millis(1));
+        game_loop.shutdown_token().shutdown();
+
+        let statistics = game_loop.run(|_| panic!("Ticked after shutdown"));
+
+        assert_eq!(statistics, GameLoopStatistics::default());
+    }
+
+    #[test]
+    fn shutdown_exits_once_frame_completes() {
+        let mut game_loop = GameLoop::new(Duration::from_millis(1)).with_max_catch_up(1);
+        let shutdown = game_loop.shutdown_token();
+
+        let mut indices = vec![];
+        let statistics = game_loop.run(|tick| {
+            indices.push(tick.index);
+            if tick.index == 4 {
+                shutdown.shutdown();
+            }
+        });
+
+        assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+        assert_eq!(statistics.ticks, 5);
+        assert_eq!(statistics.frames, 5);
+        assert!(statistics.mean_jitter <= statistics.max_jitter);
+        assert_eq!(game_loop.statistics(), statistics);
+    }
+
+    #[test]
+    fn slow_frame_catches_up_then_drops_remainder() {
+        let timestep = Duration::from_millis(1);
+        let mut game_loop = GameLoop::new(timestep).with_max_catch_up(3);
+        let shutdown = game_loop.shutdown_token();
+
+        let mut catching_up = vec![];
+        let statistics = game_loop.run(|tick| {
+            catching_up.push(tick.catching_up);
+            assert_eq!(tick.timestep, timestep);
+
+            match tick.index {
+                0 => std::thread::sleep(timestep * 5),
+                3 => shutdown.shutdown(),
+                _ => (),
+            }
+        });
+
+        // One tick in the first frame, then three to catch up on the five lost to sleeping
+        assert_eq!(catching_up, vec![false, false, true, true]);
+        assert_eq!(statistics.ticks, 4);
+        assert_eq!(statistics.frames, 2);
+        assert!(statistics.dropped_ticks >= 2);
+        assert!(statistics.max_jitter >= timestep * 4);
+
+        let alpha = game_loop.alpha().get();
+        assert!((0.0..1.0).contains(&alpha));
+    }
+}
@@ -1,5 +1,12 @@
+mod game_loop;
+
+pub use game_loop::*;
+
 use std::time::{Duration, Instant};
 
+/// Busy-wait loop running `f` at most once per `duration`
+///
+/// Prefer [`GameLoop`], which doesn't drift under load and can be shut down.
 pub fn spin_loop(duration: Duration, mut f: impl FnMut()) -> ! {
     loop {
         let ts = Instant::now();
@@ -9,4 +16,3 @@ pub fn spin_loop(duration: Duration, mut f: impl FnMut()) -> ! {
         }
     }
 }
-
@@ -1,39 +1,53 @@
 use antigen_core::{
-    AddIndirectComponent, AsUsage, Changed, ChangedFlag, Construct, LazyComponent, Usage, With,
+    register_component_hooks, AddIndirectComponent, AsUsage, Changed, ChangedFlag,
+    ComponentHooks, Construct, ImmutableWorld, LazyComponent, ReadWriteLock, Usage, With,
 };
+use antigen_winit::WindowComponent;
 
-use legion::{storage::Component, Entity, World};
+use legion::{storage::Component, Entity};
 use wgpu::{Adapter, Backends, BufferAddress, BufferDescriptor, Device, DeviceDescriptor, ImageCopyTextureBase, ImageDataLayout, Instance, Queue, SamplerDescriptor, ShaderModuleDescriptor, ShaderModuleDescriptorSpirV, Surface, SurfaceConfiguration, TextureDescriptor, TextureFormat, TextureUsages, TextureViewDescriptor, util::BufferInitDescriptor};
 
 use std::path::Path;
 
 use crate::{
-    BindGroupComponent, BindGroupLayoutComponent, BufferComponent, BufferDescriptorComponent,
-    BufferInitDescriptorComponent, BufferWriteComponent, CommandBuffersComponent,
-    ComputePipelineComponent, PipelineLayoutComponent, RenderAttachmentTextureView,
-    RenderAttachmentTextureViewDescriptor, RenderBundleComponent, RenderPipelineComponent,
-    SamplerComponent, SamplerDescriptorComponent, ShaderModuleComponent,
+    drop_window_surface, BindGroupComponent, BindGroupLayoutComponent, BufferComponent,
+    BufferDescriptorComponent, BufferInitDescriptorComponent, BufferWriteComponent,
+    CommandBuffersComponent, ComputePipelineComponent, PipelineLayoutComponent,
+    RenderAttachmentTextureView, RenderAttachmentTextureViewDescriptor, RenderBundleComponent,
+    RenderPipelineComponent, SamplerComponent, SamplerDescriptorComponent, ShaderModuleComponent,
     ShaderModuleDescriptorComponent, ShaderModuleDescriptorSpirVComponent, SurfaceComponent,
     SurfaceConfigurationComponent, SurfaceTextureComponent, TextureComponent,
     TextureDescriptorComponent, TextureViewComponent, TextureViewDescriptorComponent,
     TextureWriteComponent,
 };
 
-/// Create an entity to hold an Instance, Adapter, Device and Queue
-pub fn assemble_wgpu_entity(
-    world: &mut World,
+/// Insert an Instance, Adapter, Device and Queue into the world's resources,
+/// and register hooks to drop window surfaces alongside their windows
+pub fn assemble_wgpu_resources(
+    world: &ImmutableWorld,
     instance: Instance,
     adapter: Adapter,
     device: Device,
     queue: Queue,
-) -> Entity {
-    world.push((instance, adapter, device, queue))
+) {
+    let resources = world.resources();
+    resources.insert(instance);
+    resources.insert(adapter);
+    resources.insert(device);
+    resources.insert(queue);
+
+    register_component_hooks(
+        &mut world.write(),
+        ComponentHooks::<WindowComponent>::default()
+            .on_drop(drop_window_surface)
+            .on_remove(drop_window_surface),
+    );
 }
 
-/// Retrieve WGPU settings from environment variables, and use them to create an entity
-/// holding an Instance, Adapter, Device, and Queue
-pub fn assemble_wgpu_entity_from_env(
-    world: &mut World,
+/// Retrieve WGPU settings from environment variables, and use them to create
+/// an Instance, Adapter, Device, and Queue in the world's resources
+pub fn assemble_wgpu_resources_from_env(
+    world: &ImmutableWorld,
     device_desc: &DeviceDescriptor,
     compatible_surface: Option<&Surface>,
     trace_path: Option<&Path>,
@@ -41,7 +55,7 @@ pub fn assemble_wgpu_entity_from_env(
     let backend_bits = wgpu::util::backend_bits_from_env().unwrap_or(Backends::PRIMARY);
 
     let instance = Instance::new(backend_bits);
-    println!("Created WGPU instance: {:#?}\n", instance);
+    tracing::debug!("Created WGPU instance: {:?}", instance);
 
     let adapter = pollster::block_on(wgpu::util::initialize_adapter_from_env_or_default(
         &instance,
@@ -51,15 +65,15 @@ pub fn assemble_wgpu_entity_from_env(
     .expect("Failed to acquire WGPU adapter");
 
     let adapter_info = adapter.get_info();
-    println!("Acquired WGPU adapter: {:#?}\n", adapter_info);
+    tracing::info!("Acquired WGPU adapter: {:?}", adapter_info);
 
     let (device, queue) =
         pollster::block_on(adapter.request_device(device_desc, trace_path)).unwrap();
 
-    println!("Acquired WGPU device: {:#?}\n", device);
-    println!("Acquired WGPU queue: {:#?}\n", queue);
+    tracing::debug!("Acquired WGPU device: {:?}", device);
+    tracing::debug!("Acquired WGPU queue: {:?}", queue);
 
-    assemble_wgpu_entity(world, instance, adapter, device, queue);
+    assemble_wgpu_resources(world, instance, adapter, device, queue);
 }
 
 /// [`CommandBuffer`] extension trait containing WGPU-specific assembly methods
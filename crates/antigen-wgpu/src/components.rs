@@ -1,5 +1,6 @@
 use antigen_core::{
-    Changed, LazyComponent, ReadWriteLock, RwLock, RwLockReadGuard, RwLockWriteGuard, Usage,
+    ChangeTick, Changed, LazyComponent, ReadWriteLock, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    Usage,
 };
 
 use wgpu::{
@@ -91,6 +92,7 @@ pub type BufferComponent = RwLock<LazyComponent<Buffer>>;
 // Buffer write operation
 pub struct BufferWriteComponent<T> {
     offset: RwLock<BufferAddress>,
+    change_tick: ChangeTick,
     _phantom: PhantomData<T>,
 }
 
@@ -108,15 +110,22 @@ impl<T> BufferWriteComponent<T> {
     pub fn new(offset: BufferAddress) -> Self {
         BufferWriteComponent {
             offset: RwLock::new(offset),
+            change_tick: Default::default(),
             _phantom: Default::default(),
         }
     }
+
+    /// Last generation of the source data written by this operation
+    pub fn change_tick(&self) -> &ChangeTick {
+        &self.change_tick
+    }
 }
 
 // Texture write operation
 pub struct TextureWriteComponent<T> {
     image_copy_texture: RwLock<ImageCopyTextureBase<()>>,
     image_data_layout: RwLock<wgpu::ImageDataLayout>,
+    change_tick: ChangeTick,
     _phantom: PhantomData<T>,
 }
 
@@ -148,9 +157,15 @@ impl<T> TextureWriteComponent<T> {
         TextureWriteComponent {
             image_copy_texture: RwLock::new(image_copy_texture),
             image_data_layout: RwLock::new(image_data_layout),
+            change_tick: Default::default(),
             _phantom: Default::default(),
         }
     }
+
+    /// Last generation of the source texels written by this operation
+    pub fn change_tick(&self) -> &ChangeTick {
+        &self.change_tick
+    }
 }
 
 // WGPU shader module descriptor
@@ -18,7 +18,11 @@ pub use systems::*;
 pub use to_bytes::*;
 pub use wgpu;
 
-use antigen_core::{ImmutableSchedule, ImmutableWorld, ReadWriteLock, Serial, parallel, serial, single};
+use antigen_core::{
+    serial, single, App, CascadePolicy, ChangeTracker, Changed, ImmutableResources,
+    ImmutableSchedule, ImmutableWorld, IntoScheduledSystem, Plugin, ReadWriteLock, ScheduleBuilder,
+    Serial,
+};
 use wgpu::BufferAddress;
 
 // Return the size of type T in bytes, respresented as a BufferAddress
@@ -26,30 +30,73 @@ pub fn buffer_size_of<T>() -> BufferAddress {
     std::mem::size_of::<T>() as BufferAddress
 }
 
+/// Registers the indirect components used by wgpu systems and demos,
+/// so that references to despawned surfaces and staging belts are removed and reported
+///
+/// `Usage`-tagged buffer and texture references are generic,
+/// and should be registered via [`App::add_indirect_component`] by the code that assembles them.
+pub struct WgpuIndirectPlugin;
+
+impl<E> Plugin<E> for WgpuIndirectPlugin {
+    fn build(&self, app: App<E>) -> App<E> {
+        app.add_indirect_component::<Changed<StagingBeltComponent>>(CascadePolicy::Remove)
+            .add_indirect_component::<CommandBuffersComponent>(CascadePolicy::Remove)
+            .add_indirect_component::<SurfaceConfigurationComponent>(CascadePolicy::Remove)
+            .add_indirect_component::<RenderAttachmentTextureView>(CascadePolicy::Remove)
+    }
+}
+
 // Submit comomand buffers, present surface textures, and drop texture views
-pub fn submit_and_present_schedule() -> ImmutableSchedule<Serial> {
+pub fn submit_and_present_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
-        submit_command_buffers_system(),
+        submit_command_buffers_system(resources.clone()),
         surface_texture_present_system()
         surface_texture_view_drop_system()
     ]
 }
 
+/// Create window surfaces, and resize and reconfigure them to match their windows
+///
+/// Downstream crates can order their own systems against
+/// `create_window_surfaces`, `surface_size` and `reconfigure_surfaces`
+/// before passing the result to [`winit_event_handler_with`].
+pub fn window_surfaces_schedule(resources: &ImmutableResources) -> ScheduleBuilder {
+    ScheduleBuilder::default()
+        .add_system(create_window_surfaces_system(resources.clone()))
+        .add_system(surface_size_system(ChangeTracker::default()))
+        .add_system(
+            reconfigure_surfaces_system(resources.clone(), ChangeTracker::default())
+                .after("surface_size"),
+        )
+}
+
 /// Extend an event loop closure with wgpu resource handling
-pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl EventLoopHandler<T> {
+///
+/// `resources` must belong to the world the handler is run against.
+pub fn winit_event_handler<T: Clone>(
+    resources: &ImmutableResources,
+    f: impl EventLoopHandler<T>,
+) -> impl EventLoopHandler<T> {
+    winit_event_handler_with(resources, window_surfaces_schedule(resources), f)
+}
+
+/// Extend an event loop closure with wgpu resource handling,
+/// using a custom [`window_surfaces_schedule`]
+pub fn winit_event_handler_with<T: Clone>(
+    resources: &ImmutableResources,
+    window_surfaces_schedule: ScheduleBuilder,
+    mut f: impl EventLoopHandler<T>,
+) -> impl EventLoopHandler<T> {
     let mut staging_belt_manager = StagingBeltManager::new();
 
-    let mut window_surfaces_schedule = parallel![
-        create_window_surfaces_system(),
-        serial![
-            surface_size_system()
-            reconfigure_surfaces_system(),
-        ]
-    ];
+    let mut window_surfaces_schedule = window_surfaces_schedule
+        .build()
+        .unwrap_or_else(|e| panic!("Invalid window surfaces schedule: {}", e));
 
-    let mut surface_textures_views_schedule = single![surface_textures_views_system()];
+    let mut surface_textures_views_schedule =
+        single![surface_textures_views_system(resources.clone())];
 
-    let mut reset_surface_config_changed_schedule = single![reset_surface_config_changed_system()];
+    let mut submit_and_present_schedule = submit_and_present_schedule(resources);
 
     move |world: &ImmutableWorld,
           event: Event<'static, T>,
@@ -77,10 +124,9 @@ pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl Ev
         match event {
             Event::MainEventsCleared => {
                 staging_belt_flush_thread_local(&world.read(), &mut staging_belt_manager);
-                reset_surface_config_changed_schedule.execute(world);
             }
             Event::RedrawEventsCleared => {
-                submit_and_present_schedule().execute(world);
+                submit_and_present_schedule.execute(world);
                 staging_belt_recall_thread_local(
                     &world.read(),
                     &mut staging_belt_manager,
@@ -1,7 +1,7 @@
 use antigen_core::{
-    impl_read_write_lock, AddIndirectComponent, Changed, ChangedTrait, GetIndirect,
-    IndirectComponent, LazyComponent, ReadWriteLock, RwLock, RwLockReadGuard, RwLockWriteGuard,
-    Usage,
+    impl_read_write_lock, AddIndirectComponent, ChangeTick, Changed, ChangedTrait, GetIndirect,
+    ImmutableResources, IndirectComponent, LazyComponent, LazyReadWriteLock, ReadWriteLock, RwLock,
+    RwLockReadGuard, RwLockWriteGuard, Usage,
 };
 use legion::{world::SubWorld, Entity, IntoQuery, World};
 use wgpu::{
@@ -106,6 +106,7 @@ impl StagingBeltComponent {
 pub struct StagingBeltWriteComponent<T> {
     offset: RwLock<BufferAddress>,
     size: RwLock<BufferSize>,
+    change_tick: ChangeTick,
     _phantom: PhantomData<T>,
 }
 
@@ -134,9 +135,15 @@ impl<T> StagingBeltWriteComponent<T> {
         StagingBeltWriteComponent {
             offset: RwLock::new(offset),
             size: RwLock::new(size),
+            change_tick: Default::default(),
             _phantom: Default::default(),
         }
     }
+
+    /// Last generation of the source data written by this operation
+    pub fn change_tick(&self) -> &ChangeTick {
+        &self.change_tick
+    }
 }
 
 pub fn assemble_staging_belt(
@@ -177,7 +184,7 @@ pub fn create_staging_belt_thread_local(
             let staging_belt_id =
                 staging_belt_manager.create_staging_belt(*staging_belt.chunk_size());
             staging_belt.write().set_ready(staging_belt_id);
-            println!("Created staging belt with ID {:?}", staging_belt_id);
+            tracing::debug!("Created staging belt with ID {:?}", staging_belt_id);
         }
     })
 }
@@ -191,79 +198,103 @@ pub fn staging_belt_write<
     V: ToBytes,
 >(
     world: &SubWorld,
+    #[state] resources: &ImmutableResources,
     entity: &Entity,
     staging_belt_write: &StagingBeltWriteComponent<L>,
     staging_belt: &IndirectComponent<Changed<StagingBeltComponent>>,
 ) {
     let entity = *entity;
 
-    let staging_belt = world.get_indirect(staging_belt).unwrap();
+    let staging_belt = match world.get_indirect(staging_belt) {
+        Ok(staging_belt) => staging_belt,
+        Err(e) => {
+            tracing::warn!("Failed to resolve {:?}: {}", staging_belt, e);
+            return;
+        }
+    };
 
     let offset = *ReadWriteLock::<BufferAddress>::read(staging_belt_write);
     let size = *ReadWriteLock::<BufferSize>::read(staging_belt_write);
 
+    let resources = resources.clone();
     staging_belt.map(move |world, staging_belt_manager| {
-        let device = if let Some(device) = <&Device>::query().iter(world).next() {
+        let device = if let Some(device) = resources.get_or_warn::<Device>() {
             device
         } else {
             return;
         };
 
-        let (
-            data_component,
-            staging_belt,
-            buffer,
-            command_buffers,
-        ) = if let Ok(components) = <(
-            &Changed<L>,
-            &IndirectComponent<Changed<StagingBeltComponent>>,
-            &IndirectComponent<Usage<T, BufferComponent>>,
-            &IndirectComponent<CommandBuffersComponent>,
-        )>::query()
-            .get(world, entity) {
+        let (staging_belt_write, data_component, staging_belt, buffer, command_buffers) =
+            if let Ok(components) = <(
+                &StagingBeltWriteComponent<L>,
+                &Changed<L>,
+                &IndirectComponent<Changed<StagingBeltComponent>>,
+                &IndirectComponent<Usage<T, BufferComponent>>,
+                &IndirectComponent<CommandBuffersComponent>,
+            )>::query()
+            .get(world, entity)
+            {
                 components
             } else {
                 return;
             };
 
-        let staging_belt_component = world.get_indirect(staging_belt).unwrap();
-        let buffer = world.get_indirect(buffer).unwrap();
-        let command_buffers = world.get_indirect(command_buffers).unwrap();
+        let staging_belt_component = match world.get_indirect(staging_belt) {
+            Ok(staging_belt_component) => staging_belt_component,
+            Err(e) => {
+                tracing::warn!("Failed to resolve {:?}: {}", staging_belt, e);
+                return;
+            }
+        };
+        let buffer = match world.get_indirect(buffer) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                tracing::warn!("Failed to resolve {:?}: {}", buffer, e);
+                return;
+            }
+        };
+        let command_buffers = match world.get_indirect(command_buffers) {
+            Ok(command_buffers) => command_buffers,
+            Err(e) => {
+                tracing::warn!("Failed to resolve {:?}: {}", command_buffers, e);
+                return;
+            }
+        };
 
-        if data_component.get_changed() {
-            let staging_belt = staging_belt_component.read();
-            let staging_belt = if let LazyComponent::Ready(staging_belt) = &*staging_belt {
+        if staging_belt_write.change_tick().has_changed(data_component) {
+            let staging_belt = if let Some(staging_belt) = staging_belt_component.read_ready() {
                 staging_belt
             } else {
                 return;
             };
 
-            let buffer = buffer.read();
-            let buffer = if let LazyComponent::Ready(buffer) = &*buffer {
+            let buffer = if let Some(buffer) = buffer.read_ready() {
                 buffer
             } else {
                 return;
             };
 
+            staging_belt_write.change_tick().observe(data_component);
+
             let data = data_component.read();
             let bytes = data.to_bytes();
 
             let mut encoder =
                 device.create_command_encoder(&CommandEncoderDescriptor { label: None });
 
-            println!(
-                    "Writing {} bytes to {} buffer at offset {} with size {} via staging belt with id {:?}",
-                    bytes.len(),
-                    std::any::type_name::<T>(),
-                    offset,
-                    size,
-                    staging_belt,
-                );
+            tracing::trace!(
+                "Writing {} bytes to {} buffer at offset {} with size {} via {:?}",
+                bytes.len(),
+                std::any::type_name::<T>(),
+                offset,
+                size,
+                staging_belt,
+            );
 
             staging_belt_manager.write_buffer(
-                device,
+                &device,
                 &mut encoder,
-                buffer,
+                &buffer,
                 offset,
                 size,
                 &*staging_belt,
@@ -272,7 +303,6 @@ pub fn staging_belt_write<
 
             command_buffers.write().push(encoder.finish());
 
-            data_component.set_changed(false);
             staging_belt_component.set_changed(true);
         }
     });
@@ -296,14 +326,13 @@ pub fn staging_belt_finish_thread_local(
             return;
         }
 
-        let staging_belt = staging_belt.read();
-        let staging_belt = if let LazyComponent::Ready(staging_belt) = &*staging_belt {
+        let staging_belt = if let Some(staging_belt) = staging_belt.read_ready() {
             staging_belt
         } else {
             return;
         };
-        staging_belt_manager.finish(staging_belt);
-        println!("Finished staging belt with id {:?}", staging_belt);
+        staging_belt_manager.finish(&staging_belt);
+        tracing::trace!("Finished staging belt with id {:?}", staging_belt);
     });
 }
 
@@ -316,16 +345,17 @@ pub fn staging_belt_recall_thread_local(
             return;
         }
 
-        let staging_belt = staging_belt_component.read();
-        let staging_belt = if let LazyComponent::Ready(staging_belt) = &*staging_belt {
+        let staging_belt = if let Some(staging_belt) = staging_belt_component.read_ready() {
             staging_belt
         } else {
             return;
         };
 
         // Ignore resulting future - this assumes the wgpu device is being polled in wait mode
-        let _ = staging_belt_manager.recall(staging_belt);
+        let _ = staging_belt_manager.recall(&staging_belt);
+
+        // The flag only marks writes made this frame, which have now been recalled
         staging_belt_component.set_changed(false);
-        println!("Recalled staging belt with id {:?}", staging_belt);
+        tracing::trace!("Recalled staging belt with id {:?}", staging_belt);
     });
 }
@@ -11,89 +11,110 @@ use crate::{
 };
 
 use antigen_core::{
-    Changed, ChangedTrait, GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock, Usage,
+    ChangeTracker, Changed, ChangedTrait, GetIndirect, ImmutableResources, IndirectComponent,
+    LazyReadWriteLock, ReadWriteLock, Usage,
 };
 use antigen_winit::{WindowComponent, WindowEntityMap, WindowEventComponent, WindowSizeComponent};
 
-use legion::{world::SubWorld, IntoQuery};
+use legion::{world::SubWorld, Entity, IntoQuery, World};
 use wgpu::{
     util::DeviceExt, Adapter, Device, ImageCopyTextureBase, ImageDataLayout, Instance, Maintain,
-    Queue, Surface,
+    Queue,
 };
 
-#[legion::system(par_for_each)]
-pub fn device_poll(device: &Device, #[state] maintain: &Maintain) {
-    device.poll(*maintain)
+#[legion::system]
+pub fn device_poll(#[state] resources: &ImmutableResources, #[state] maintain: &Maintain) {
+    if let Some(device) = resources.get_or_warn::<Device>() {
+        device.poll(*maintain)
+    }
 }
 
 // Initialize pending surfaces that share an entity with a window
 #[legion::system(for_each)]
-#[read_component(wgpu::Device)]
-#[read_component(wgpu::Adapter)]
-#[read_component(wgpu::Instance)]
 pub fn create_window_surfaces(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
     window_component: &WindowComponent,
     surface_configuration_component: &SurfaceConfigurationComponent,
     surface_component: &SurfaceComponent,
 ) {
-    if let LazyComponent::Ready(window) = &*window_component.read() {
-        let adapter = <&Adapter>::query().iter(world).next().unwrap();
-        let device = <&Device>::query().iter(world).next().unwrap();
+    if !surface_component.read().is_pending() {
+        return;
+    }
 
-        if ReadWriteLock::<LazyComponent<Surface>>::read(surface_component).is_pending() {
-            let instance = <&Instance>::query().iter(world).next().unwrap();
-            let surface = unsafe { instance.create_surface(window) };
-            let mut config = surface_configuration_component.write();
+    let window = if let Some(window) = window_component.read_ready() {
+        window
+    } else {
+        return;
+    };
 
-            let window_size = window.inner_size();
-            config.width = window_size.width;
-            config.height = window_size.height;
+    let instance = if let Some(instance) = resources.get_or_warn::<Instance>() {
+        instance
+    } else {
+        return;
+    };
+    let adapter = if let Some(adapter) = resources.get_or_warn::<Adapter>() {
+        adapter
+    } else {
+        return;
+    };
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
-            config.format = surface
-                .get_preferred_format(adapter)
-                .expect("Surface is incompatible with adapter");
+    let surface = unsafe { instance.create_surface(&*window) };
+    let mut config = surface_configuration_component.write();
 
-            surface.configure(device, &config);
+    let window_size = window.inner_size();
+    config.width = window_size.width;
+    config.height = window_size.height;
 
-            ReadWriteLock::<LazyComponent<Surface>>::write(surface_component).set_ready(surface);
-        }
-    }
+    config.format = if let Some(format) = surface.get_preferred_format(&adapter) {
+        format
+    } else {
+        tracing::error!("Failed to create surface: Surface is incompatible with adapter");
+        surface_component
+            .write()
+            .set_failed("Surface is incompatible with adapter");
+        return;
+    };
+
+    surface.configure(&device, &config);
+
+    surface_component.write().set_ready(surface);
 }
 
-// Initialize pending surfaces that share an entity with a window
+// Reconfigure ready surfaces whose configuration has changed
 #[legion::system(for_each)]
-#[read_component(wgpu::Device)]
-#[read_component(wgpu::Adapter)]
-#[read_component(wgpu::Instance)]
 pub fn reconfigure_surfaces(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     surface_config: &SurfaceConfigurationComponent,
     surface_component: &SurfaceComponent,
 ) {
-    let device = <&Device>::query().iter(world).next().unwrap();
-
-    let surface_read = surface_component.read();
-    let surface = if let LazyComponent::Ready(surface) = &*surface_read {
+    let surface = if let Some(surface) = surface_component.read_ready() {
         surface
     } else {
         return;
     };
 
-    if !surface_config.get_changed() {
+    if !tracker.has_changed(*entity, surface_config) {
         return;
     }
 
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
+
+    tracker.observe(*entity, surface_config);
+
     let config = surface_config.read();
     if config.width > 0 && config.height > 0 {
-        surface.configure(device, &config);
-    }
-}
-
-#[legion::system(par_for_each)]
-pub fn reset_surface_config_changed(surface_config: &SurfaceConfigurationComponent) {
-    if surface_config.get_changed() {
-        surface_config.set_changed(false);
+        surface.configure(&device, &config);
     }
 }
 
@@ -107,8 +128,7 @@ pub fn surface_texture_query(world: &legion::world::SubWorld, entity: &legion::E
         return;
     };
 
-    let surface = surface.read();
-    let surface = if let LazyComponent::Ready(surface) = &*surface {
+    let surface = if let Some(surface) = surface.read_ready() {
         surface
     } else {
         return;
@@ -156,10 +176,12 @@ pub fn surface_texture_view_query(world: &legion::world::SubWorld, entity: &legi
 
 #[legion::system(par_for_each)]
 pub fn surface_size(
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     window_size: &WindowSizeComponent,
     surface_configuration_component: &SurfaceConfigurationComponent,
 ) {
-    if window_size.get_changed() {
+    if tracker.observe(*entity, window_size) {
         let window_size = *window_size.read();
         let mut surface_configuration = surface_configuration_component.write();
         surface_configuration.width = window_size.width;
@@ -195,149 +217,192 @@ pub fn surface_texture_view_drop(
     surface_texture.set_changed(false);
 }
 
-/// Create pending untagged shader modules, recreating them if a Changed flag is set
+// Drop the surface and any unpresented surface texture of a window once it's dropped or removed
+pub fn drop_window_surface(world: &World, _: &mut legion::systems::CommandBuffer, entity: Entity) {
+    if let Ok(surface_texture) = <&SurfaceTextureComponent>::query().get(world, entity) {
+        if surface_texture.write().take().is_some() {
+            surface_texture.set_changed(true);
+        }
+    }
+
+    if let Ok(surface) = <&SurfaceComponent>::query().get(world, entity) {
+        surface.write().set_dropped();
+    }
+}
+
+/// Create pending untagged shader modules, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_shader_modules(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     shader_module_desc: &ShaderModuleDescriptorComponent,
     shader_module: &ShaderModuleComponent,
 ) {
-    if !shader_module.read().is_pending() && !shader_module_desc.get_changed() {
+    if !shader_module.read().is_pending() && !tracker.has_changed(*entity, shader_module_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     shader_module
         .write()
         .set_ready(device.create_shader_module(&shader_module_desc.read()));
 
-    shader_module_desc.set_changed(false);
+    tracker.observe(*entity, shader_module_desc);
 
-    println!("Created shader module");
+    tracing::debug!("Created shader module");
 }
 
-/// Create pending usage-tagged shader modules, recreating them if a Changed flag is set
+/// Create pending usage-tagged shader modules, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_shader_modules_with_usage<T: Send + Sync + 'static>(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     shader_module_desc: &Usage<T, ShaderModuleDescriptorComponent>,
     shader_module: &Usage<T, ShaderModuleComponent>,
 ) {
-    if !shader_module.read().is_pending() && !shader_module_desc.get_changed() {
+    if !shader_module.read().is_pending() && !tracker.has_changed(*entity, shader_module_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     shader_module
         .write()
         .set_ready(device.create_shader_module(&shader_module_desc.read()));
 
-    shader_module_desc.set_changed(false);
-    println!("Created {} shader module", std::any::type_name::<T>());
+    tracker.observe(*entity, shader_module_desc);
+    tracing::debug!("Created {} shader module", std::any::type_name::<T>());
 }
 
-/// Create pending untagged shader modules, recreating them if a Changed flag is set
+/// Create pending untagged shader modules, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_shader_modules_spirv(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     shader_module_desc: &ShaderModuleDescriptorSpirVComponent,
     shader_module: &ShaderModuleComponent,
 ) {
-    println!("Create shader modules spirv");
-    if !shader_module.read().is_pending() && !shader_module_desc.get_changed() {
+    if !shader_module.read().is_pending() && !tracker.has_changed(*entity, shader_module_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     shader_module
         .write()
         .set_ready(unsafe { device.create_shader_module_spirv(&shader_module_desc.read()) });
 
-    shader_module_desc.set_changed(false);
+    tracker.observe(*entity, shader_module_desc);
 
-    println!("Created spir-v shader module");
+    tracing::debug!("Created spir-v shader module");
 }
 
-/// Create pending usage-tagged shader modules, recreating them if a Changed flag is set
+/// Create pending usage-tagged shader modules, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_shader_modules_usage_spirv<T: Send + Sync + 'static>(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     shader_module_desc: &Usage<T, ShaderModuleDescriptorSpirVComponent>,
     shader_module: &Usage<T, ShaderModuleComponent>,
 ) {
-    if !shader_module.read().is_pending() && !shader_module_desc.get_changed() {
+    if !shader_module.read().is_pending() && !tracker.has_changed(*entity, shader_module_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     shader_module
         .write()
         .set_ready(unsafe { device.create_shader_module_spirv(&shader_module_desc.read()) });
 
-    shader_module_desc.set_changed(false);
-    println!(
+    tracker.observe(*entity, shader_module_desc);
+    tracing::debug!(
         "Created {} spir-v shader module",
         std::any::type_name::<T>()
     );
 }
 
-/// Create pending usage-tagged buffers, recreating them if a Changed flag is set
+/// Create pending usage-tagged buffers, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_buffers<T: Send + Sync + 'static>(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     buffer_desc: &Usage<T, BufferDescriptorComponent>,
     buffer: &Usage<T, BufferComponent>,
 ) {
-    if !buffer.read().is_pending() && !buffer_desc.get_changed() {
+    if !buffer.read().is_pending() && !tracker.has_changed(*entity, buffer_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     buffer
         .write()
         .set_ready(device.create_buffer(&buffer_desc.read()));
 
-    buffer_desc.set_changed(false);
+    tracker.observe(*entity, buffer_desc);
 
-    println!("Created {} buffer", std::any::type_name::<T>());
+    tracing::debug!("Created {} buffer", std::any::type_name::<T>());
 }
 
-/// Create-initialize pending usage-tagged buffers, recreating them if a Changed flag is set
+/// Create-initialize pending usage-tagged buffers, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_buffers_init<T: Send + Sync + 'static>(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     buffer_init_desc: &Usage<T, BufferInitDescriptorComponent>,
     buffer: &Usage<T, BufferComponent>,
 ) {
-    if !buffer.read().is_pending() && !buffer_init_desc.get_changed() {
+    if !buffer.read().is_pending() && !tracker.has_changed(*entity, buffer_init_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     buffer
         .write()
         .set_ready(device.create_buffer_init(&buffer_init_desc.read()));
 
-    buffer_init_desc.set_changed(false);
+    tracker.observe(*entity, buffer_init_desc);
 
-    println!("Create-initialized {} buffer", std::any::type_name::<T>());
+    tracing::debug!("Create-initialized {} buffer", std::any::type_name::<T>());
 }
 
-/// Create pending usage-tagged textures, recreating them if a Changed flag is set
+/// Create pending usage-tagged textures, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_textures<T: Send + Sync + 'static>(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     texture_descriptor_component: &Usage<T, TextureDescriptorComponent>,
     texture: &Usage<T, TextureComponent>,
 ) {
-    if !texture.read().is_pending() && !texture_descriptor_component.get_changed() {
+    if !texture.read().is_pending() && !tracker.has_changed(*entity, texture_descriptor_component) {
         return;
     }
 
@@ -349,33 +414,43 @@ pub fn create_textures<T: Send + Sync + 'static>(
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     texture
         .write()
         .set_ready(device.create_texture(&*texture_descriptor));
 
-    texture_descriptor_component.set_changed(false);
+    tracker.observe(*entity, texture_descriptor_component);
 
-    println!("Created texture: {:#?}", texture_descriptor);
+    tracing::debug!("Created texture: {:?}", texture_descriptor);
 }
 
-/// Create pending usage-tagged texture views, recreating them if a Changed flag is set
+/// Create pending usage-tagged texture views, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
 #[read_component(Usage<T, TextureComponent>)]
-#[read_component(Device)]
 pub fn create_texture_views<T: Send + Sync + 'static>(
     world: &SubWorld,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     texture: &IndirectComponent<Usage<T, TextureComponent>>,
     texture_view_desc: &Usage<T, TextureViewDescriptorComponent>,
     texture_view: &Usage<T, TextureViewComponent>,
 ) {
-    if !texture_view.read().is_pending() && !texture_view_desc.get_changed() {
+    if !texture_view.read().is_pending() && !tracker.has_changed(*entity, texture_view_desc) {
         return;
     }
 
-    let texture = world.get_indirect(texture).unwrap();
-    let texture = texture.read();
-    let texture = if let LazyComponent::Ready(texture) = &*texture {
+    let texture = match world.get_indirect(texture) {
+        Ok(texture) => texture,
+        Err(e) => {
+            tracing::warn!("Failed to resolve {:?}: {}", texture, e);
+            return;
+        }
+    };
+    let texture = if let Some(texture) = texture.read_ready() {
         texture
     } else {
         return;
@@ -385,58 +460,67 @@ pub fn create_texture_views<T: Send + Sync + 'static>(
         .write()
         .set_ready(texture.create_view(&texture_view_desc.read()));
 
-    texture_view_desc.set_changed(false);
+    tracker.observe(*entity, texture_view_desc);
 
-    println!("Created texture view: {:#?}", texture_view_desc.read());
+    tracing::debug!("Created texture view: {:?}", texture_view_desc.read());
 }
 
-/// Create pending samplers, recreating them if a Changed flag is set
+/// Create pending samplers, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_samplers(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     sampler_desc: &SamplerDescriptorComponent,
     sampler: &SamplerComponent,
 ) {
-    if !sampler.read().is_pending() && !sampler_desc.get_changed() {
+    if !sampler.read().is_pending() && !tracker.has_changed(*entity, sampler_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     sampler
         .write()
         .set_ready(device.create_sampler(&sampler_desc.read()));
 
-    sampler_desc.set_changed(false);
+    tracker.observe(*entity, sampler_desc);
 
-    println!("Created sampler: {:#?}", sampler_desc.read());
+    tracing::debug!("Created sampler: {:?}", sampler_desc.read());
 }
 
-/// Create pending usage-tagged samplers, recreating them if a Changed flag is set
+/// Create pending usage-tagged samplers, recreating them when their descriptor changes
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 pub fn create_samplers_with_usage<T: Send + Sync + 'static>(
-    world: &SubWorld,
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
+    entity: &Entity,
     sampler_desc: &Usage<T, SamplerDescriptorComponent>,
     sampler: &Usage<T, SamplerComponent>,
 ) {
-    if !sampler.read().is_pending() && !sampler_desc.get_changed() {
+    if !sampler.read().is_pending() && !tracker.has_changed(*entity, sampler_desc) {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     sampler
         .write()
         .set_ready(device.create_sampler(&sampler_desc.read()));
 
-    sampler_desc.set_changed(false);
+    tracker.observe(*entity, sampler_desc);
 
-    println!("Created sampler: {:#?}", sampler_desc.read());
+    tracing::debug!("Created sampler: {:?}", sampler_desc.read());
 }
 
 // Write data to buffer
 #[legion::system]
-#[read_component(Queue)]
 #[read_component(Usage<T, BufferWriteComponent<L>>)]
 #[read_component(Changed<L>)]
 #[read_component(IndirectComponent<Usage<T, BufferComponent>>)]
@@ -447,66 +531,67 @@ pub fn buffer_write<
     V: ToBytes,
 >(
     world: &SubWorld,
+    #[state] resources: &ImmutableResources,
 ) {
-    let queue = if let Some(queue) = <&Queue>::query().iter(world).next() {
-        queue
-    } else {
-        return;
-    };
-
     <(
         &Usage<T, BufferWriteComponent<L>>,
         &Changed<L>,
         &IndirectComponent<Usage<T, BufferComponent>>,
     )>::query()
     .par_for_each(world, |(buffer_write, data_component, buffer)| {
-        let buffer = world.get_indirect(buffer).unwrap();
+        if !buffer_write.change_tick().has_changed(data_component) {
+            return;
+        }
 
-        if data_component.get_changed() {
-            let buffer = buffer.read();
-            let buffer = if let LazyComponent::Ready(buffer) = &*buffer {
-                buffer
-            } else {
+        let buffer = match world.get_indirect(buffer) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                tracing::warn!("Failed to resolve {:?}: {}", buffer, e);
                 return;
-            };
+            }
+        };
 
-            let data = data_component.read();
-            let bytes = data.to_bytes();
+        let buffer = if let Some(buffer) = buffer.read_ready() {
+            buffer
+        } else {
+            return;
+        };
 
-            println!(
-                "Writing {} bytes to {} buffer at offset {}",
-                bytes.len(),
-                std::any::type_name::<T>(),
-                *buffer_write.read()
-            );
-            queue.write_buffer(buffer, *buffer_write.read(), bytes);
+        let queue = if let Some(queue) = resources.get_or_warn::<Queue>() {
+            queue
+        } else {
+            return;
+        };
 
-            data_component.set_changed(false);
-        }
+        buffer_write.change_tick().observe(data_component);
+
+        let data = data_component.read();
+        let bytes = data.to_bytes();
+
+        tracing::trace!(
+            "Writing {} bytes to {} buffer at offset {}",
+            bytes.len(),
+            std::any::type_name::<T>(),
+            *buffer_write.read()
+        );
+        queue.write_buffer(&buffer, *buffer_write.read(), bytes);
     });
 }
 
 // Write data to texture
 #[legion::system]
-#[read_component(Queue)]
 #[read_component(Usage<T, TextureWriteComponent<L>>)]
 #[read_component(Changed<L>)]
 #[read_component(IndirectComponent<Usage<T, TextureDescriptorComponent>>)]
 #[read_component(IndirectComponent<Usage<T, TextureComponent>>)]
 #[read_component(Usage<T, TextureDescriptorComponent>)]
 #[read_component(Usage<T, TextureComponent>)]
-pub fn texture_write<T, L, V>(world: &SubWorld)
+pub fn texture_write<T, L, V>(world: &SubWorld, #[state] resources: &ImmutableResources)
 where
     T: Send + Sync + 'static,
     L: ReadWriteLock<V> + Send + Sync + 'static,
     V: ToBytes,
 {
-    let queue = if let Some(queue) = <&Queue>::query().iter(world).next() {
-        queue
-    } else {
-        return;
-    };
-
     <(
         &Usage<T, TextureWriteComponent<L>>,
         &Changed<L>,
@@ -516,52 +601,76 @@ where
     .par_for_each(
         world,
         |(texture_write, texels_component, texture_desc, texture)| {
-            let texture_descriptor_component = world.get_indirect(texture_desc).unwrap();
-            let texture_component = world.get_indirect(texture).unwrap();
-
-            if texels_component.get_changed() {
-                let texture = texture_component.read();
-                let texture = if let LazyComponent::Ready(texture) = &*texture {
-                    texture
-                } else {
-                    return;
-                };
-
-                let texels = texels_component.read();
-                let bytes = texels.to_bytes();
-                let image_copy_texture =
-                    ReadWriteLock::<ImageCopyTextureBase<()>>::read(texture_write);
-                let image_data_layout = ReadWriteLock::<ImageDataLayout>::read(texture_write);
-
-                println!(
-                    "Writing {} bytes to texture at offset {}",
-                    bytes.len(),
-                    ReadWriteLock::<wgpu::ImageDataLayout>::read(texture_write).offset,
-                );
-
-                queue.write_texture(
-                    wgpu::ImageCopyTexture {
-                        texture: &*texture,
-                        mip_level: image_copy_texture.mip_level,
-                        origin: image_copy_texture.origin,
-                        aspect: image_copy_texture.aspect,
-                    },
-                    bytes,
-                    *image_data_layout,
-                    texture_descriptor_component.read().size,
-                );
-
-                texels_component.set_changed(false);
+            if !texture_write.change_tick().has_changed(texels_component) {
+                return;
             }
+
+            let texture_descriptor_component = match world.get_indirect(texture_desc) {
+                Ok(texture_descriptor_component) => texture_descriptor_component,
+                Err(e) => {
+                    tracing::warn!("Failed to resolve {:?}: {}", texture_desc, e);
+                    return;
+                }
+            };
+            let texture_component = match world.get_indirect(texture) {
+                Ok(texture_component) => texture_component,
+                Err(e) => {
+                    tracing::warn!("Failed to resolve {:?}: {}", texture, e);
+                    return;
+                }
+            };
+
+            let texture = if let Some(texture) = texture_component.read_ready() {
+                texture
+            } else {
+                return;
+            };
+
+            let queue = if let Some(queue) = resources.get_or_warn::<Queue>() {
+                queue
+            } else {
+                return;
+            };
+
+            texture_write.change_tick().observe(texels_component);
+
+            let texels = texels_component.read();
+            let bytes = texels.to_bytes();
+            let image_copy_texture = ReadWriteLock::<ImageCopyTextureBase<()>>::read(texture_write);
+            let image_data_layout = ReadWriteLock::<ImageDataLayout>::read(texture_write);
+
+            tracing::trace!(
+                "Writing {} bytes to texture at offset {}",
+                bytes.len(),
+                ReadWriteLock::<wgpu::ImageDataLayout>::read(texture_write).offset,
+            );
+
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &*texture,
+                    mip_level: image_copy_texture.mip_level,
+                    origin: image_copy_texture.origin,
+                    aspect: image_copy_texture.aspect,
+                },
+                bytes,
+                *image_data_layout,
+                texture_descriptor_component.read().size,
+            );
         },
     );
 }
 
 // Flush command buffers to the WGPU queue
 #[legion::system(par_for_each)]
-#[read_component(Queue)]
-pub fn submit_command_buffers(world: &SubWorld, command_buffers: &CommandBuffersComponent) {
-    let queue = if let Some(queue) = <&Queue>::query().iter(world).next() {
+pub fn submit_command_buffers(
+    #[state] resources: &ImmutableResources,
+    command_buffers: &CommandBuffersComponent,
+) {
+    if command_buffers.read().is_empty() {
+        return;
+    }
+
+    let queue = if let Some(queue) = resources.get_or_warn::<Queue>() {
         queue
     } else {
         return;
@@ -572,30 +681,42 @@ pub fn submit_command_buffers(world: &SubWorld, command_buffers: &CommandBuffers
 
 // Create textures and corresponding texture views for surfaces
 #[legion::system]
-#[read_component(WindowEventComponent)]
-#[read_component(WindowEntityMap)]
 #[read_component(SurfaceComponent)]
 #[read_component(SurfaceTextureComponent)]
 #[read_component(RenderAttachmentTextureViewDescriptor)]
 #[read_component(RenderAttachmentTextureView)]
-pub fn surface_textures_views(world: &SubWorld) {
-    use legion::IntoQuery;
-
-    let window_event = <&WindowEventComponent>::query()
-        .iter(&*world)
-        .next()
-        .unwrap();
-    let window_event = window_event.read().0.expect("No window for current event");
+pub fn surface_textures_views(world: &SubWorld, #[state] resources: &ImmutableResources) {
+    let window_event = if let Some(window_event) = resources.get_or_warn::<WindowEventComponent>() {
+        window_event
+    } else {
+        return;
+    };
+    let window_event = if let Some(window_event) = window_event.read().0 {
+        window_event
+    } else {
+        tracing::warn!("Failed to create surface texture views: No window for current event");
+        return;
+    };
 
-    let window_entity_map = <&WindowEntityMap>::query().iter(&*world).next().unwrap();
-    let window_entity_map = window_entity_map.read();
+    let window_entity_map =
+        if let Some(window_entity_map) = resources.get_or_warn::<WindowEntityMap>() {
+            window_entity_map
+        } else {
+            return;
+        };
 
-    let entity = window_entity_map
-        .get(&window_event)
-        .expect("Redraw requested for window without entity");
+    let entity = if let Some(entity) = window_entity_map.get(&window_event) {
+        entity
+    } else {
+        tracing::warn!(
+            "Failed to create surface texture views for window {:?}: No entity for window",
+            window_event
+        );
+        return;
+    };
 
     // Create surface textures and views
     // These will be rendered to and presented during RedrawEventsCleared
-    surface_texture_query(&world, entity);
-    surface_texture_view_query(&world, entity);
+    surface_texture_query(&world, &entity);
+    surface_texture_view_query(&world, &entity);
 }
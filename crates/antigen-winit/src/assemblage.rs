@@ -1,17 +1,21 @@
-use antigen_core::{ChangedFlag, Construct, LazyComponent, With};
-use legion::{Entity, World};
+use antigen_core::{
+    assemble_index, ChangedFlag, Construct, ImmutableWorld, LazyComponent, LazyReadWriteLock, With,
+};
+use legion::Entity;
 use winit::dpi::PhysicalSize;
 
 use crate::{
-    WindowComponent, WindowEntityMap, WindowEventComponent, WindowSizeComponent,
-    WindowTitleComponent,
+    WindowComponent, WindowEventComponent, WindowEvents, WindowSizeComponent, WindowTitleComponent,
 };
 
-pub fn assemble_winit_backend(world: &mut World) -> Entity {
-    world.push((
-        WindowEntityMap::new(Default::default()),
-        WindowEventComponent::new(),
-    ))
+/// Insert the window event resources, and a [`WindowEntityMap`](crate::WindowEntityMap) of windows
+pub fn assemble_winit_backend(world: &ImmutableWorld) {
+    assemble_index(world, |window: &WindowComponent| {
+        window.read_ready().map(|window| window.id())
+    });
+
+    world.resources().insert(WindowEventComponent::new());
+    world.resources().insert(WindowEvents::default());
 }
 
 pub trait AssembleWinit {
@@ -1,10 +1,9 @@
-use antigen_core::{Changed, LazyComponent, RwLock, Usage, impl_read_write_lock};
+use antigen_core::{
+    Changed, EventReader, Events, Index, LazyComponent, RwLock, Usage, impl_read_write_lock,
+};
 
-use legion::Entity;
 use winit::{dpi::PhysicalSize, event::WindowEvent, window::WindowId};
 
-use std::collections::BTreeMap;
-
 // Winit window
 pub type WindowComponent = RwLock<LazyComponent<winit::window::Window>>;
 
@@ -12,10 +11,13 @@ pub type WindowComponent = RwLock<LazyComponent<winit::window::Window>>;
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RedrawUnconditionally;
 
-// Window ID -> Entity ID map for winit event handling
-pub type WindowEntityMap = RwLock<BTreeMap<WindowId, Entity>>;
+// Window ID -> Entity ID map for winit event handling, indexing ready windows
+pub type WindowEntityMap = Index<WindowId, WindowComponent>;
 
 /// Window event wrapper
+///
+/// Only holds the event currently being dispatched, so is only visible to event hook schedules.
+/// Systems that run elsewhere should consume [`WindowEvents`] instead.
 pub struct WindowEventComponent(RwLock<(Option<WindowId>, Option<WindowEvent<'static>>)>);
 
 impl_read_write_lock!(WindowEventComponent, 0, (Option<WindowId>, Option<WindowEvent<'static>>));
@@ -26,6 +28,16 @@ impl WindowEventComponent {
     }
 }
 
+/// Queue of every window event, readable from any thread via [`WindowEventReader`]
+///
+/// Updated on each `MainEventsCleared`, after the event loop stages have run.
+/// Events are retained until every live reader has read them,
+/// so readers on the game thread observe every event despite running at a different rate.
+pub type WindowEvents = Events<(WindowId, WindowEvent<'static>)>;
+
+/// Cursor into [`WindowEvents`], typically held as system `#[state]`
+pub type WindowEventReader = EventReader<(WindowId, WindowEvent<'static>)>;
+
 /// Usage tag for SizeComponent
 pub enum WindowSize {}
 pub type WindowSizeComponent = Usage<WindowSize, Changed<RwLock<PhysicalSize<u32>>>>;
@@ -8,15 +8,16 @@ pub use systems::*;
 
 pub use winit;
 
-use antigen_core::{serial, single, ImmutableWorld, ReadWriteLock};
+use antigen_core::{
+    run_lifecycle_hooks, serial, single, ChangeTracker, EventLoopSchedules, ImmutableResources,
+    ImmutableWorld, ReadWriteLock, Stage,
+};
 
 use winit::{
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoopWindowTarget},
 };
 
-use legion::IntoQuery;
-
 /// A winit-compatible event loop closure
 pub trait WinitEventLoopHandler<T>:
     FnMut(Event<T>, &EventLoopWindowTarget<T>, &mut ControlFlow)
@@ -56,48 +57,56 @@ pub fn wrap_event_loop<T>(
 }
 
 /// Extend an event loop closure with ECS event loop handling and window functionality
-pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl EventLoopHandler<T> {
+///
+/// `resources` must belong to the world the handler is run against.
+pub fn winit_event_handler<T: Clone>(
+    resources: &ImmutableResources,
+    mut f: impl EventLoopHandler<T>,
+) -> impl EventLoopHandler<T> {
     let mut main_events_cleared_schedule = serial![
-        window_title_system(),
+        window_title_system(ChangeTracker::default()),
         redraw_windows_on_main_events_cleared_system()
     ];
 
-    let mut resize_window_schedule = single![resize_window_system()];
-    let mut close_window_schedule = single![close_window_system()];
-    let mut reset_resize_window_changed_flag_schedule =
-        single![reset_resize_window_dirty_flags_system()];
+    let mut resize_window_schedule = single![resize_window_system(resources.clone())];
+    let mut close_window_schedule = single![close_window_system(resources.clone())];
 
     move |world: &ImmutableWorld,
           event: Event<'static, T>,
           event_loop_window_target: &EventLoopWindowTarget<T>,
           control_flow: &mut ControlFlow| {
-        let world_read = world.read();
-        let window_event = <&WindowEventComponent>::query()
-            .iter(&*world_read)
-            .next()
-            .unwrap();
-        *window_event.write() = (None, None);
+        // Publish the current event before any schedules run
+        let resources = world.resources();
+        if let (Some(window_event), Some(window_events)) = (
+            resources.get_or_warn::<WindowEventComponent>(),
+            resources.get_or_warn::<WindowEvents>(),
+        ) {
+            *window_event.write() = match &event {
+                winit::event::Event::RedrawRequested(window_id) => (Some(*window_id), None),
+                winit::event::Event::WindowEvent { window_id, event } => {
+                    window_events.send((*window_id, event.clone()));
+                    (Some(*window_id), Some(event.clone()))
+                }
+                _ => (None, None),
+            };
+        }
 
         match &event {
             winit::event::Event::MainEventsCleared => {
                 create_windows_thread_local(&world, event_loop_window_target);
+                // Once-per-frame sync point, indexing new windows before any of their events are handled
+                run_lifecycle_hooks(world);
                 main_events_cleared_schedule.execute(world);
             }
-            winit::event::Event::RedrawRequested(window_id) => {
-                window_event.write().0 = Some(*window_id);
-            }
-            winit::event::Event::WindowEvent { window_id, event } => {
-                *window_event.write() = (Some(*window_id), Some(event.clone()));
-                match event {
-                    WindowEvent::Resized(_) => {
-                        resize_window_schedule.execute(world);
-                    }
-                    WindowEvent::CloseRequested => {
-                        close_window_schedule.execute(&world);
-                    }
-                    _ => (),
+            winit::event::Event::WindowEvent { event, .. } => match event {
+                WindowEvent::Resized(_) => {
+                    resize_window_schedule.execute(world);
                 }
-            }
+                WindowEvent::CloseRequested => {
+                    close_window_schedule.execute(&world);
+                }
+                _ => (),
+            },
             _ => (),
         }
 
@@ -105,13 +114,76 @@ pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl Ev
 
         match &event {
             winit::event::Event::MainEventsCleared => {
-                reset_resize_window_changed_flag_schedule.execute(world);
+                if let Some(window_events) = world.resources().get_or_warn::<WindowEvents>() {
+                    window_events.update();
+                }
             }
             _ => (),
         }
     }
 }
 
+/// Extend an event loop closure with the event loop stages and event hooks of an [`App`](antigen_core::App)
+///
+/// Event hooks run as their events arrive, [`Stage::UPDATE`] runs on `MainEventsCleared`,
+/// and [`Stage::RENDER`] runs on `RedrawEventsCleared`, all before `f` is called.
+pub fn app_event_handler<T>(
+    mut schedules: EventLoopSchedules<Event<'static, T>>,
+    mut f: impl EventLoopHandler<T>,
+) -> impl EventLoopHandler<T> {
+    move |world: &ImmutableWorld,
+          event: Event<'static, T>,
+          event_loop_window_target: &EventLoopWindowTarget<T>,
+          control_flow: &mut ControlFlow| {
+        schedules.execute_event_hooks(&event, world);
+
+        let stages: &[Stage] = match &event {
+            Event::MainEventsCleared => &Stage::UPDATE,
+            Event::RedrawEventsCleared => &Stage::RENDER,
+            _ => &[],
+        };
+
+        for stage in stages {
+            schedules.execute_stage(*stage, world);
+        }
+
+        f(world, event, event_loop_window_target, control_flow);
+    }
+}
+
+/// [`App`](antigen_core::App) event filter matching window resizes
+pub fn on_window_resized<T>(event: &Event<'static, T>) -> bool {
+    matches!(
+        event,
+        Event::WindowEvent {
+            event: WindowEvent::Resized(_),
+            ..
+        }
+    )
+}
+
+/// [`App`](antigen_core::App) event filter matching keyboard input
+pub fn on_keyboard_input<T>(event: &Event<'static, T>) -> bool {
+    matches!(
+        event,
+        Event::WindowEvent {
+            event: WindowEvent::KeyboardInput { .. },
+            ..
+        }
+    )
+}
+
+/// [`App`](antigen_core::App) event filter matching cursor movement
+pub fn on_cursor_moved<T>(event: &Event<'static, T>) -> bool {
+    matches!(
+        event,
+        Event::WindowEvent {
+            event: WindowEvent::CursorMoved { .. },
+            ..
+        }
+    )
+}
+
 /// Unit winit event handler
 pub fn winit_event_terminator<T>() -> impl EventLoopHandler<T> {
     move |_: &ImmutableWorld,
@@ -119,3 +191,37 @@ pub fn winit_event_terminator<T>() -> impl EventLoopHandler<T> {
           _: &EventLoopWindowTarget<T>,
           _: &mut ControlFlow| {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::{dpi::PhysicalSize, window::WindowId};
+
+    fn window_event(event: WindowEvent<'static>) -> Event<'static, ()> {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event,
+        }
+    }
+
+    #[test]
+    fn event_filters_match_their_window_events() {
+        let resized = window_event(WindowEvent::Resized(PhysicalSize::new(1, 1)));
+        let cursor_moved = window_event(WindowEvent::CursorMoved {
+            device_id: unsafe { winit::event::DeviceId::dummy() },
+            position: Default::default(),
+            modifiers: Default::default(),
+        });
+        let main_events_cleared = Event::<()>::MainEventsCleared;
+
+        assert!(on_window_resized(&resized));
+        assert!(!on_window_resized(&cursor_moved));
+        assert!(!on_window_resized(&main_events_cleared));
+
+        assert!(on_cursor_moved(&cursor_moved));
+        assert!(!on_cursor_moved(&resized));
+
+        assert!(!on_keyboard_input(&resized));
+        assert!(!on_keyboard_input(&cursor_moved));
+    }
+}
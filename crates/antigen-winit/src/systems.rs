@@ -1,7 +1,10 @@
 use super::{RedrawUnconditionally, WindowComponent};
 use crate::{WindowEntityMap, WindowEventComponent, WindowSizeComponent, WindowTitleComponent};
 
-use antigen_core::{ChangedTrait, ImmutableWorld, LazyComponent, ReadWriteLock};
+use antigen_core::{
+    ChangeTracker, ChangedTrait, ImmutableResources, ImmutableWorld, LazyComponent,
+    LazyReadWriteLock, ReadWriteLock,
+};
 
 use legion::{world::SubWorld, IntoQuery};
 use rayon::iter::ParallelIterator;
@@ -14,11 +17,6 @@ pub fn create_windows_thread_local<T>(
 ) {
     let world_read = world.read();
 
-    let window_entity_map = <&WindowEntityMap>::query()
-        .iter(&*world_read)
-        .next()
-        .unwrap();
-
     let mut iter = <(legion::Entity, &WindowComponent)>::query();
 
     let pending_entities = iter
@@ -37,10 +35,16 @@ pub fn create_windows_thread_local<T>(
                 .get(&*world_read, *entity)
                 .unwrap();
 
-        let window = winit::window::Window::new(event_loop_proxy).unwrap();
+        let window = match winit::window::Window::new(event_loop_proxy) {
+            Ok(window) => window,
+            Err(e) => {
+                tracing::error!("Failed to create window: {}", e);
+                window_component.write().set_failed(e);
+                continue;
+            }
+        };
         let size = window.inner_size();
 
-        window_entity_map.write().insert(window.id(), *entity);
         *window_component.write() = LazyComponent::Ready(window);
 
         if let Some(window_size) = size_component {
@@ -56,80 +60,82 @@ pub fn redraw_windows_on_main_events_cleared(
     window: &WindowComponent,
     _redraw: &RedrawUnconditionally,
 ) {
-    match &*window.read() {
-        LazyComponent::Ready(window) => window.request_redraw(),
-        _ => (),
+    if let Some(window) = window.read_ready() {
+        window.request_redraw();
     }
 }
 
 #[legion::system]
-#[read_component(WindowEventComponent)]
-#[read_component(WindowEntityMap)]
 #[read_component(WindowComponent)]
 #[read_component(WindowSizeComponent)]
-pub fn resize_window(world: &SubWorld) {
-    let event_window = <&WindowEventComponent>::query()
-        .iter(&*world)
-        .next()
-        .unwrap();
+pub fn resize_window(world: &SubWorld, #[state] resources: &ImmutableResources) {
+    let event_window = if let Some(event_window) = resources.get_or_warn::<WindowEventComponent>() {
+        event_window
+    } else {
+        return;
+    };
 
-    let window_id = event_window.read().0.expect("No window for current event");
+    let window_id = if let Some(window_id) = event_window.read().0 {
+        window_id
+    } else {
+        tracing::warn!("Failed to resize window: No window for current event");
+        return;
+    };
 
-    let window_entity_map = <&WindowEntityMap>::query().iter(world).next().unwrap();
-    let window_entity_map = window_entity_map.read();
+    let window_entity_map =
+        if let Some(window_entity_map) = resources.get_or_warn::<WindowEntityMap>() {
+            window_entity_map
+        } else {
+            return;
+        };
 
-    let entity = window_entity_map
-        .get(&window_id)
-        .expect("Resize requested for window without entity");
+    let entity = if let Some(entity) = window_entity_map.get(&window_id) {
+        entity
+    } else {
+        tracing::warn!(
+            "Failed to resize window {:?}: No entity for window",
+            window_id
+        );
+        return;
+    };
 
     let (window_component, size_component) = if let Ok(components) =
-        <(&WindowComponent, &WindowSizeComponent)>::query().get(&*world, *entity)
+        <(&WindowComponent, &WindowSizeComponent)>::query().get(&*world, entity)
     {
         components
     } else {
         return;
     };
 
-    if let LazyComponent::Ready(window) = &*window_component.read() {
+    if let Some(window) = window_component.read_ready() {
         *size_component.write() = window.inner_size();
         size_component.set_changed(true);
     }
 }
 
-#[legion::system(par_for_each)]
-pub fn reset_resize_window_dirty_flags(window_size: &WindowSizeComponent) {
-    if window_size.get_changed() {
-        println!("Resetting window size changed flag");
-        window_size.set_changed(false);
-    }
-}
-
 #[legion::system]
 #[read_component(WindowComponent)]
 #[read_component(WindowTitleComponent)]
-pub fn window_title(world: &SubWorld) {
-    <(&WindowComponent, &WindowTitleComponent)>::query()
+pub fn window_title(world: &SubWorld, #[state] tracker: &ChangeTracker) {
+    <(legion::Entity, &WindowComponent, &WindowTitleComponent)>::query()
         .iter(world)
-        .for_each(|(window, title)| {
-            let window = window.read();
-            if let LazyComponent::Ready(window) = &*window {
-                if title.get_changed() {
+        .for_each(|(entity, window, title)| {
+            if let Some(window) = window.read_ready() {
+                if tracker.observe(*entity, title) {
                     window.set_title(&title.read());
-                    title.set_changed(false);
                 }
             }
         });
 }
 
 #[legion::system]
-#[read_component(WindowEventComponent)]
-#[read_component(WindowEntityMap)]
 #[read_component(WindowComponent)]
-pub fn close_window(world: &SubWorld) {
-    let window_event = <&WindowEventComponent>::query()
-        .iter(&*world)
-        .next()
-        .unwrap();
+pub fn close_window(world: &SubWorld, #[state] resources: &ImmutableResources) {
+    let window_event = if let Some(window_event) = resources.get_or_warn::<WindowEventComponent>() {
+        window_event
+    } else {
+        return;
+    };
 
     let window_event = window_event.read();
 
@@ -139,18 +145,35 @@ pub fn close_window(world: &SubWorld) {
         return;
     };
 
-    let window_entity_map = <&WindowEntityMap>::query().iter(world).next().unwrap();
-    let window_entity_map = window_entity_map.read();
-
-    let entity = window_entity_map
-        .get(&window_id)
-        .expect("Close requested for window without entity");
+    let window_entity_map =
+        if let Some(window_entity_map) = resources.get_or_warn::<WindowEntityMap>() {
+            window_entity_map
+        } else {
+            return;
+        };
 
-    let window_component = <&WindowComponent>::query().get(&*world, *entity).unwrap();
+    let entity = if let Some(entity) = window_entity_map.get(window_id) {
+        entity
+    } else {
+        tracing::warn!(
+            "Failed to close window {:?}: No entity for window",
+            window_id
+        );
+        return;
+    };
 
-    if window_component.read().is_ready() {
-        window_component.write().set_dropped()
+    let window_component =
+        if let Ok(window_component) = <&WindowComponent>::query().get(&*world, entity) {
+            window_component
+        } else {
+            tracing::warn!("Failed to close window {:?}: No WindowComponent", window_id);
+            return;
+        };
+
+    let mut window = window_component.write();
+    if window.is_ready() {
+        window.set_dropped()
     } else {
-        panic!("Close requested for a non-open window");
+        tracing::warn!("Failed to close window {:?}: Window is not open", window_id);
     }
 }
@@ -1,6 +1,6 @@
 use std::num::NonZeroU32;
 
-use antigen_core::Construct;
+use antigen_core::{assemble_name, Construct};
 use antigen_wgpu::{
     buffer_size_of,
     wgpu::{
@@ -31,6 +31,8 @@ pub fn assemble_oscilloscope(
     delta_intensity: f32,
 ) {
     let entity = cmd.push(());
+    // Buffer offsets are unique per entity, so keep names distinct for lookup
+    assemble_name(cmd, entity, format!("oscilloscope_{}", vertex_head));
     cmd.add_component(entity, OriginComponent::construct(origin));
     cmd.add_component(entity, osc);
 
@@ -60,7 +62,7 @@ pub fn assemble_oscilloscope(
     );
 
     let indices = vec![(*vertex_head as u32), (*vertex_head + 1) as u32];
-    println!("Ocilloscope indices: {:#?}", indices);
+    tracing::debug!("Ocilloscope indices: {:?}", indices);
 
     cmd.assemble_wgpu_buffer_data_with_usage::<LineIndex, _>(
         entity,
@@ -244,6 +246,7 @@ pub fn assemble_lines(
     indices: Vec<u32>,
 ) {
     let entity = cmd.push(());
+    assemble_name(cmd, entity, format!("lines_{}", vertex_head));
     let vertex_count = vertices.len();
     let index_count = indices.len();
     cmd.assemble_wgpu_buffer_data_with_usage::<MeshVertex, _>(
@@ -280,7 +283,7 @@ pub fn assemble_line_list(
         })
         .collect::<Vec<_>>();
 
-    println!("Line list indices: {:#?}", indices);
+    tracing::debug!("Line list indices: {:?}", indices);
 
     assemble_lines(
         cmd,
@@ -299,6 +302,7 @@ pub fn assemble_line_indices(
     indices: Vec<u32>,
 ) {
     let entity = cmd.push(());
+    assemble_name(cmd, entity, format!("line_indices_{}", line_index_head));
     let index_count = indices.len();
     cmd.assemble_wgpu_buffer_data_with_usage::<LineIndex, _>(
         entity,
@@ -328,7 +332,7 @@ pub fn assemble_line_strip(
         .chain(std::iter::once(last))
         .collect();
 
-    println!("Line strip indices: {:#?}", indices);
+    tracing::debug!("Line strip indices: {:?}", indices);
 
     assemble_lines(
         cmd,
@@ -355,6 +359,7 @@ pub fn assemble_mesh(
     mut indices: Vec<u16>,
 ) {
     let entity = cmd.push(());
+    assemble_name(cmd, entity, format!("mesh_{}", vertex_buffer_index));
     let vertex_offset = buffer_size_of::<MeshVertexData>() * *vertex_buffer_index;
     let index_offset = buffer_size_of::<u16>() * *index_buffer_index;
 
@@ -363,8 +368,8 @@ pub fn assemble_mesh(
     let vertex_count = vertices.len();
     let index_count = indices.len();
 
-    println!("Index count: {}", index_count);
-    println!("Index offset: {}", index_offset);
+    tracing::debug!("Index count: {}", index_count);
+    tracing::debug!("Index offset: {}", index_offset);
 
     cmd.assemble_wgpu_buffer_data_with_usage::<MeshVertex, _>(
         entity,
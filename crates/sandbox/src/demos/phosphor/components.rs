@@ -1,7 +1,6 @@
 use bytemuck::{Pod, Zeroable};
-use std::time::Instant;
 
-use antigen_core::{Changed, RwLock, Usage};
+use antigen_core::{RwLock, Usage};
 use antigen_wgpu::{
     BindGroupComponent, BufferComponent, ComputePipelineComponent, RenderPipelineComponent,
     SamplerComponent, ShaderModuleComponent, TextureComponent, TextureViewComponent, ToBytes,
@@ -13,8 +12,6 @@ pub struct PhosphorRenderer;
 // Usage tags
 pub enum Position {}
 
-pub enum StartTime {}
-pub enum Timestamp {}
 pub enum TotalTime {}
 pub enum DeltaTime {}
 
@@ -62,8 +59,6 @@ pub enum LineIndexCount {}
 // Usage-tagged components
 pub type PositionComponent = Usage<Position, RwLock<(f32, f32)>>;
 
-pub type StartTimeComponent = Usage<StartTime, Instant>;
-pub type TimestampComponent = Usage<Timestamp, RwLock<Instant>>;
 pub type TotalTimeComponent = Usage<TotalTime, RwLock<f32>>;
 pub type DeltaTimeComponent = Usage<DeltaTime, RwLock<f32>>;
 pub type PerspectiveMatrixComponent = Usage<Perspective, RwLock<[[f32; 4]; 4]>>;
@@ -208,11 +203,3 @@ impl Oscilloscope {
         (x * self.magnitude, y * self.magnitude, z * self.magnitude)
     }
 }
-
-#[derive(Debug, Copy, Clone)]
-pub struct Timer {
-    pub timestamp: std::time::Instant,
-    pub duration: std::time::Duration,
-}
-
-pub type TimerComponent = Changed<RwLock<Timer>>;
@@ -52,8 +52,10 @@
 //           [ ] Investigate calculating subsectors from internal faces
 //           [ ] Paralellize shambler
 //
-//       [ ] Figure out how to flush command buffers at runtime
+//       [✓] Figure out how to flush command buffers at runtime
 //           * Needed to add, remove components or entities
+//           * WorldCommandQueue reserves entities immediately from any thread,
+//             and applies its commands under a single short write lock at sync points
 //           * Want to avoid the Godot issue of stalling the main thread for object allocation
 //           * Only the allocating thread should block
 //           * This would suggest maintaining one world per thread and
@@ -106,39 +108,39 @@ use legion::Entity;
 pub use systems::*;
 
 use expression::EvalTrait;
-use std::{collections::BTreeMap, time::Instant};
+use std::collections::BTreeMap;
 
 use legion::IntoQuery;
 
 use antigen_winit::{
-    winit::{
-        event::{Event, WindowEvent},
-        event_loop::{ControlFlow, EventLoopWindowTarget},
-    },
-    AssembleWinit, EventLoopHandler, RedrawUnconditionally, WindowComponent,
+    on_window_resized, winit::event::Event, AssembleWinit, RedrawUnconditionally, WindowComponent,
+    WindowEventReader,
 };
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, Construct, ImmutableWorld, LazyComponent, Usage,
+    assemble_name, parallel, serial, AddIndirectComponent, App, ChangeTracker, Construct,
+    ImmutableResources, ImmutableSchedule, IntoScheduledSystem, LazyReadWriteLock, Plugin, Serial,
+    Stage, Usage,
 };
 
 use antigen_wgpu::{
     buffer_size_of,
     wgpu::{
-        AddressMode, BufferAddress, BufferDescriptor, BufferUsages, Device, Extent3d, FilterMode,
-        Maintain, SamplerDescriptor, ShaderModuleDescriptor, ShaderSource, TextureAspect,
-        TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor,
+        AddressMode, BufferAddress, BufferDescriptor, BufferUsages, Extent3d, FilterMode, Maintain,
+        SamplerDescriptor, ShaderModuleDescriptor, ShaderSource, TextureAspect, TextureDescriptor,
+        TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor,
     },
     AssembleWgpu, RenderAttachmentTextureView, SurfaceConfigurationComponent,
 };
 
-use antigen_shambler::MapFileComponent;
+use antigen_shambler::{MapFileComponent, MapFilePlugin};
 
 const HDR_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
 const MAX_MESH_VERTICES: usize = 10000;
 const MAX_MESH_INDICES: usize = 10000;
 const MAX_LINE_INDICES: usize = 20000;
 const MAX_LINES: usize = MAX_LINE_INDICES / 2;
+const MAP_FILE_RETRY_ATTEMPTS: usize = 3;
 const CLEAR_COLOR: antigen_wgpu::wgpu::Color = antigen_wgpu::wgpu::Color {
     r: 0.0,
     g: 0.0,
@@ -227,14 +229,16 @@ fn circle_strip(subdiv: usize) -> Vec<LineVertexData> {
 }
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let time_entity = cmd.push(());
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
 
+    assemble_name(cmd, time_entity, "time");
+    assemble_name(cmd, window_entity, "window");
+    assemble_name(cmd, renderer_entity, "renderer");
+
     // Assemble time entity
-    cmd.add_component(time_entity, StartTimeComponent::construct(Instant::now()));
     cmd.add_component(time_entity, TotalTimeComponent::construct(0.0));
     cmd.assemble_wgpu_buffer_data_with_usage::<Uniform, _>(
         time_entity,
@@ -242,7 +246,6 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
         buffer_size_of::<[[f32; 4]; 4]>() * 2,
         Some(renderer_entity),
     );
-    cmd.add_component(time_entity, TimestampComponent::construct(Instant::now()));
     cmd.assemble_wgpu_buffer_data_with_usage::<Uniform, _>(
         time_entity,
         DeltaTimeComponent::construct(1.0 / 60.0),
@@ -751,12 +754,8 @@ pub fn build_map(
         .next()
         .unwrap();
 
-    let geo_map = geo_map.read();
-    let geo_map = if let LazyComponent::Ready(geo_map) = &*geo_map {
-        geo_map
-    } else {
-        panic!("Geo map is not ready");
-    };
+    // Wait for the map to load; failures are reported and retried by the map file plugin
+    let geo_map = geo_map.read_ready()?;
 
     let (buffer_target, vertex_head) = <(Entity, &VertexCountComponent)>::query()
         .iter(world)
@@ -781,7 +780,7 @@ pub fn build_map(
     let mut line_index_head = line_index_head.write();
     let line_index_head = &mut *line_index_head;
 
-    println!("Building map...");
+    tracing::info!("Building map...");
 
     // Create geo planes from brush planes
     let face_planes = shambler::face::FacePlanes::new(&geo_map.face_planes);
@@ -1063,108 +1062,175 @@ pub fn build_map(
         );
     }
 
-    println!("Map build complete");
+    tracing::info!("Map build complete");
 
     *done = true;
 
     Some(())
 }
 
-pub fn winit_event_handler<T>(mut f: impl EventLoopHandler<T>) -> impl EventLoopHandler<T> {
-    let mut prepare_schedule = serial![
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
+    serial![
         parallel![
-            antigen_wgpu::create_shader_modules_with_usage_system::<ComputeLineInstances>(),
-            antigen_wgpu::create_shader_modules_with_usage_system::<BeamLine>(),
-            antigen_wgpu::create_shader_modules_with_usage_system::<BeamMesh>(),
-            antigen_wgpu::create_shader_modules_with_usage_system::<PhosphorDecay>(),
-            antigen_wgpu::create_shader_modules_with_usage_system::<Tonemap>(),
-            antigen_wgpu::create_buffers_system::<Uniform>(),
-            antigen_wgpu::create_buffers_system::<LineVertex>(),
-            antigen_wgpu::create_buffers_system::<LineIndex>(),
-            antigen_wgpu::create_buffers_system::<LineInstance>(),
-            antigen_wgpu::create_buffers_system::<MeshVertex>(),
-            antigen_wgpu::create_buffers_system::<MeshIndex>(),
+            antigen_wgpu::create_shader_modules_with_usage_system::<ComputeLineInstances>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_shader_modules_with_usage_system::<BeamLine>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_shader_modules_with_usage_system::<BeamMesh>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_shader_modules_with_usage_system::<PhosphorDecay>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_shader_modules_with_usage_system::<Tonemap>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<Uniform>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<LineVertex>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<LineIndex>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<LineInstance>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<MeshVertex>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<MeshIndex>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
             serial![
-                antigen_wgpu::create_textures_system::<BeamBuffer>(),
-                antigen_wgpu::create_texture_views_system::<BeamBuffer>(),
+                antigen_wgpu::create_textures_system::<BeamBuffer>(
+                    resources.clone(),
+                    ChangeTracker::default()
+                ),
+                antigen_wgpu::create_texture_views_system::<BeamBuffer>(ChangeTracker::default()),
             ],
             serial![
-                antigen_wgpu::create_textures_system::<BeamDepthBuffer>(),
-                antigen_wgpu::create_texture_views_system::<BeamDepthBuffer>(),
+                antigen_wgpu::create_textures_system::<BeamDepthBuffer>(
+                    resources.clone(),
+                    ChangeTracker::default()
+                ),
+                antigen_wgpu::create_texture_views_system::<BeamDepthBuffer>(
+                    ChangeTracker::default()
+                ),
             ],
             serial![
-                antigen_wgpu::create_textures_system::<BeamMultisample>(),
-                antigen_wgpu::create_texture_views_system::<BeamMultisample>(),
+                antigen_wgpu::create_textures_system::<BeamMultisample>(
+                    resources.clone(),
+                    ChangeTracker::default()
+                ),
+                antigen_wgpu::create_texture_views_system::<BeamMultisample>(
+                    ChangeTracker::default()
+                ),
             ],
             serial![
-                antigen_wgpu::create_textures_system::<PhosphorFrontBuffer>(),
-                antigen_wgpu::create_texture_views_system::<PhosphorFrontBuffer>(),
+                antigen_wgpu::create_textures_system::<PhosphorFrontBuffer>(
+                    resources.clone(),
+                    ChangeTracker::default()
+                ),
+                antigen_wgpu::create_texture_views_system::<PhosphorFrontBuffer>(
+                    ChangeTracker::default()
+                ),
             ],
             serial![
-                antigen_wgpu::create_textures_system::<PhosphorBackBuffer>(),
-                antigen_wgpu::create_texture_views_system::<PhosphorBackBuffer>(),
+                antigen_wgpu::create_textures_system::<PhosphorBackBuffer>(
+                    resources.clone(),
+                    ChangeTracker::default()
+                ),
+                antigen_wgpu::create_texture_views_system::<PhosphorBackBuffer>(
+                    ChangeTracker::default()
+                ),
             ],
-            antigen_wgpu::create_samplers_with_usage_system::<Linear>(),
+            antigen_wgpu::create_samplers_with_usage_system::<Linear>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
         ],
         parallel![
-            antigen_wgpu::buffer_write_system::<Uniform, TotalTimeComponent, f32>(),
-            antigen_wgpu::buffer_write_system::<Uniform, DeltaTimeComponent, f32>(),
+            antigen_wgpu::buffer_write_system::<Uniform, TotalTimeComponent, f32>(
+                resources.clone()
+            ),
+            antigen_wgpu::buffer_write_system::<Uniform, DeltaTimeComponent, f32>(
+                resources.clone()
+            ),
             antigen_wgpu::buffer_write_system::<Uniform, PerspectiveMatrixComponent, [[f32; 4]; 4]>(
+                resources.clone(),
             ),
             antigen_wgpu::buffer_write_system::<Uniform, OrthographicMatrixComponent, [[f32; 4]; 4]>(
+                resources.clone(),
             ),
             antigen_wgpu::buffer_write_system::<
                 LineVertex,
                 LineVertexDataComponent,
                 Vec<LineVertexData>,
-            >(),
-            antigen_wgpu::buffer_write_system::<LineIndex, LineIndexDataComponent, Vec<u32>>(),
+            >(resources.clone()),
+            antigen_wgpu::buffer_write_system::<LineIndex, LineIndexDataComponent, Vec<u32>>(
+                resources.clone()
+            ),
             antigen_wgpu::buffer_write_system::<
                 MeshVertex,
                 MeshVertexDataComponent,
                 Vec<MeshVertexData>,
-            >(),
-            antigen_wgpu::buffer_write_system::<MeshIndex, MeshIndexDataComponent, Vec<u16>>(),
+            >(resources.clone()),
+            antigen_wgpu::buffer_write_system::<MeshIndex, MeshIndexDataComponent, Vec<u16>>(
+                resources.clone()
+            ),
         ],
-        phosphor_prepare_system()
-    ];
+        phosphor_prepare_system(resources.clone())
+    ]
+}
 
-    let mut render_schedule = serial![
-        parallel![
-            phosphor_update_total_time_system(),
-            phosphor_update_delta_time_system(),
-        ],
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
+    serial![
+        phosphor_update_time_system(),
         phosphor_update_oscilloscopes_system(),
-        phosphor_render_system(),
-        phosphor_update_timestamp_system(),
-        antigen_wgpu::device_poll_system(Maintain::Wait),
-    ];
-
-    let mut surface_resize_schedule = single![phosphor_resize_system()];
-    let mut cursor_moved_schedule = single![phosphor_cursor_moved_system()];
-
-    move |world: &ImmutableWorld,
-          event: Event<'static, T>,
-          event_loop_window_target: &EventLoopWindowTarget<T>,
-          control_flow: &mut ControlFlow| {
-        match &event {
-            Event::MainEventsCleared => {
-                surface_resize_schedule.execute(world);
-                prepare_schedule.execute_and_flush(world);
-            }
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(_) => {
-                    surface_resize_schedule.execute(world);
-                }
-                WindowEvent::CursorMoved { .. } => cursor_moved_schedule.execute(world),
-                _ => (),
-            },
-            Event::RedrawEventsCleared => {
-                render_schedule.execute(world);
-            }
-            _ => (),
-        }
+        phosphor_render_system(resources.clone()),
+        antigen_wgpu::device_poll_system(resources.clone(), Maintain::Wait),
+    ]
+}
 
-        f(world, event, event_loop_window_target, control_flow);
+pub struct PhosphorPlugin;
+
+impl<T: 'static> Plugin<Event<'static, T>> for PhosphorPlugin {
+    fn build(&self, app: App<Event<'static, T>>) -> App<Event<'static, T>> {
+        let resources = app.resources().clone();
+        app.add_system(Stage::Assemble, assemble_system())
+            .add_plugin(MapFilePlugin::<MapFile>::new(MAP_FILE_RETRY_ATTEMPTS))
+            .add_system(
+                Stage::PreUpdate,
+                build_map_system(false).after("parse_map_files"),
+            )
+            .add_system(
+                Stage::PreUpdate,
+                phosphor_resize_system(ChangeTracker::default()),
+            )
+            .add_system(
+                Stage::Update,
+                phosphor_cursor_moved_system(resources.clone(), WindowEventReader::default()),
+            )
+            .add_system(Stage::Update, prepare_schedule(&resources))
+            .add_system(Stage::Render, render_schedule(&resources))
+            .add_event_system(
+                on_window_resized,
+                phosphor_resize_system(ChangeTracker::default()),
+            )
     }
 }
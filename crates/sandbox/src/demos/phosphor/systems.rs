@@ -1,11 +1,11 @@
-use std::time::Instant;
+use std::collections::BTreeMap;
 
 use crate::phosphor::HDR_TEXTURE_FORMAT;
 
 use super::*;
 use antigen_core::{
-    lazy_read_ready_else_return, Changed, ChangedTrait, GetIndirect, IndirectComponent,
-    LazyComponent, ReadWriteLock, Usage,
+    ChangeTracker, Changed, ChangedTrait, GameClockComponent, GetIndirect, ImmutableResources,
+    IndirectComponent, LazyReadWriteLock, ReadWriteLock, Usage,
 };
 
 use antigen_wgpu::{
@@ -27,14 +27,14 @@ use antigen_wgpu::{
     TextureDescriptorComponent, TextureViewDescriptorComponent,
 };
 
-use antigen_winit::{winit::event::WindowEvent, WindowComponent, WindowEventComponent};
-use legion::{world::SubWorld, IntoQuery};
+use antigen_winit::{winit::event::WindowEvent, WindowComponent, WindowEventReader, WindowEvents};
+use legion::{world::SubWorld, Entity, IntoQuery};
 
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn phosphor_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &PhosphorRenderer,
     // Render pipelines
@@ -69,24 +69,82 @@ pub fn phosphor_prepare(
     surface_component: &IndirectComponent<SurfaceConfigurationComponent>,
 ) {
     // Fetch resources
-    let device = <&Device>::query().iter(world).next().unwrap();
-
-    lazy_read_ready_else_return!(compute_line_instances_shader);
-    lazy_read_ready_else_return!(phosphor_decay_shader);
-    lazy_read_ready_else_return!(beam_line_shader);
-    lazy_read_ready_else_return!(beam_mesh_shader);
-    lazy_read_ready_else_return!(tonemap_shader);
-
-    lazy_read_ready_else_return!(beam_buffer_view);
-    lazy_read_ready_else_return!(phosphor_front_buffer_view);
-    lazy_read_ready_else_return!(phosphor_back_buffer_view);
-
-    lazy_read_ready_else_return!(linear_sampler);
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
-    lazy_read_ready_else_return!(uniform_buffer);
-    lazy_read_ready_else_return!(mesh_vertex_buffer);
-    lazy_read_ready_else_return!(line_index_buffer);
-    lazy_read_ready_else_return!(line_instance_buffer);
+    let compute_line_instances_shader =
+        if let Some(compute_line_instances_shader) = compute_line_instances_shader.read_ready() {
+            compute_line_instances_shader
+        } else {
+            return;
+        };
+    let phosphor_decay_shader =
+        if let Some(phosphor_decay_shader) = phosphor_decay_shader.read_ready() {
+            phosphor_decay_shader
+        } else {
+            return;
+        };
+    let beam_line_shader = if let Some(beam_line_shader) = beam_line_shader.read_ready() {
+        beam_line_shader
+    } else {
+        return;
+    };
+    let beam_mesh_shader = if let Some(beam_mesh_shader) = beam_mesh_shader.read_ready() {
+        beam_mesh_shader
+    } else {
+        return;
+    };
+    let tonemap_shader = if let Some(tonemap_shader) = tonemap_shader.read_ready() {
+        tonemap_shader
+    } else {
+        return;
+    };
+    let beam_buffer_view = if let Some(beam_buffer_view) = beam_buffer_view.read_ready() {
+        beam_buffer_view
+    } else {
+        return;
+    };
+    let phosphor_front_buffer_view =
+        if let Some(phosphor_front_buffer_view) = phosphor_front_buffer_view.read_ready() {
+            phosphor_front_buffer_view
+        } else {
+            return;
+        };
+    let phosphor_back_buffer_view =
+        if let Some(phosphor_back_buffer_view) = phosphor_back_buffer_view.read_ready() {
+            phosphor_back_buffer_view
+        } else {
+            return;
+        };
+    let linear_sampler = if let Some(linear_sampler) = linear_sampler.read_ready() {
+        linear_sampler
+    } else {
+        return;
+    };
+    let uniform_buffer = if let Some(uniform_buffer) = uniform_buffer.read_ready() {
+        uniform_buffer
+    } else {
+        return;
+    };
+    let mesh_vertex_buffer = if let Some(mesh_vertex_buffer) = mesh_vertex_buffer.read_ready() {
+        mesh_vertex_buffer
+    } else {
+        return;
+    };
+    let line_index_buffer = if let Some(line_index_buffer) = line_index_buffer.read_ready() {
+        line_index_buffer
+    } else {
+        return;
+    };
+    let line_instance_buffer = if let Some(line_instance_buffer) = line_instance_buffer.read_ready()
+    {
+        line_instance_buffer
+    } else {
+        return;
+    };
 
     let surface_component = world.get_indirect(surface_component).unwrap();
     let config = surface_component.read();
@@ -163,7 +221,7 @@ pub fn phosphor_prepare(
     let compute_pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
         label: Some("Compute Pipeline"),
         layout: Some(&compute_pipeline_layout),
-        module: compute_line_instances_shader,
+        module: &compute_line_instances_shader,
         entry_point: "main",
     });
 
@@ -547,43 +605,28 @@ pub fn phosphor_prepare(
 
 // Game tick update
 #[legion::system(par_for_each)]
-pub fn phosphor_update_total_time(
-    start_time: &StartTimeComponent,
+#[read_component(GameClockComponent)]
+pub fn phosphor_update_time(
+    world: &legion::world::SubWorld,
     total_time: &Changed<TotalTimeComponent>,
-) {
-    *total_time.write() = Instant::now().duration_since(**start_time).as_secs_f32();
-    println!("Total time: {:#?}", total_time.read());
-    total_time.set_changed(true);
-}
-
-#[legion::system(par_for_each)]
-pub fn phosphor_update_delta_time(
-    timestamp: &TimestampComponent,
     delta_time: &Changed<DeltaTimeComponent>,
 ) {
-    let timestamp = *timestamp.read();
-    *delta_time.write() = Instant::now().duration_since(timestamp).as_secs_f32();
-    println!("Delta time: {:#?}", delta_time.read());
-    delta_time.set_changed(true);
-}
+    let clock = if let Some(clock) = <&GameClockComponent>::query().iter(world).next() {
+        *clock.read()
+    } else {
+        return;
+    };
 
-#[legion::system(par_for_each)]
-pub fn phosphor_update_timestamp(timestamp: &TimestampComponent) {
-    *timestamp.write() = Instant::now();
-}
+    *total_time.write() = clock.elapsed().as_secs_f32();
+    tracing::trace!("Total time: {:?}", total_time.read());
+    total_time.set_changed(true);
 
-#[legion::system(par_for_each)]
-pub fn phosphor_update_timers(timer_component: &TimerComponent) {
-    let now = Instant::now();
-    let timer = *timer_component.read();
-    if now.duration_since(timer.timestamp) > timer.duration {
-        timer_component.write().timestamp = now;
-        timer_component.set_changed(true);
-    }
+    *delta_time.write() = clock.delta().as_secs_f32();
+    tracing::trace!("Delta time: {:?}", delta_time.read());
+    delta_time.set_changed(true);
 }
 
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(Changed<TotalTimeComponent>)]
 #[read_component(Changed<DeltaTimeComponent>)]
 pub fn phosphor_update_oscilloscopes(
@@ -624,7 +667,9 @@ pub fn phosphor_update_oscilloscopes(
 #[legion::system(par_for_each)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn phosphor_resize(
+    #[state] tracker: &ChangeTracker,
     world: &SubWorld,
+    entity: &Entity,
     _: &PhosphorRenderer,
     surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
     // Bind groups
@@ -653,7 +698,7 @@ pub fn phosphor_resize(
     orthographic_matrix: &Changed<OrthographicMatrixComponent>,
 ) {
     let surface_config = world.get_indirect(surface_config).unwrap();
-    if !surface_config.get_changed() {
+    if !tracker.observe(*entity, surface_config) {
         return;
     }
 
@@ -695,69 +740,81 @@ pub fn phosphor_resize(
     orthographic_matrix.set_changed(true);
 }
 
-#[legion::system(par_for_each)]
+#[legion::system]
+#[read_component(PhosphorRenderer)]
+#[read_component(Changed<PerspectiveMatrixComponent>)]
+#[read_component(IndirectComponent<WindowComponent>)]
+#[read_component(IndirectComponent<SurfaceConfigurationComponent>)]
 #[read_component(WindowComponent)]
 #[read_component(SurfaceConfigurationComponent)]
-#[read_component(WindowEventComponent)]
 pub fn phosphor_cursor_moved(
+    #[state] resources: &ImmutableResources,
+    #[state] reader: &WindowEventReader,
     world: &SubWorld,
-    _: &PhosphorRenderer,
-    projection_matrix: &Changed<PerspectiveMatrixComponent>,
-    window: &IndirectComponent<WindowComponent>,
-    surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
 ) {
-    let window = world
-        .get_indirect(window)
-        .expect("No indirect WindowComponent");
-    let window = window.read();
-    let window = if let LazyComponent::Ready(window) = &*window {
-        window
+    let window_events = if let Some(window_events) = resources.get_or_warn::<WindowEvents>() {
+        window_events
     } else {
         return;
     };
 
-    let surface_config = world
-        .get_indirect(surface_config)
-        .expect("No indirect SurfaceConfigurationComponent");
-    let surface_config = surface_config.read();
-
-    let window_event = <&WindowEventComponent>::query()
-        .iter(world)
-        .next()
-        .expect("No WindowEventComponent");
-
-    let window_event = window_event.read();
-    let (window_id, position) = if let (
-        Some(window_id),
-        Some(WindowEvent::CursorMoved { position, .. }),
-    ) = &*window_event
-    {
-        (window_id, position)
-    } else {
-        return;
-    };
+    // Only the latest position in each window is of interest
+    let mut positions = BTreeMap::new();
+    reader.read(&window_events, |(window_id, event)| {
+        if let WindowEvent::CursorMoved { position, .. } = event {
+            positions.insert(*window_id, *position);
+        }
+    });
 
-    if window.id() != *window_id {
+    if positions.is_empty() {
         return;
     }
 
-    let norm_x = ((position.x as f32 / surface_config.width as f32) * 2.0) - 1.0;
-    let norm_y = ((position.y as f32 / surface_config.height as f32) * 2.0) - 1.0;
+    <(
+        &PhosphorRenderer,
+        &Changed<PerspectiveMatrixComponent>,
+        &IndirectComponent<WindowComponent>,
+        &IndirectComponent<SurfaceConfigurationComponent>,
+    )>::query()
+    .for_each(world, |(_, projection_matrix, window, surface_config)| {
+        let window = world
+            .get_indirect(window)
+            .expect("No indirect WindowComponent");
+        let window = if let Some(window) = window.read_ready() {
+            window
+        } else {
+            return;
+        };
 
-    *projection_matrix.write() = super::perspective_matrix(
-        surface_config.width as f32 / surface_config.height as f32,
-        (-norm_x, norm_y),
-        1.0,
-        500.0,
-    );
-    projection_matrix.set_changed(true);
+        let position = if let Some(position) = positions.get(&window.id()) {
+            position
+        } else {
+            return;
+        };
+
+        let surface_config = world
+            .get_indirect(surface_config)
+            .expect("No indirect SurfaceConfigurationComponent");
+        let surface_config = surface_config.read();
+
+        let norm_x = ((position.x as f32 / surface_config.width as f32) * 2.0) - 1.0;
+        let norm_y = ((position.y as f32 / surface_config.height as f32) * 2.0) - 1.0;
+
+        *projection_matrix.write() = super::perspective_matrix(
+            surface_config.width as f32 / surface_config.height as f32,
+            (-norm_x, norm_y),
+            1.0,
+            500.0,
+        );
+        projection_matrix.set_changed(true);
+    });
 }
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn phosphor_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &PhosphorRenderer,
     // Pipelines
@@ -789,33 +846,105 @@ pub fn phosphor_render(
     mesh_index_count: &MeshIndexCountComponent,
     line_index_count: &LineIndexCountComponent,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    lazy_read_ready_else_return!(compute_pipeline);
-    lazy_read_ready_else_return!(phosphor_decay_pipeline);
-    lazy_read_ready_else_return!(beam_line_pipeline);
-    lazy_read_ready_else_return!(beam_mesh_pipeline);
-    lazy_read_ready_else_return!(tonemap_pipeline);
-
-    lazy_read_ready_else_return!(uniform_bind_group);
-    lazy_read_ready_else_return!(compute_bind_group);
-    lazy_read_ready_else_return!(front_bind_group);
-    lazy_read_ready_else_return!(back_bind_group);
-
-    lazy_read_ready_else_return!(beam_buffer_view);
-    lazy_read_ready_else_return!(beam_depth_view);
-    lazy_read_ready_else_return!(beam_multisample_view);
-    lazy_read_ready_else_return!(phosphor_front_view);
-    lazy_read_ready_else_return!(phosphor_back_view);
-
-    lazy_read_ready_else_return!(line_vertex_buffer);
-    lazy_read_ready_else_return!(line_instance_buffer);
-    lazy_read_ready_else_return!(mesh_vertex_buffer);
-    lazy_read_ready_else_return!(mesh_index_buffer);
+    let compute_pipeline = if let Some(compute_pipeline) = compute_pipeline.read_ready() {
+        compute_pipeline
+    } else {
+        return;
+    };
+    let phosphor_decay_pipeline =
+        if let Some(phosphor_decay_pipeline) = phosphor_decay_pipeline.read_ready() {
+            phosphor_decay_pipeline
+        } else {
+            return;
+        };
+    let beam_line_pipeline = if let Some(beam_line_pipeline) = beam_line_pipeline.read_ready() {
+        beam_line_pipeline
+    } else {
+        return;
+    };
+    let beam_mesh_pipeline = if let Some(beam_mesh_pipeline) = beam_mesh_pipeline.read_ready() {
+        beam_mesh_pipeline
+    } else {
+        return;
+    };
+    let tonemap_pipeline = if let Some(tonemap_pipeline) = tonemap_pipeline.read_ready() {
+        tonemap_pipeline
+    } else {
+        return;
+    };
+    let uniform_bind_group = if let Some(uniform_bind_group) = uniform_bind_group.read_ready() {
+        uniform_bind_group
+    } else {
+        return;
+    };
+    let compute_bind_group = if let Some(compute_bind_group) = compute_bind_group.read_ready() {
+        compute_bind_group
+    } else {
+        return;
+    };
+    let front_bind_group = if let Some(front_bind_group) = front_bind_group.read_ready() {
+        front_bind_group
+    } else {
+        return;
+    };
+    let back_bind_group = if let Some(back_bind_group) = back_bind_group.read_ready() {
+        back_bind_group
+    } else {
+        return;
+    };
+    let beam_buffer_view = if let Some(beam_buffer_view) = beam_buffer_view.read_ready() {
+        beam_buffer_view
+    } else {
+        return;
+    };
+    let beam_depth_view = if let Some(beam_depth_view) = beam_depth_view.read_ready() {
+        beam_depth_view
+    } else {
+        return;
+    };
+    let beam_multisample_view =
+        if let Some(beam_multisample_view) = beam_multisample_view.read_ready() {
+            beam_multisample_view
+        } else {
+            return;
+        };
+    let phosphor_front_view = if let Some(phosphor_front_view) = phosphor_front_view.read_ready() {
+        phosphor_front_view
+    } else {
+        return;
+    };
+    let phosphor_back_view = if let Some(phosphor_back_view) = phosphor_back_view.read_ready() {
+        phosphor_back_view
+    } else {
+        return;
+    };
+    let line_vertex_buffer = if let Some(line_vertex_buffer) = line_vertex_buffer.read_ready() {
+        line_vertex_buffer
+    } else {
+        return;
+    };
+    let line_instance_buffer = if let Some(line_instance_buffer) = line_instance_buffer.read_ready()
+    {
+        line_instance_buffer
+    } else {
+        return;
+    };
+    let mesh_vertex_buffer = if let Some(mesh_vertex_buffer) = mesh_vertex_buffer.read_ready() {
+        mesh_vertex_buffer
+    } else {
+        return;
+    };
+    let mesh_index_buffer = if let Some(mesh_index_buffer) = mesh_index_buffer.read_ready() {
+        mesh_index_buffer
+    } else {
+        return;
+    };
 
     let buffer_flip_state = *buffer_flip_flop.read();
     let mesh_index_count = *mesh_index_count.read();
@@ -823,7 +952,12 @@ pub fn phosphor_render(
     let line_count = line_index_count / 2;
 
     let render_attachment_view = world.get_indirect(render_attachment_view).unwrap();
-    lazy_read_ready_else_return!(render_attachment_view);
+    let render_attachment_view =
+        if let Some(render_attachment_view) = render_attachment_view.read_ready() {
+            render_attachment_view
+        } else {
+            return;
+        };
 
     let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
 
@@ -831,13 +965,13 @@ pub fn phosphor_render(
     let mut cpass = encoder.begin_compute_pass(&ComputePassDescriptor {
         label: Some("Compute Pass"),
     });
-    cpass.set_pipeline(compute_pipeline);
-    cpass.set_bind_group(0, compute_bind_group, &[]);
+    cpass.set_pipeline(&compute_pipeline);
+    cpass.set_bind_group(0, &compute_bind_group, &[]);
     cpass.dispatch(line_count as u32, 1, 1);
     drop(cpass);
 
     // Draw beam meshes
-    println!(
+    tracing::trace!(
         "Drawing {} mesh indices ({} triangles)",
         mesh_index_count,
         mesh_index_count / 3
@@ -845,15 +979,15 @@ pub fn phosphor_render(
     let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
         label: None,
         color_attachments: &[RenderPassColorAttachment {
-            view: beam_multisample_view,
-            resolve_target: Some(beam_buffer_view),
+            view: &beam_multisample_view,
+            resolve_target: Some(&beam_buffer_view),
             ops: Operations {
                 load: LoadOp::Clear(CLEAR_COLOR),
                 store: true,
             },
         }],
         depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
-            view: beam_depth_view,
+            view: &beam_depth_view,
             depth_ops: Some(Operations {
                 load: LoadOp::Clear(1.0),
                 store: true,
@@ -861,10 +995,10 @@ pub fn phosphor_render(
             stencil_ops: None,
         }),
     });
-    rpass.set_pipeline(beam_mesh_pipeline);
+    rpass.set_pipeline(&beam_mesh_pipeline);
     rpass.set_vertex_buffer(0, mesh_vertex_buffer.slice(..));
     rpass.set_index_buffer(mesh_index_buffer.slice(..), IndexFormat::Uint16);
-    rpass.set_bind_group(0, uniform_bind_group, &[]);
+    rpass.set_bind_group(0, &uniform_bind_group, &[]);
     rpass.draw_indexed(0..mesh_index_count as u32, 0, 0..1);
     drop(rpass);
 
@@ -872,15 +1006,15 @@ pub fn phosphor_render(
     let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
         label: None,
         color_attachments: &[RenderPassColorAttachment {
-            view: beam_multisample_view,
-            resolve_target: Some(beam_buffer_view),
+            view: &beam_multisample_view,
+            resolve_target: Some(&beam_buffer_view),
             ops: Operations {
                 load: LoadOp::Load,
                 store: true,
             },
         }],
         depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
-            view: beam_depth_view,
+            view: &beam_depth_view,
             depth_ops: Some(Operations {
                 load: LoadOp::Load,
                 store: false,
@@ -888,10 +1022,10 @@ pub fn phosphor_render(
             stencil_ops: None,
         }),
     });
-    rpass.set_pipeline(beam_line_pipeline);
+    rpass.set_pipeline(&beam_line_pipeline);
     rpass.set_vertex_buffer(0, line_vertex_buffer.slice(..));
     rpass.set_vertex_buffer(1, line_instance_buffer.slice(..));
-    rpass.set_bind_group(0, uniform_bind_group, &[]);
+    rpass.set_bind_group(0, &uniform_bind_group, &[]);
 
     rpass.draw(0..14, 0..line_count as u32);
     drop(rpass);
@@ -913,8 +1047,8 @@ pub fn phosphor_render(
         }],
         depth_stencil_attachment: None,
     });
-    rpass.set_pipeline(phosphor_decay_pipeline);
-    rpass.set_bind_group(0, uniform_bind_group, &[]);
+    rpass.set_pipeline(&phosphor_decay_pipeline);
+    rpass.set_bind_group(0, &uniform_bind_group, &[]);
     rpass.set_bind_group(
         1,
         if buffer_flip_state {
@@ -931,7 +1065,7 @@ pub fn phosphor_render(
     let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
         label: None,
         color_attachments: &[RenderPassColorAttachment {
-            view: render_attachment_view,
+            view: &render_attachment_view,
             resolve_target: None,
             ops: Operations {
                 load: LoadOp::Clear(Color::BLACK),
@@ -940,7 +1074,7 @@ pub fn phosphor_render(
         }],
         depth_stencil_attachment: None,
     });
-    rpass.set_pipeline(tonemap_pipeline);
+    rpass.set_pipeline(&tonemap_pipeline);
     rpass.set_bind_group(
         0,
         if buffer_flip_state {
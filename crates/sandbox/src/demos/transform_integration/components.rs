@@ -1,52 +1,17 @@
-use antigen_core::RwLock;
-
-use crate::impl_read_write_lock;
+use antigen_core::{Construct, ReadWriteLock, RwLock};
 
 // Position
-#[derive(Debug, Default)]
+#[derive(Debug, Default, ReadWriteLock, Construct)]
 pub struct Position(RwLock<(f32, f32, f32)>);
 
-impl Position {
-    pub fn new(position: (f32, f32, f32)) -> Self {
-        Position(RwLock::new(position))
-    }
-}
-
-impl_read_write_lock!(Position, 0, (f32, f32, f32));
-
 // Rotation
-#[derive(Debug, Default)]
+#[derive(Debug, Default, ReadWriteLock, Construct)]
 pub struct Rotation(RwLock<f32>);
 
-impl Rotation {
-    pub fn new(rotation: f32) -> Self {
-        Rotation(RwLock::new(rotation))
-    }
-}
-
-impl_read_write_lock!(Rotation, 0, f32);
-
 // Linear velocity
-#[derive(Debug, Default)]
+#[derive(Debug, Default, ReadWriteLock, Construct)]
 pub struct LinearVelocity(RwLock<(f32, f32, f32)>);
 
-impl_read_write_lock!(LinearVelocity, 0, (f32, f32, f32));
-
-impl LinearVelocity {
-    pub fn new(velocity: (f32, f32, f32)) -> Self {
-        LinearVelocity(RwLock::new(velocity))
-    }
-}
-
 // Angular velocity
-#[derive(Debug, Default)]
+#[derive(Debug, Default, ReadWriteLock, Construct)]
 pub struct AngularVelocity(RwLock<f32>);
-
-impl_read_write_lock!(AngularVelocity, 0, f32);
-
-impl AngularVelocity {
-    pub fn new(angular_velocity: f32) -> Self {
-        AngularVelocity(RwLock::new(angular_velocity))
-    }
-}
-
@@ -1,24 +1,53 @@
 mod components;
 mod systems;
 
-use antigen_core::{ImmutableSchedule, Parallel, Serial, parallel, serial};
+use antigen_core::{
+    parallel, serial, App, ImmutableSchedule, IntoScheduledSystem, Parallel, Plugin, Scene,
+    SceneRegistry, Serial, Stage,
+};
 pub use components::*;
 pub use systems::*;
 
+const SCENE_PATH: &str = "crates/sandbox/src/demos/transform_integration/scene.ron";
+
+pub fn scene_registry() -> SceneRegistry {
+    SceneRegistry::default()
+        .register_construct::<Position, (f32, f32, f32), _>("position")
+        .register_construct::<Rotation, f32, _>("rotation")
+        .register_construct::<LinearVelocity, (f32, f32, f32), _>("linear_velocity")
+        .register_construct::<AngularVelocity, f32, _>("angular_velocity")
+}
+
 #[legion::system]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
-    cmd.push((
-        Position::default(),
-        Rotation::default(),
-        LinearVelocity::new((1.0, 1.0, 1.0)),
-        AngularVelocity::new(0.5),
-    ));
+    let spawned = Scene::load(SCENE_PATH).and_then(|scene| scene.spawn(&scene_registry(), cmd));
+
+    if let Err(e) = spawned {
+        tracing::error!("Failed to spawn {}: {}", SCENE_PATH, e);
+    }
 }
 
 pub fn integrate_schedule() -> ImmutableSchedule<Parallel> {
-    parallel![integrate_position_system(), integrate_rotation_system(),]
+    parallel![
+        integrate_position_system()
+            .reads_lock::<LinearVelocity>()
+            .writes_lock::<Position>(),
+        integrate_rotation_system()
+            .reads_lock::<AngularVelocity>()
+            .writes_lock::<Rotation>(),
+    ]
 }
 
 pub fn print_schedule() -> ImmutableSchedule<Serial> {
     serial![print_position_system(), print_rotation_system(),]
 }
+
+pub struct TransformIntegrationPlugin;
+
+impl<E> Plugin<E> for TransformIntegrationPlugin {
+    fn build(&self, app: App<E>) -> App<E> {
+        app.add_system(Stage::Assemble, assemble_system())
+            .add_system(Stage::Tick, integrate_schedule().label("integrate"))
+            .add_system(Stage::Tick, print_schedule().after("integrate"))
+    }
+}
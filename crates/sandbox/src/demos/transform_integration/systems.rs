@@ -21,14 +21,14 @@ pub fn integrate_rotation(angular_velocity: &AngularVelocity, rotation: &Rotatio
     *rotation += *angular_velocity;
 }
 
-// Print position components
+// Log position components
 #[legion::system(par_for_each)]
 pub fn print_position(position: &Position) {
-    println!("Position: {:#?}", position.read());
+    tracing::trace!("Position: {:?}", position.read());
 }
 
-// Print rotation components
+// Log rotation components
 #[legion::system(par_for_each)]
 pub fn print_rotation(rotation: &Rotation) {
-    println!("Rotation: {:#?}", rotation.read());
+    tracing::trace!("Rotation: {:?}", rotation.read());
 }
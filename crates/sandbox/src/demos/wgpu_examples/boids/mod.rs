@@ -7,11 +7,14 @@ use antigen_winit::{AssembleWinit, RedrawUnconditionally};
 pub use components::*;
 pub use systems::*;
 
-use antigen_core::{AddIndirectComponent, ImmutableSchedule, RwLock, Serial, Single, parallel, serial, single};
+use antigen_core::{
+    AddIndirectComponent, ChangeTracker, ImmutableResources, ImmutableSchedule, RwLock, Serial,
+    Single, parallel, serial, single,
+};
 
 use antigen_wgpu::{
     wgpu::{
-        util::BufferInitDescriptor, BufferAddress, BufferDescriptor, BufferUsages, Device,
+        util::BufferInitDescriptor, BufferAddress, BufferDescriptor, BufferUsages,
         ShaderModuleDescriptor, ShaderSource,
     },
     AssembleWgpu, RenderAttachmentTextureView, SurfaceConfigurationComponent,
@@ -23,7 +26,6 @@ const NUM_PARTICLES: usize = 1500;
 const PARTICLES_PER_GROUP: usize = 64;
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -149,27 +151,46 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     );
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_with_usage_system::<Compute>(),
-            antigen_wgpu::create_shader_modules_with_usage_system::<Draw>(),
-            antigen_wgpu::create_buffers_init_system::<Vertex>(),
-            antigen_wgpu::create_buffers_init_system::<Uniform>(),
-            antigen_wgpu::create_buffers_system::<FrontBuffer>(),
-            antigen_wgpu::create_buffers_system::<BackBuffer>(),
+            antigen_wgpu::create_shader_modules_with_usage_system::<Compute>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_shader_modules_with_usage_system::<Draw>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_buffers_init_system::<Vertex>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_buffers_init_system::<Uniform>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_buffers_system::<FrontBuffer>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_buffers_system::<BackBuffer>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
         ],
         parallel![
             antigen_wgpu::buffer_write_system::<FrontBuffer, Arc<RwLock<Vec<f32>>>, Vec<f32>>(),
             antigen_wgpu::buffer_write_system::<BackBuffer, Arc<RwLock<Vec<f32>>>, Vec<f32>>(),
         ],
-        boids_prepare_system(),
+        boids_prepare_system(resources.clone()),
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    println!("Allocating render schedule");
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    tracing::debug!("Allocating render schedule");
     single![boids_render_system(
+        resources.clone(),
         AtomicUsize::new(0),
         ((NUM_PARTICLES as f32) / (PARTICLES_PER_GROUP as f32)).ceil() as u32,
     )]
@@ -5,7 +5,9 @@ use super::{
     DrawShaderModuleComponent, FrontBufferBindGroupComponent, FrontBufferComponent,
     UniformBufferComponent, VertexBufferComponent, NUM_PARTICLES,
 };
-use antigen_core::{GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock};
+use antigen_core::{
+    GetIndirect, ImmutableResources, IndirectComponent, LazyReadWriteLock, ReadWriteLock,
+};
 
 use antigen_wgpu::{
     wgpu::{
@@ -20,13 +22,11 @@ use antigen_wgpu::{
     RenderPipelineComponent, SurfaceConfigurationComponent,
 };
 
-use legion::IntoQuery;
-
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn boids_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Boids,
     compute_shader: &ComputeShaderModuleComponent,
@@ -44,36 +44,31 @@ pub fn boids_prepare(
         return;
     }
 
-    let compute_shader = compute_shader.read();
-    let compute_shader = if let LazyComponent::Ready(compute_shader) = &*compute_shader {
+    let compute_shader = if let Some(compute_shader) = compute_shader.read_ready() {
         compute_shader
     } else {
         return;
     };
 
-    let draw_shader = draw_shader.read();
-    let draw_shader = if let LazyComponent::Ready(draw_shader) = &*draw_shader {
+    let draw_shader = if let Some(draw_shader) = draw_shader.read_ready() {
         draw_shader
     } else {
         return;
     };
 
-    let sim_param_buffer = sim_param_buffer.read();
-    let sim_param_buffer = if let LazyComponent::Ready(sim_param_buffer) = &*sim_param_buffer {
+    let sim_param_buffer = if let Some(sim_param_buffer) = sim_param_buffer.read_ready() {
         sim_param_buffer
     } else {
         return;
     };
 
-    let front_buffer = front_buffer.read();
-    let front_buffer = if let LazyComponent::Ready(front_buffer) = &*front_buffer {
+    let front_buffer = if let Some(front_buffer) = front_buffer.read_ready() {
         front_buffer
     } else {
         return;
     };
 
-    let back_buffer = back_buffer.read();
-    let back_buffer = if let LazyComponent::Ready(back_buffer) = &*back_buffer {
+    let back_buffer = if let Some(back_buffer) = back_buffer.read_ready() {
         back_buffer
     } else {
         return;
@@ -83,7 +78,11 @@ pub fn boids_prepare(
         world.get_indirect(surface_configuration_component).unwrap();
     let config = surface_configuration_component.read();
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
     let compute_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
         entries: &[
@@ -226,9 +225,9 @@ pub fn boids_prepare(
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn boids_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Boids,
     render_pipeline: &RenderPipelineComponent,
@@ -243,58 +242,51 @@ pub fn boids_render(
     #[state] frame_num_atomic: &AtomicUsize,
     #[state] work_group_count: &u32,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let render_pipeline = render_pipeline.read();
-    let render_pipeline = if let LazyComponent::Ready(render_pipeline) = &*render_pipeline {
+    let render_pipeline = if let Some(render_pipeline) = render_pipeline.read_ready() {
         render_pipeline
     } else {
         return;
     };
 
-    let compute_pipeline = compute_pipeline.read();
-    let compute_pipeline = if let LazyComponent::Ready(compute_pipeline) = &*compute_pipeline {
+    let compute_pipeline = if let Some(compute_pipeline) = compute_pipeline.read_ready() {
         compute_pipeline
     } else {
         return;
     };
 
-    let vertex_buffer = vertex_buffer.read();
-    let vertex_buffer = if let LazyComponent::Ready(vertex_buffer) = &*vertex_buffer {
+    let vertex_buffer = if let Some(vertex_buffer) = vertex_buffer.read_ready() {
         vertex_buffer
     } else {
         return;
     };
 
-    let front_buffer = front_buffer.read();
-    let front_buffer = if let LazyComponent::Ready(front_buffer) = &*front_buffer {
+    let front_buffer = if let Some(front_buffer) = front_buffer.read_ready() {
         front_buffer
     } else {
         return;
     };
 
-    let back_buffer = back_buffer.read();
-    let back_buffer = if let LazyComponent::Ready(back_buffer) = &*back_buffer {
+    let back_buffer = if let Some(back_buffer) = back_buffer.read_ready() {
         back_buffer
     } else {
         return;
     };
 
-    let front_buffer_bind_group = front_buffer_bind_group.read();
     let front_buffer_bind_group =
-        if let LazyComponent::Ready(front_buffer_bind_group) = &*front_buffer_bind_group {
+        if let Some(front_buffer_bind_group) = front_buffer_bind_group.read_ready() {
             front_buffer_bind_group
         } else {
             return;
         };
 
-    let back_buffer_bind_group = back_buffer_bind_group.read();
     let back_buffer_bind_group =
-        if let LazyComponent::Ready(back_buffer_bind_group) = &*back_buffer_bind_group {
+        if let Some(back_buffer_bind_group) = back_buffer_bind_group.read_ready() {
             back_buffer_bind_group
         } else {
             return;
@@ -304,8 +296,7 @@ pub fn boids_render(
 
     let frame_num = frame_num_atomic.load(Ordering::Relaxed);
 
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
@@ -335,7 +326,7 @@ pub fn boids_render(
     let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
         label: None,
         color_attachments: &[RenderPassColorAttachment {
-            view: texture_view,
+            view: &texture_view,
             resolve_target: None,
             ops: Operations {
                 load: LoadOp::Clear(Color::BLACK),
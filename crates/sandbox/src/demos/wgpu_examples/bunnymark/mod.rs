@@ -3,13 +3,13 @@ mod systems;
 
 use std::{borrow::Cow, num::NonZeroU32};
 
-use antigen_winit::{AssembleWinit, RedrawUnconditionally, WindowComponent};
+use antigen_winit::{AssembleWinit, RedrawUnconditionally, WindowComponent, WindowEventReader};
 pub use components::*;
-use legion::{world::SubWorld, IntoQuery};
 pub use systems::*;
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, Construct, ImmutableSchedule, Serial, Single,
+    parallel, serial, single, AddIndirectComponent, ChangeTracker, Construct, ImmutableResources,
+    ImmutableSchedule, Serial, Single,
 };
 
 use antigen_wgpu::{
@@ -58,9 +58,12 @@ impl ToBytes for Locals {
 }
 
 #[legion::system]
-#[read_component(Device)]
-pub fn assemble(world: &SubWorld, cmd: &mut legion::systems::CommandBuffer) {
-    let device = <&Device>::query().iter(world).next().unwrap();
+pub fn assemble(#[state] resources: &ImmutableResources, cmd: &mut legion::systems::CommandBuffer) {
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -215,29 +218,53 @@ pub fn assemble(world: &SubWorld, cmd: &mut legion::systems::CommandBuffer) {
     cmd.add_component(renderer_entity, PlayfieldExtentComponent::construct(extent));
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_system(),
-            antigen_wgpu::create_buffers_system::<Global>(),
-            antigen_wgpu::create_buffers_system::<Local>(),
-            antigen_wgpu::create_textures_system::<Logo>(),
-            antigen_wgpu::create_texture_views_system::<Logo>(),
-            antigen_wgpu::create_samplers_with_usage_system::<Logo>(),
+            antigen_wgpu::create_shader_modules_system(resources.clone(), ChangeTracker::default()),
+            antigen_wgpu::create_buffers_system::<Global>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<Local>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_textures_system::<Logo>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_texture_views_system::<Logo>(ChangeTracker::default()),
+            antigen_wgpu::create_samplers_with_usage_system::<Logo>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
         ],
         parallel![
-            antigen_wgpu::buffer_write_system::<Global, GlobalDataComponent, Globals>(),
-            antigen_wgpu::buffer_write_system::<Local, BunniesComponent, Vec<Locals>>(),
-            antigen_wgpu::texture_write_system::<Logo, TexelDataComponent, Vec<u8>>(),
+            antigen_wgpu::buffer_write_system::<Global, GlobalDataComponent, Globals>(
+                resources.clone()
+            ),
+            antigen_wgpu::buffer_write_system::<Local, BunniesComponent, Vec<Locals>>(
+                resources.clone()
+            ),
+            antigen_wgpu::texture_write_system::<Logo, TexelDataComponent, Vec<u8>>(
+                resources.clone()
+            ),
         ],
-        bunnymark_prepare_system(),
+        bunnymark_prepare_system(resources.clone()),
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Serial> {
-    serial![bunnymark_tick_system(), bunnymark_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
+    serial![
+        bunnymark_tick_system(),
+        bunnymark_render_system(resources.clone())
+    ]
 }
 
-pub fn keyboard_event_schedule() -> ImmutableSchedule<Single> {
-    single![bunnymark_key_event_system()]
+pub fn keyboard_event_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![bunnymark_key_event_system(
+        resources.clone(),
+        WindowEventReader::default()
+    )]
 }
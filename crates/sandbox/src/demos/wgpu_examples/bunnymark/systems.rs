@@ -6,12 +6,13 @@ use super::{
     LogoTextureViewComponent, PlayfieldExtentComponent, BUNNY_SIZE, GRAVITY,
 };
 use antigen_core::{
-    Changed, ChangedTrait, GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock,
+    Changed, ChangedTrait, GetIndirect, ImmutableResources, IndirectComponent, LazyReadWriteLock,
+    ReadWriteLock,
 };
 
 use antigen_winit::{
     winit::event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent},
-    WindowComponent, WindowEventComponent,
+    WindowComponent, WindowEventReader, WindowEvents,
 };
 
 use antigen_wgpu::{
@@ -32,9 +33,9 @@ use legion::{world::SubWorld, IntoQuery};
 
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn bunnymark_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Bunnymark,
     shader_module: &ShaderModuleComponent,
@@ -47,47 +48,46 @@ pub fn bunnymark_prepare(
     local_bind_group: &LocalBindGroupComponent,
     surface_configuration_component: &IndirectComponent<SurfaceConfigurationComponent>,
 ) {
-    println!("Bunnymark prepare");
+    tracing::trace!("Bunnymark prepare");
 
     if !render_pipeline_component.read().is_pending() {
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
     let surface_configuration_component =
         world.get_indirect(surface_configuration_component).unwrap();
     let config = surface_configuration_component.read();
 
-    let shader_module = shader_module.read();
-    let shader_module = if let LazyComponent::Ready(shader_module) = &*shader_module {
+    let shader_module = if let Some(shader_module) = shader_module.read_ready() {
         shader_module
     } else {
         return;
     };
 
-    let global_buffer = global_buffer.read();
-    let global_buffer = if let LazyComponent::Ready(global_buffer) = &*global_buffer {
+    let global_buffer = if let Some(global_buffer) = global_buffer.read_ready() {
         global_buffer
     } else {
         return;
     };
 
-    let local_buffer = local_buffer.read();
-    let local_buffer = if let LazyComponent::Ready(local_buffer) = &*local_buffer {
+    let local_buffer = if let Some(local_buffer) = local_buffer.read_ready() {
         local_buffer
     } else {
         return;
     };
 
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
     };
 
-    let sampler = sampler.read();
-    let sampler = if let LazyComponent::Ready(sampler) = &*sampler {
+    let sampler = if let Some(sampler) = sampler.read_ready() {
         sampler
     } else {
         return;
@@ -209,7 +209,7 @@ pub fn bunnymark_prepare(
             label: None,
         }));
 
-    println!("Bunnymark prepare complete");
+    tracing::trace!("Bunnymark prepare complete");
 }
 
 #[legion::system(par_for_each)]
@@ -234,9 +234,9 @@ pub fn bunnymark_tick(bunnies: &Changed<BunniesComponent>, extent: &PlayfieldExt
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn bunnymark_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Bunnymark,
     bunnies: &Changed<BunniesComponent>,
@@ -246,32 +246,30 @@ pub fn bunnymark_render(
     local_bind_group: &LocalBindGroupComponent,
     texture_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    println!("Bunnymark render");
+    tracing::trace!("Bunnymark render");
 
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let global_bind_group = global_bind_group.read();
-    let global_bind_group = if let LazyComponent::Ready(global_bind_group) = &*global_bind_group {
+    let global_bind_group = if let Some(global_bind_group) = global_bind_group.read_ready() {
         global_bind_group
     } else {
         return;
     };
 
-    let local_bind_group = local_bind_group.read();
-    let local_bind_group = if let LazyComponent::Ready(local_bind_group) = &*local_bind_group {
+    let local_bind_group = if let Some(local_bind_group) = local_bind_group.read_ready() {
         local_bind_group
     } else {
         return;
     };
 
-    if let LazyComponent::Ready(render_pipeline) = &*render_pipeline.read() {
+    if let Some(render_pipeline) = render_pipeline.read_ready() {
         let texture_view = world.get_indirect(texture_view).unwrap();
 
-        if let LazyComponent::Ready(texture_view) = &*texture_view.read() {
+        if let Some(texture_view) = texture_view.read_ready() {
             let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
             {
                 let clear_color = Color {
@@ -308,36 +306,28 @@ pub fn bunnymark_render(
         }
     }
 
-    println!("Bunnymark render complete");
+    tracing::trace!("Bunnymark render complete");
 }
 
-#[legion::system(par_for_each)]
+#[legion::system]
+#[read_component(IndirectComponent<WindowComponent>)]
+#[read_component(Changed<BunniesComponent>)]
+#[read_component(PlayfieldExtentComponent)]
 #[read_component(WindowComponent)]
-#[read_component(WindowEventComponent)]
 pub fn bunnymark_key_event(
+    #[state] resources: &ImmutableResources,
+    #[state] reader: &WindowEventReader,
     world: &SubWorld,
-    window: &IndirectComponent<WindowComponent>,
-    bunnies: &Changed<BunniesComponent>,
-    extent: &PlayfieldExtentComponent,
 ) {
-    let window = world
-        .get_indirect(window)
-        .expect("No indirect WindowComponent");
-    let window = window.read();
-    let window = if let LazyComponent::Ready(window) = &*window {
-        window
+    let window_events = if let Some(window_events) = resources.get_or_warn::<WindowEvents>() {
+        window_events
     } else {
         return;
     };
 
-    let window_event = <&WindowEventComponent>::query()
-        .iter(world)
-        .next()
-        .expect("No WindowEventComponent");
-    let window_event = window_event.read();
-    let window_id = if let (
-        Some(window_id),
-        Some(WindowEvent::KeyboardInput {
+    let mut presses = vec![];
+    reader.read(&window_events, |(window_id, event)| {
+        if let WindowEvent::KeyboardInput {
             input:
                 KeyboardInput {
                     state: ElementState::Pressed,
@@ -345,34 +335,55 @@ pub fn bunnymark_key_event(
                     ..
                 },
             ..
-        }),
-    ) = &*window_event
-    {
-        window_id
-    } else {
-        return;
-    };
+        } = event
+        {
+            presses.push(*window_id);
+        }
+    });
 
-    if window.id() != *window_id {
+    if presses.is_empty() {
         return;
     }
 
-    let spawn_count = 64 + bunnies.read().len() / 2;
-    let color = rand::random::<u32>();
-    println!(
-        "Spawning {} bunnies, total at {}",
-        spawn_count,
-        bunnies.read().len() + spawn_count
-    );
-
-    let mut bunnies = bunnies.write();
-    for _ in 0..spawn_count {
-        let speed = rand::random::<f32>() * MAX_VELOCITY - (MAX_VELOCITY * 0.5);
-        bunnies.push(Locals {
-            position: [0.0, 0.5 * (extent.read().0 as f32)],
-            velocity: [speed, 0.0],
-            color,
-            _pad: [0; 3],
-        });
-    }
+    <(
+        &IndirectComponent<WindowComponent>,
+        &Changed<BunniesComponent>,
+        &PlayfieldExtentComponent,
+    )>::query()
+    .for_each(world, |(window, bunnies, extent)| {
+        let window = world
+            .get_indirect(window)
+            .expect("No indirect WindowComponent");
+        let window = if let Some(window) = window.read_ready() {
+            window
+        } else {
+            return;
+        };
+
+        let press_count = presses
+            .iter()
+            .filter(|window_id| **window_id == window.id())
+            .count();
+
+        for _ in 0..press_count {
+            let spawn_count = 64 + bunnies.read().len() / 2;
+            let color = rand::random::<u32>();
+            tracing::info!(
+                "Spawning {} bunnies, total at {}",
+                spawn_count,
+                bunnies.read().len() + spawn_count
+            );
+
+            let mut bunnies = bunnies.write();
+            for _ in 0..spawn_count {
+                let speed = rand::random::<f32>() * MAX_VELOCITY - (MAX_VELOCITY * 0.5);
+                bunnies.push(Locals {
+                    position: [0.0, 0.5 * (extent.read().0 as f32)],
+                    velocity: [speed, 0.0],
+                    color,
+                    _pad: [0; 3],
+                });
+            }
+        }
+    });
 }
@@ -5,11 +5,14 @@ use antigen_winit::AssembleWinit;
 pub use components::*;
 pub use systems::*;
 
-use antigen_core::{AddIndirectComponent,  ImmutableSchedule, Serial, Single, Usage, serial, single};
+use antigen_core::{
+    AddIndirectComponent, ChangeTracker, ImmutableResources, ImmutableSchedule, Serial, Single,
+    Usage, serial, single,
+};
 
 use antigen_wgpu::{
     wgpu::{
-        Device, Extent3d, FilterMode, SamplerDescriptor, ShaderModuleDescriptor, ShaderSource,
+        Extent3d, FilterMode, SamplerDescriptor, ShaderModuleDescriptor, ShaderSource,
         TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
     },
     AssembleWgpu, RenderAttachmentTextureView, SurfaceConfigurationComponent,
@@ -19,7 +22,6 @@ use antigen_wgpu::{
 const RENDER_TARGET_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -98,17 +100,29 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     cmd.add_indirect_component_self::<Usage<LowResTarget, TextureViewDescriptorComponent>>(renderer_entity);
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
-        antigen_wgpu::create_shader_modules_with_usage_system::<TriangleAndLines>(),
-        antigen_wgpu::create_shader_modules_with_usage_system::<Upscale>(),
-        antigen_wgpu::create_textures_system::<LowResTarget>(),
-        antigen_wgpu::create_texture_views_system::<LowResTarget>(),
-        antigen_wgpu::create_samplers_with_usage_system::<LowResTarget>(),
-        conservative_raster_prepare_system()
+        antigen_wgpu::create_shader_modules_with_usage_system::<TriangleAndLines>(
+            resources.clone(),
+            ChangeTracker::default(),
+        ),
+        antigen_wgpu::create_shader_modules_with_usage_system::<Upscale>(
+            resources.clone(),
+            ChangeTracker::default(),
+        ),
+        antigen_wgpu::create_textures_system::<LowResTarget>(
+            resources.clone(),
+            ChangeTracker::default(),
+        ),
+        antigen_wgpu::create_texture_views_system::<LowResTarget>(ChangeTracker::default()),
+        antigen_wgpu::create_samplers_with_usage_system::<LowResTarget>(
+            resources.clone(),
+            ChangeTracker::default(),
+        ),
+        conservative_raster_prepare_system(resources.clone())
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![conservative_raster_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![conservative_raster_render_system(resources.clone())]
 }
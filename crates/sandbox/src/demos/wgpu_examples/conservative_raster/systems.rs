@@ -8,7 +8,8 @@ use super::{
     UpscaleShaderComponent,
 };
 use antigen_core::{
-    ChangedTrait, GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock, Usage,
+    ChangeTracker, ChangedTrait, GetIndirect, ImmutableResources, IndirectComponent,
+    LazyReadWriteLock, ReadWriteLock, Usage,
 };
 
 use antigen_wgpu::{
@@ -24,13 +25,13 @@ use antigen_wgpu::{
     TextureViewDescriptorComponent,
 };
 
-use legion::{world::SubWorld, IntoQuery};
+use legion::{world::SubWorld, Entity};
 
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn conservative_raster_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &ConservativeRaster,
     shader_triangle_and_lines: &TriangleAndLinesShaderComponent,
@@ -45,7 +46,11 @@ pub fn conservative_raster_prepare(
     low_res_sampler: &LowResSamplerComponent,
     surface_config_component: &IndirectComponent<SurfaceConfigurationComponent>,
 ) {
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
     if bind_group_layout_upscale_component.read().is_pending() {
         let bind_group_layout_upscale =
@@ -75,33 +80,29 @@ pub fn conservative_raster_prepare(
             .write()
             .set_ready(bind_group_layout_upscale);
 
-        println!("Created upscale bind group");
+        tracing::debug!("Created upscale bind group");
     }
 
-    let bind_group_layout_upscale = bind_group_layout_upscale_component.read();
     let bind_group_layout_upscale =
-        if let LazyComponent::Ready(bind_group_layout_upscale) = &*bind_group_layout_upscale {
+        if let Some(bind_group_layout_upscale) = bind_group_layout_upscale_component.read_ready() {
             bind_group_layout_upscale
         } else {
             unreachable!();
         };
 
-    let low_res_view = low_res_view.read();
-    let low_res_view = if let LazyComponent::Ready(low_res_view) = &*low_res_view {
+    let low_res_view = if let Some(low_res_view) = low_res_view.read_ready() {
         low_res_view
     } else {
         return;
     };
 
-    let low_res_sampler = low_res_sampler.read();
-    let low_res_sampler = if let LazyComponent::Ready(low_res_sampler) = &*low_res_sampler {
+    let low_res_sampler = if let Some(low_res_sampler) = low_res_sampler.read_ready() {
         low_res_sampler
     } else {
         return;
     };
 
-    let shader_upscale = shader_upscale.read();
-    let shader_upscale = if let LazyComponent::Ready(shader_upscale) = &*shader_upscale {
+    let shader_upscale = if let Some(shader_upscale) = shader_upscale.read_ready() {
         shader_upscale
     } else {
         return;
@@ -128,7 +129,7 @@ pub fn conservative_raster_prepare(
             .write()
             .set_ready(low_res_bind_group);
 
-        println!("Created low-res target");
+        tracing::debug!("Created low-res target");
     }
 
     if !pipeline_conservative_component.read().is_pending() {
@@ -138,9 +139,8 @@ pub fn conservative_raster_prepare(
     let surface_configuration_component = world.get_indirect(surface_config_component).unwrap();
     let config = surface_configuration_component.read();
 
-    let shader_triangle_and_lines = shader_triangle_and_lines.read();
     let shader_triangle_and_lines =
-        if let LazyComponent::Ready(shader_triangle_and_lines) = &*shader_triangle_and_lines {
+        if let Some(shader_triangle_and_lines) = shader_triangle_and_lines.read_ready() {
             shader_triangle_and_lines
         } else {
             return;
@@ -257,7 +257,7 @@ pub fn conservative_raster_prepare(
         .write()
         .set_ready(pipeline_upscale);
 
-    println!("Initialized conservative raster renderer");
+    tracing::debug!("Initialized conservative raster renderer");
 }
 
 #[legion::system(par_for_each)]
@@ -265,7 +265,9 @@ pub fn conservative_raster_prepare(
 #[read_component(LowResTextureDescriptorComponent<'static>)]
 #[read_component(Usage<LowResTarget, TextureViewDescriptorComponent<'static>>)]
 pub fn conservative_raster_resize(
+    #[state] tracker: &ChangeTracker,
     world: &SubWorld,
+    entity: &Entity,
     _: &ConservativeRaster,
     surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
     bind_group_upscale_component: &UpscaleBindGroupComponent,
@@ -279,11 +281,11 @@ pub fn conservative_raster_resize(
     let low_res_desc = world.get_indirect(low_res_desc).unwrap();
     let low_res_view_desc = world.get_indirect(low_res_view_desc).unwrap();
 
-    if !surface_config.get_changed() {
+    if !tracker.observe(*entity, surface_config) {
         return;
     }
 
-    println!("Surface config changed, recreating low-res target, view and bind group");
+    tracing::debug!("Surface config changed, recreating low-res target, view and bind group");
 
     let surface_config = surface_config.read();
     low_res_desc.write().size = Extent3d {
@@ -299,9 +301,9 @@ pub fn conservative_raster_resize(
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn conservative_raster_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &ConservativeRaster,
     pipeline_triangle_conservative: &TriangleConservativePipelineComponent,
@@ -313,62 +315,51 @@ pub fn conservative_raster_render(
     low_res_view: &LowResTextureViewComponent,
     render_attachment_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let pipeline_triangle_conservative = pipeline_triangle_conservative.read();
     let pipeline_triangle_conservative =
-        if let LazyComponent::Ready(pipeline_triangle_conservative) =
-            &*pipeline_triangle_conservative
-        {
+        if let Some(pipeline_triangle_conservative) = pipeline_triangle_conservative.read_ready() {
             pipeline_triangle_conservative
         } else {
             return;
         };
 
-    let pipeline_triangle_regular = pipeline_triangle_regular.read();
     let pipeline_triangle_regular =
-        if let LazyComponent::Ready(pipeline_triangle_regular) = &*pipeline_triangle_regular {
+        if let Some(pipeline_triangle_regular) = pipeline_triangle_regular.read_ready() {
             pipeline_triangle_regular
         } else {
             return;
         };
 
-    let pipeline_upscale = pipeline_upscale.read();
-    let pipeline_upscale = if let LazyComponent::Ready(pipeline_upscale) = &*pipeline_upscale {
+    let pipeline_upscale = if let Some(pipeline_upscale) = pipeline_upscale.read_ready() {
         pipeline_upscale
     } else {
         return;
     };
 
-    let bind_group_upscale = bind_group_upscale.read();
-    let bind_group_upscale = if let LazyComponent::Ready(bind_group_upscale) = &*bind_group_upscale
-    {
+    let bind_group_upscale = if let Some(bind_group_upscale) = bind_group_upscale.read_ready() {
         bind_group_upscale
     } else {
         return;
     };
 
     let texture_view = world.get_indirect(render_attachment_view).unwrap();
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
     };
 
-    let low_res_view = low_res_view.read();
-    let low_res_view = if let LazyComponent::Ready(low_res_view) = &*low_res_view {
+    let low_res_view = if let Some(low_res_view) = low_res_view.read_ready() {
         low_res_view
     } else {
         return;
     };
 
-    let pipeline_lines = pipeline_lines.read();
-
     let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
         label: Some("primary"),
     });
@@ -409,8 +400,8 @@ pub fn conservative_raster_render(
     rpass.set_bind_group(0, &bind_group_upscale, &[]);
     rpass.draw(0..3, 0..1);
 
-    if let LazyComponent::Ready(pipeline_lines) = &*pipeline_lines {
-        rpass.set_pipeline(pipeline_lines);
+    if let Some(pipeline_lines) = pipeline_lines.read_ready() {
+        rpass.set_pipeline(&pipeline_lines);
         rpass.draw(0..4, 0..1);
     };
     drop(rpass);
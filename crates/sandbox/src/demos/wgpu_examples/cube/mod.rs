@@ -7,12 +7,12 @@ use legion::systems::CommandBuffer;
 pub use systems::*;
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, Construct, ImmutableSchedule, RwLock,
-    Serial, Single, Usage,
+    parallel, serial, single, AddIndirectComponent, ChangeTracker, Construct, ImmutableResources,
+    ImmutableSchedule, RwLock, Serial, Single, Usage,
 };
 use antigen_wgpu::{
     wgpu::{
-        util::BufferInitDescriptor, BufferAddress, BufferDescriptor, BufferUsages, Device,
+        util::BufferInitDescriptor, BufferAddress, BufferDescriptor, BufferUsages,
         Extent3d, ImageCopyTextureBase, ImageDataLayout, ShaderModuleDescriptor, ShaderSource,
         TextureAspect, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
     },
@@ -132,7 +132,6 @@ fn generate_matrix(aspect_ratio: f32) -> nalgebra::Matrix4<f32> {
 }
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut CommandBuffer) {
     // Window
     let window_entity = cmd.push(());
@@ -276,17 +275,29 @@ pub fn assemble(cmd: &mut CommandBuffer) {
     );
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_system(),
-            antigen_wgpu::create_buffers_system::<Vertex>(),
-            antigen_wgpu::create_buffers_init_system::<Index>(),
-            antigen_wgpu::create_buffers_system::<Uniform>(),
-            antigen_wgpu::create_textures_system::<Mandelbrot>(),
+            antigen_wgpu::create_shader_modules_system(resources.clone(), ChangeTracker::default()),
+            antigen_wgpu::create_buffers_system::<Vertex>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_buffers_init_system::<Index>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_buffers_system::<Uniform>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_textures_system::<Mandelbrot>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
             antigen_wgpu::create_texture_views_system::<
                 crate::demos::wgpu_examples::cube::Mandelbrot,
-            >(),
+            >(ChangeTracker::default()),
         ],
         parallel![
             antigen_wgpu::buffer_write_system::<
@@ -299,13 +310,17 @@ pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
                 Usage<MeshUvs, RwLock<Vec<[f32; 2]>>>,
                 Vec<[f32; 2]>,
             >(),
-            antigen_wgpu::buffer_write_system::<Uniform, ViewProjectionMatrix, [f32; 16]>(),
-            antigen_wgpu::texture_write_system::<Mandelbrot, TexelsComponent, Vec<u8>>(),
+            antigen_wgpu::buffer_write_system::<Uniform, ViewProjectionMatrix, [f32; 16]>(
+                resources.clone(),
+            ),
+            antigen_wgpu::texture_write_system::<Mandelbrot, TexelsComponent, Vec<u8>>(
+                resources.clone(),
+            ),
         ],
-        cube_prepare_system(),
+        cube_prepare_system(resources.clone()),
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![cube_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![cube_render_system(resources.clone())]
 }
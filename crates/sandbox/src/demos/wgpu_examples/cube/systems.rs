@@ -5,8 +5,8 @@ use super::{
 };
 
 use antigen_core::{
-    Changed, ChangedTrait, GetIndirect, IndirectComponent, LazyComponent,
-    ReadWriteLock,
+    ChangeTracker, Changed, ChangedTrait, GetIndirect, ImmutableResources, IndirectComponent,
+    LazyReadWriteLock, ReadWriteLock,
 };
 use antigen_wgpu::{
     wgpu::{
@@ -23,13 +23,13 @@ use antigen_wgpu::{
     ShaderModuleComponent, SurfaceConfigurationComponent,
 };
 
-use legion::{world::SubWorld, IntoQuery};
+use legion::{world::SubWorld, Entity};
 
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn cube_prepare(
+    #[state] resources: &ImmutableResources,
     world: &SubWorld,
     _: &Cube,
     shader_module: &ShaderModuleComponent,
@@ -44,22 +44,19 @@ pub fn cube_prepare(
         return;
     }
 
-    let shader_module = shader_module.read();
-    let shader_module = if let LazyComponent::Ready(shader_module) = &*shader_module {
+    let shader_module = if let Some(shader_module) = shader_module.read_ready() {
         shader_module
     } else {
         return;
     };
 
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
     };
 
-    let uniform_buffer = uniform_buffer.read();
-    let uniform_buffer = if let LazyComponent::Ready(uniform_buffer) = &*uniform_buffer {
+    let uniform_buffer = if let Some(uniform_buffer) = uniform_buffer.read_ready() {
         uniform_buffer
     } else {
         return;
@@ -69,7 +66,11 @@ pub fn cube_prepare(
         world.get_indirect(surface_configuration_component).unwrap();
     let config = surface_configuration_component.read();
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
     // Create pipeline layout
     let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
@@ -114,7 +115,7 @@ pub fn cube_prepare(
             },
             BindGroupEntry {
                 binding: 1,
-                resource: BindingResource::TextureView(texture_view),
+                resource: BindingResource::TextureView(&texture_view),
             },
         ],
         label: None,
@@ -208,14 +209,16 @@ pub fn cube_prepare(
 #[legion::system(par_for_each)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn cube_resize(
+    #[state] tracker: &ChangeTracker,
     world: &SubWorld,
+    entity: &Entity,
     _: &Cube,
     surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
     view_projection: &Changed<ViewProjectionMatrix>,
 ) {
     let surface_config = world.get_indirect(surface_config).unwrap();
 
-    if surface_config.get_changed() {
+    if tracker.observe(*entity, surface_config) {
         let surface_config = surface_config.read();
         let aspect = surface_config.width as f32 / surface_config.height as f32;
         let matrix = super::generate_matrix(aspect);
@@ -226,9 +229,9 @@ pub fn cube_resize(
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn cube_render(
+    #[state] resources: &ImmutableResources,
     world: &SubWorld,
     _: &Cube,
     opaque_pipeline: &OpaquePassRenderPipelineComponent,
@@ -239,60 +242,55 @@ pub fn cube_render(
     command_buffers: &CommandBuffersComponent,
     texture_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    println!("Cube render");
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    tracing::trace!("Cube render");
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    println!("Device is ready");
+    tracing::trace!("Device is ready");
 
-    let opaque_pipeline = opaque_pipeline.read();
-    let opaque_pipeline = if let LazyComponent::Ready(opaque_pipeline) = &*opaque_pipeline {
+    let opaque_pipeline = if let Some(opaque_pipeline) = opaque_pipeline.read_ready() {
         opaque_pipeline
     } else {
         return;
     };
 
-    println!("Opaque pipeline is ready");
+    tracing::trace!("Opaque pipeline is ready");
 
-    let bind_group = bind_group.read();
-    let bind_group = if let LazyComponent::Ready(bind_group) = &*bind_group {
+    let bind_group = if let Some(bind_group) = bind_group.read_ready() {
         bind_group
     } else {
         return;
     };
 
-    println!("Bind group is ready");
+    tracing::trace!("Bind group is ready");
 
-    let vertex_buffer = vertex_buffer.read();
-    let vertex_buffer = if let LazyComponent::Ready(vertex_buffer) = &*vertex_buffer {
+    let vertex_buffer = if let Some(vertex_buffer) = vertex_buffer.read_ready() {
         vertex_buffer
     } else {
         return;
     };
 
-    println!("Vertex buffer is ready");
+    tracing::trace!("Vertex buffer is ready");
 
-    let index_buffer = index_buffer.read();
-    let index_buffer = if let LazyComponent::Ready(index_buffer) = &*index_buffer {
+    let index_buffer = if let Some(index_buffer) = index_buffer.read_ready() {
         index_buffer
     } else {
         return;
     };
 
-    println!("Index buffer is ready");
+    tracing::trace!("Index buffer is ready");
 
     let texture_view = world.get_indirect(texture_view).unwrap();
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
     };
 
-    println!("Texture view is ready");
+    tracing::trace!("Texture view is ready");
 
     let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
 
@@ -300,7 +298,7 @@ pub fn cube_render(
         let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
             label: None,
             color_attachments: &[RenderPassColorAttachment {
-                view: texture_view,
+                view: &texture_view,
                 resolve_target: None,
                 ops: Operations {
                     load: LoadOp::Clear(Color {
@@ -320,21 +318,21 @@ pub fn cube_render(
         let vertex_offset = vertex_count * std::mem::size_of::<[f32; 3]>() as BufferAddress;
 
         rpass.push_debug_group("Prepare data for draw.");
-        rpass.set_pipeline(opaque_pipeline);
-        rpass.set_bind_group(0, bind_group, &[]);
+        rpass.set_pipeline(&opaque_pipeline);
+        rpass.set_bind_group(0, &bind_group, &[]);
         rpass.set_index_buffer(index_buffer.slice(..), IndexFormat::Uint16);
         rpass.set_vertex_buffer(0, vertex_buffer.slice(..vertex_offset));
         rpass.set_vertex_buffer(1, vertex_buffer.slice(vertex_offset..));
         rpass.pop_debug_group();
         rpass.insert_debug_marker("Draw!");
         rpass.draw_indexed(0..index_count as u32, 0, 0..1);
-        if let LazyComponent::Ready(wire_pipeline) = &*wire_pipeline.read() {
-            rpass.set_pipeline(wire_pipeline);
+        if let Some(wire_pipeline) = wire_pipeline.read_ready() {
+            rpass.set_pipeline(&wire_pipeline);
             rpass.draw_indexed(0..index_count as u32, 0, 0..1);
         }
     }
 
     command_buffers.write().push(encoder.finish());
 
-    println!("Cube render complete");
+    tracing::trace!("Cube render complete");
 }
@@ -5,12 +5,14 @@ use antigen_winit::AssembleWinit;
 pub use components::*;
 pub use systems::*;
 
-use antigen_core::{AddIndirectComponent,  ImmutableSchedule, Serial, Single, serial, single};
+use antigen_core::{
+    AddIndirectComponent, ChangeTracker, ImmutableResources, ImmutableSchedule, Serial, Single,
+    serial, single,
+};
 
-use antigen_wgpu::{AssembleWgpu, RenderAttachmentTextureView, SurfaceConfigurationComponent, wgpu::{Device, ShaderModuleDescriptor, ShaderSource}};
+use antigen_wgpu::{AssembleWgpu, RenderAttachmentTextureView, SurfaceConfigurationComponent, wgpu::{ShaderModuleDescriptor, ShaderSource}};
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -38,13 +40,13 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     );
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
-        antigen_wgpu::create_shader_modules_system(),
-        hello_triangle_prepare_system()
+        antigen_wgpu::create_shader_modules_system(resources.clone(), ChangeTracker::default()),
+        hello_triangle_prepare_system(resources.clone())
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![hello_triangle_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![hello_triangle_render_system(resources.clone())]
 }
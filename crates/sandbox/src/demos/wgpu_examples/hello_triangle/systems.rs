@@ -1,5 +1,7 @@
 use super::HelloTriangle;
-use antigen_core::{ GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock};
+use antigen_core::{
+    GetIndirect, ImmutableResources, IndirectComponent, LazyReadWriteLock, ReadWriteLock,
+};
 
 use antigen_wgpu::{
     wgpu::{
@@ -11,13 +13,11 @@ use antigen_wgpu::{
     ShaderModuleComponent, SurfaceConfigurationComponent,
 };
 
-use legion::IntoQuery;
-
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn hello_triangle_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &HelloTriangle,
     shader_module: &ShaderModuleComponent,
@@ -27,10 +27,13 @@ pub fn hello_triangle_prepare(
     if !render_pipeline_component.read().is_pending() {
         return;
     }
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
-    let shader_module = shader_module.read();
-    let shader_module = if let LazyComponent::Ready(shader_module) = &*shader_module {
+    let shader_module = if let Some(shader_module) = shader_module.read_ready() {
         shader_module
     } else {
         return;
@@ -69,31 +72,29 @@ pub fn hello_triangle_prepare(
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn hello_triangle_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &HelloTriangle,
     render_pipeline: &RenderPipelineComponent,
     command_buffers: &CommandBuffersComponent,
     texture_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let render_pipeline = render_pipeline.read();
-    let render_pipeline = if let LazyComponent::Ready(render_pipeline) = &*render_pipeline {
+    let render_pipeline = if let Some(render_pipeline) = render_pipeline.read_ready() {
         render_pipeline
     } else {
         return;
     };
 
     let texture_view = world.get_indirect(texture_view).unwrap();
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
@@ -104,7 +105,7 @@ pub fn hello_triangle_render(
     let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
         label: None,
         color_attachments: &[RenderPassColorAttachment {
-            view: texture_view,
+            view: &texture_view,
             resolve_target: None,
             ops: Operations {
                 load: LoadOp::Clear(Color::GREEN),
@@ -113,7 +114,7 @@ pub fn hello_triangle_render(
         }],
         depth_stencil_attachment: None,
     });
-    rpass.set_pipeline(render_pipeline);
+    rpass.set_pipeline(&render_pipeline);
     rpass.draw(0..3, 0..1);
     drop(rpass);
 
@@ -8,12 +8,13 @@ pub use components::*;
 pub use systems::*;
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, Construct, ImmutableSchedule, Serial, Single,
+    parallel, serial, single, AddIndirectComponent, ChangeTracker, Construct, ImmutableResources,
+    ImmutableSchedule, Serial, Single,
 };
 
 use antigen_wgpu::{
     wgpu::{
-        AddressMode, BufferAddress, BufferDescriptor, BufferUsages, Device, Extent3d, FilterMode,
+        AddressMode, BufferAddress, BufferDescriptor, BufferUsages, Extent3d, FilterMode,
         ImageCopyTextureBase, ImageDataLayout, Origin3d, SamplerDescriptor, ShaderModuleDescriptor,
         ShaderSource, TextureAspect, TextureDescriptor, TextureDimension, TextureFormat,
         TextureUsages,
@@ -61,7 +62,6 @@ fn create_texels(size: usize, cx: f32, cy: f32) -> Vec<u8> {
 }
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
 
@@ -179,23 +179,39 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     );
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_with_usage_system::<Draw>(),
-            antigen_wgpu::create_buffers_system::<Uniform>(),
-            antigen_wgpu::create_textures_system::<JuliaSet>(),
-            antigen_wgpu::create_texture_views_system::<JuliaSet>(),
-            antigen_wgpu::create_samplers_with_usage_system::<JuliaSet>(),
+            antigen_wgpu::create_shader_modules_with_usage_system::<Draw>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<Uniform>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_textures_system::<JuliaSet>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_texture_views_system::<JuliaSet>(ChangeTracker::default()),
+            antigen_wgpu::create_samplers_with_usage_system::<JuliaSet>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
         ],
         parallel![
-            antigen_wgpu::buffer_write_system::<Uniform, ViewProjectionMatrix, [f32; 16]>(),
-            antigen_wgpu::texture_write_system::<JuliaSet, TexelsComponent, Vec<u8>>(),
+            antigen_wgpu::buffer_write_system::<Uniform, ViewProjectionMatrix, [f32; 16]>(
+                resources.clone()
+            ),
+            antigen_wgpu::texture_write_system::<JuliaSet, TexelsComponent, Vec<u8>>(
+                resources.clone()
+            ),
         ],
-        mipmap_prepare_system()
+        mipmap_prepare_system(resources.clone())
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![mipmap_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![mipmap_render_system(resources.clone())]
 }
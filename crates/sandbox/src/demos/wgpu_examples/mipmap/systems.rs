@@ -7,7 +7,10 @@ use super::{
     JuliaSetTextureViewComponent, Mipmap, UniformBufferComponent, ViewProjectionMatrix,
     MIP_PASS_COUNT,
 };
-use antigen_core::{Changed, ChangedTrait, GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock};
+use antigen_core::{
+    ChangeTracker, Changed, ChangedTrait, GetIndirect, ImmutableResources, IndirectComponent,
+    LazyReadWriteLock, ReadWriteLock,
+};
 
 use antigen_wgpu::{
     wgpu::{
@@ -24,7 +27,7 @@ use antigen_wgpu::{
     SurfaceConfigurationComponent,
 };
 
-use legion::{world::SubWorld, IntoQuery};
+use legion::{world::SubWorld, Entity, IntoQuery};
 
 use bytemuck::{Pod, Zeroable};
 
@@ -161,14 +164,14 @@ fn generate_mipmaps(
 
     if let Some(ref query_sets) = query_sets {
         let timestamp_query_count = MIP_PASS_COUNT * 2;
-        println!("Resolving timestamp queries");
+        tracing::debug!("Resolving timestamp queries");
         encoder.resolve_query_set(
             &query_sets.timestamp,
             0..timestamp_query_count,
             &query_sets.data_buffer,
             0,
         );
-        println!("Resolving pipeline statistics queries");
+        tracing::debug!("Resolving pipeline statistics queries");
         encoder.resolve_query_set(
             &query_sets.pipeline_statistics,
             0..MIP_PASS_COUNT,
@@ -180,11 +183,10 @@ fn generate_mipmaps(
 
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
-#[read_component(Queue)]
 #[read_component(SurfaceConfigurationComponent)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn mipmap_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Mipmap,
     draw_pipeline_component: &DrawPipelineComponent,
@@ -200,14 +202,14 @@ pub fn mipmap_prepare(
         return;
     }
 
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let queue = if let Some(components) = <&Queue>::query().iter(world).next() {
-        components
+    let queue = if let Some(queue) = resources.get_or_warn::<Queue>() {
+        queue
     } else {
         return;
     };
@@ -215,37 +217,32 @@ pub fn mipmap_prepare(
     let surface_component = world.get_indirect(surface_component).unwrap();
     let config = surface_component.read();
 
-    let julia_set_texture_view = julia_set_texture_view.read();
     let julia_set_texture_view =
-        if let LazyComponent::Ready(julia_set_texture_view) = &*julia_set_texture_view {
+        if let Some(julia_set_texture_view) = julia_set_texture_view.read_ready() {
             julia_set_texture_view
         } else {
             return;
         };
 
-    let draw_shader = draw_shader.read();
-    let draw_shader = if let LazyComponent::Ready(draw_shader) = &*draw_shader {
+    let draw_shader = if let Some(draw_shader) = draw_shader.read_ready() {
         draw_shader
     } else {
         return;
     };
 
-    let uniform_buffer = uniform_buffer.read();
-    let uniform_buffer = if let LazyComponent::Ready(uniform_buffer) = &*uniform_buffer {
+    let uniform_buffer = if let Some(uniform_buffer) = uniform_buffer.read_ready() {
         uniform_buffer
     } else {
         return;
     };
 
-    let julia_set_texture = julia_set_texture.read();
-    let julia_set_texture = if let LazyComponent::Ready(julia_set_texture) = &*julia_set_texture {
+    let julia_set_texture = if let Some(julia_set_texture) = julia_set_texture.read_ready() {
         julia_set_texture
     } else {
         return;
     };
 
-    let julia_set_sampler = julia_set_sampler.read();
-    let julia_set_sampler = if let LazyComponent::Ready(julia_set_sampler) = &*julia_set_sampler {
+    let julia_set_sampler = if let Some(julia_set_sampler) = julia_set_sampler.read_ready() {
         julia_set_sampler
     } else {
         return;
@@ -352,7 +349,7 @@ pub fn mipmap_prepare(
 
     generate_mipmaps(
         &mut init_encoder,
-        device,
+        &device,
         &julia_set_texture,
         &query_sets,
         MIP_LEVEL_COUNT,
@@ -390,7 +387,7 @@ pub fn mipmap_prepare(
             // Nanoseconds is a bit small, so lets use microseconds.
             let microseconds = nanoseconds / 1000.0;
             // Print the data!
-            println!(
+            tracing::info!(
                 "Generating mip level {} took {:.3} μs and called the fragment shader {} times",
                 idx + 1,
                 microseconds,
@@ -403,14 +400,16 @@ pub fn mipmap_prepare(
 #[legion::system(par_for_each)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn mipmap_resize(
+    #[state] tracker: &ChangeTracker,
     world: &SubWorld,
+    entity: &Entity,
     _: &Mipmap,
     surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
     view_projection: &Changed<ViewProjectionMatrix>,
 ) {
     let surface_config = world.get_indirect(surface_config).unwrap();
 
-    if surface_config.get_changed() {
+    if tracker.observe(*entity, surface_config) {
         let surface_config = surface_config.read();
         let aspect = surface_config.width as f32 / surface_config.height as f32;
         let matrix = super::generate_matrix(aspect);
@@ -421,9 +420,9 @@ pub fn mipmap_resize(
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn mipmap_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Mipmap,
     draw_pipeline: &DrawPipelineComponent,
@@ -431,29 +430,26 @@ pub fn mipmap_render(
     command_buffers: &CommandBuffersComponent,
     texture_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let draw_pipeline = draw_pipeline.read();
-    let draw_pipeline = if let LazyComponent::Ready(draw_pipeline) = &*draw_pipeline {
+    let draw_pipeline = if let Some(draw_pipeline) = draw_pipeline.read_ready() {
         draw_pipeline
     } else {
         return;
     };
 
-    let bind_group = bind_group.read();
-    let bind_group = if let LazyComponent::Ready(bind_group) = &*bind_group {
+    let bind_group = if let Some(bind_group) = bind_group.read_ready() {
         bind_group
     } else {
         return;
     };
 
     let texture_view = world.get_indirect(texture_view).unwrap();
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
@@ -1,11 +1,5 @@
-use antigen_core::{ImmutableWorld};
-use antigen_winit::{
-    winit::{
-        event::{Event, WindowEvent},
-        event_loop::{ControlFlow, EventLoopWindowTarget},
-    },
-    EventLoopHandler,
-};
+use antigen_core::{App, ChangeTracker, ImmutableResources, Plugin, Stage};
+use antigen_winit::{on_window_resized, winit::event::Event};
 
 use crate::{parallel, ImmutableSchedule, Parallel};
 
@@ -20,12 +14,12 @@ pub mod shadow;
 pub mod skybox;
 pub mod texture_arrays;
 
-pub fn assemble_schedule() -> ImmutableSchedule<Parallel> {
+pub fn assemble_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Parallel> {
     parallel![
         hello_triangle::assemble_system(),
         cube::assemble_system(),
         boids::assemble_system(),
-        bunnymark::assemble_system(),
+        bunnymark::assemble_system(resources.clone()),
         msaa_line::assemble_system(),
         conservative_raster::assemble_system(),
         mipmap::assemble_system(),
@@ -35,72 +29,65 @@ pub fn assemble_schedule() -> ImmutableSchedule<Parallel> {
     ]
 }
 
-pub fn winit_event_handler<T>(mut f: impl EventLoopHandler<T>) -> impl EventLoopHandler<T> {
-    let mut prepare_schedule = parallel![
-        hello_triangle::prepare_schedule(),
-        cube::prepare_schedule(),
-        boids::prepare_schedule(),
-        bunnymark::prepare_schedule(),
-        msaa_line::prepare_schedule(),
-        conservative_raster::prepare_schedule(),
-        mipmap::prepare_schedule(),
-        texture_arrays::prepare_schedule(),
-        skybox::prepare_schedule(),
-        shadow::prepare_schedule(),
-    ];
-
-    let mut render_schedule = parallel![
-        hello_triangle::render_schedule(),
-        cube::render_schedule(),
-        boids::render_schedule(),
-        bunnymark::render_schedule(),
-        msaa_line::render_schedule(),
-        conservative_raster::render_schedule(),
-        mipmap::render_schedule(),
-        texture_arrays::render_schedule(),
-        skybox::render_schedule(),
-        shadow::render_schedule(),
-    ];
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Parallel> {
+    parallel![
+        hello_triangle::prepare_schedule(resources),
+        cube::prepare_schedule(resources),
+        boids::prepare_schedule(resources),
+        bunnymark::prepare_schedule(resources),
+        msaa_line::prepare_schedule(resources),
+        conservative_raster::prepare_schedule(resources),
+        mipmap::prepare_schedule(resources),
+        texture_arrays::prepare_schedule(resources),
+        skybox::prepare_schedule(resources),
+        shadow::prepare_schedule(resources),
+    ]
+}
 
-    let mut surface_resize_schedule = parallel![
-        cube::cube_resize_system()
-        msaa_line::msaa_line_resize_system()
-        conservative_raster::conservative_raster_resize_system()
-        mipmap::mipmap_resize_system(),
-        skybox::skybox_resize_system(),
-        shadow::shadow_resize_system(),
-    ];
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Parallel> {
+    parallel![
+        hello_triangle::render_schedule(resources),
+        cube::render_schedule(resources),
+        boids::render_schedule(resources),
+        bunnymark::render_schedule(resources),
+        msaa_line::render_schedule(resources),
+        conservative_raster::render_schedule(resources),
+        mipmap::render_schedule(resources),
+        texture_arrays::render_schedule(resources),
+        skybox::render_schedule(resources),
+        shadow::render_schedule(resources),
+    ]
+}
 
-    let mut keyboard_event_schedule = parallel![
-        bunnymark::keyboard_event_schedule(),
-        msaa_line::keyboard_event_schedule(),
-    ];
+pub fn surface_resize_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Parallel> {
+    parallel![
+        cube::cube_resize_system(ChangeTracker::default())
+        msaa_line::msaa_line_resize_system(ChangeTracker::default())
+        conservative_raster::conservative_raster_resize_system(ChangeTracker::default())
+        mipmap::mipmap_resize_system(ChangeTracker::default()),
+        skybox::skybox_resize_system(resources.clone(), ChangeTracker::default()),
+        shadow::shadow_resize_system(resources.clone(), ChangeTracker::default()),
+    ]
+}
 
-    let mut window_cursor_moved_schedule = parallel![skybox::cursor_moved_schedule(),];
+pub fn keyboard_event_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Parallel> {
+    parallel![
+        bunnymark::keyboard_event_schedule(resources),
+        msaa_line::keyboard_event_schedule(resources),
+    ]
+}
 
-    move |world: &ImmutableWorld,
-          event: Event<'static, T>,
-          event_loop_window_target: &EventLoopWindowTarget<T>,
-          control_flow: &mut ControlFlow| {
-        match &event {
-            Event::MainEventsCleared => {
-                surface_resize_schedule.execute(world);
-                prepare_schedule.execute(world);
-            }
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(_) => {
-                    surface_resize_schedule.execute(world);
-                }
-                WindowEvent::KeyboardInput { .. } => keyboard_event_schedule.execute(world),
-                WindowEvent::CursorMoved { .. } => window_cursor_moved_schedule.execute(world),
-                _ => (),
-            },
-            Event::RedrawEventsCleared => {
-                render_schedule.execute(world);
-            }
-            _ => (),
-        }
+pub struct WgpuExamplesPlugin;
 
-        f(world, event, event_loop_window_target, control_flow);
+impl<T: 'static> Plugin<Event<'static, T>> for WgpuExamplesPlugin {
+    fn build(&self, app: App<Event<'static, T>>) -> App<Event<'static, T>> {
+        let resources = app.resources().clone();
+        app.add_system(Stage::Assemble, assemble_schedule(&resources))
+            .add_system(Stage::PreUpdate, surface_resize_schedule(&resources))
+            .add_system(Stage::Update, keyboard_event_schedule(&resources))
+            .add_system(Stage::Update, skybox::cursor_moved_schedule(&resources))
+            .add_system(Stage::Update, prepare_schedule(&resources))
+            .add_system(Stage::Render, render_schedule(&resources))
+            .add_event_system(on_window_resized, surface_resize_schedule(&resources))
     }
 }
@@ -1,18 +1,18 @@
 mod components;
 mod systems;
 
-use antigen_winit::{AssembleWinit, WindowComponent};
+use antigen_winit::{AssembleWinit, WindowComponent, WindowEventReader};
 pub use components::*;
 pub use systems::*;
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, Construct, ImmutableSchedule, Serial, Single,
-    Usage,
+    parallel, serial, single, AddIndirectComponent, ChangeTracker, Construct, ImmutableResources,
+    ImmutableSchedule, Serial, Single, Usage,
 };
 
 use antigen_wgpu::{
     wgpu::{
-        BufferAddress, BufferDescriptor, BufferUsages, Device, Extent3d, ShaderModuleDescriptor,
+        BufferAddress, BufferDescriptor, BufferUsages, Extent3d, ShaderModuleDescriptor,
         ShaderSource, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
     },
     AssembleWgpu, MsaaFramebuffer, MsaaFramebufferTextureDescriptor,
@@ -35,7 +35,6 @@ const VERTEX_COUNT: u32 = LINE_COUNT * 2;
 const SAMPLE_COUNT: u32 = 4;
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -134,25 +133,38 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     cmd.add_indirect_component_self::<MsaaFramebufferTextureView>(renderer_entity);
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_system(),
-            antigen_wgpu::create_buffers_system::<VertexBuffer>(),
+            antigen_wgpu::create_shader_modules_system(resources.clone(), ChangeTracker::default()),
+            antigen_wgpu::create_buffers_system::<VertexBuffer>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
             serial![
-                antigen_wgpu::create_textures_system::<MsaaFramebuffer>(),
-                antigen_wgpu::create_texture_views_system::<MsaaFramebuffer>(),
+                antigen_wgpu::create_textures_system::<MsaaFramebuffer>(
+                    resources.clone(),
+                    ChangeTracker::default(),
+                ),
+                antigen_wgpu::create_texture_views_system::<MsaaFramebuffer>(
+                    ChangeTracker::default(),
+                ),
             ]
         ],
-        antigen_wgpu::buffer_write_system::<VertexBuffer, MeshVerticesComponent, Vec<Vertex>>(),
-        msaa_line_prepare_system()
+        antigen_wgpu::buffer_write_system::<VertexBuffer, MeshVerticesComponent, Vec<Vertex>>(
+            resources.clone(),
+        ),
+        msaa_line_prepare_system(resources.clone())
     ]
 }
 
-pub fn keyboard_event_schedule() -> ImmutableSchedule<Single> {
-    single![msaa_line_key_event_system()]
+pub fn keyboard_event_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![msaa_line_key_event_system(
+        resources.clone(),
+        WindowEventReader::default()
+    )]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![msaa_line_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![msaa_line_render_system(resources.clone())]
 }
@@ -1,6 +1,7 @@
 use super::{MsaaLine, Vertex, VertexBufferComponent, VERTEX_COUNT};
 use antigen_core::{
-    ChangedTrait, GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock, Usage,
+    ChangeTracker, ChangedTrait, GetIndirect, ImmutableResources, IndirectComponent,
+    LazyReadWriteLock, ReadWriteLock, Usage,
 };
 
 use antigen_wgpu::{
@@ -9,7 +10,7 @@ use antigen_wgpu::{
         FragmentState, FrontFace, LoadOp, MultisampleState, Operations, PipelineLayoutDescriptor,
         PrimitiveState, PrimitiveTopology, RenderBundleDescriptor, RenderBundleEncoderDescriptor,
         RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor,
-        SurfaceConfiguration, VertexBufferLayout, VertexState, VertexStepMode,
+        VertexBufferLayout, VertexState, VertexStepMode,
     },
     CommandBuffersComponent, MsaaFramebuffer, MsaaFramebufferTextureDescriptor,
     MsaaFramebufferTextureView, PipelineLayoutComponent, RenderAttachmentTextureView,
@@ -19,16 +20,16 @@ use antigen_wgpu::{
 
 use antigen_winit::{
     winit::event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent},
-    WindowComponent, WindowEventComponent,
+    WindowComponent, WindowEventReader, WindowEvents,
 };
-use legion::{world::SubWorld, IntoQuery};
+use legion::{world::SubWorld, Entity, IntoQuery};
 
 // Initialize the MSAA lines render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 #[read_component(MsaaFramebufferTextureDescriptor<'static>)]
 pub fn msaa_line_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &MsaaLine,
     shader_module: &ShaderModuleComponent,
@@ -38,9 +39,13 @@ pub fn msaa_line_prepare(
     surface_configuration_component: &IndirectComponent<SurfaceConfigurationComponent>,
     msaa_framebuffer_desc: &IndirectComponent<MsaaFramebufferTextureDescriptor<'static>>,
 ) {
-    println!("MSAA Line Prepare");
+    tracing::trace!("MSAA Line Prepare");
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
     // Create pipeline layout if needed
     if pipeline_layout_component.read().is_pending() {
@@ -52,56 +57,52 @@ pub fn msaa_line_prepare(
 
         pipeline_layout_component.write().set_ready(pipeline_layout);
 
-        println!("Created pipeline layout");
+        tracing::debug!("Created pipeline layout");
     }
 
-    let pipeline_layout_component = pipeline_layout_component.read();
-    let pipeline_layout = if let LazyComponent::Ready(pipeline_layout) = &*pipeline_layout_component
-    {
+    let pipeline_layout = if let Some(pipeline_layout) = pipeline_layout_component.read_ready() {
         pipeline_layout
     } else {
         unreachable!()
     };
 
-    println!("Pipeline layout ready");
+    tracing::trace!("Pipeline layout ready");
 
     // Create render bundle
     if !render_bundle_component.read().is_pending() {
         return;
     }
 
-    println!("Render bundle is pending");
+    tracing::trace!("Render bundle is pending");
 
-    let shader_module = shader_module.read();
-    let shader_module = if let LazyComponent::Ready(shader_module) = &*shader_module {
+    let shader_module = if let Some(shader_module) = shader_module.read_ready() {
         shader_module
     } else {
         return;
     };
 
-    println!("Shader module ready");
+    tracing::trace!("Shader module ready");
 
-    let vertex_buffer_component = vertex_buffer_component.read();
-    let vertex_buffer = if let LazyComponent::Ready(vertex_buffer) = &*vertex_buffer_component {
+    let vertex_buffer = if let Some(vertex_buffer) = vertex_buffer_component.read_ready() {
         vertex_buffer
     } else {
         return;
     };
 
-    println!("Vertex buffer ready");
+    tracing::trace!("Vertex buffer ready");
 
     let surface_configuration_component =
         world.get_indirect(surface_configuration_component).unwrap();
-    let config = ReadWriteLock::<SurfaceConfiguration>::read(surface_configuration_component);
+    let config = surface_configuration_component.read();
 
     let msaa_framebuffer_desc = world.get_indirect(msaa_framebuffer_desc).unwrap();
     let msaa_framebuffer_desc = msaa_framebuffer_desc.read();
 
     let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
         label: None,
-        layout: Some(pipeline_layout),
+        layout: Some(&pipeline_layout),
         vertex: VertexState {
-            module: shader_module,
+            module: &shader_module,
             entry_point: "vs_main",
             buffers: &[VertexBufferLayout {
                 array_stride: std::mem::size_of::<Vertex>() as BufferAddress,
@@ -110,7 +111,7 @@ pub fn msaa_line_prepare(
             }],
         },
         fragment: Some(FragmentState {
-            module: shader_module,
+            module: &shader_module,
             entry_point: "fs_main",
             targets: &[config.format.into()],
         }),
@@ -151,7 +152,9 @@ pub fn msaa_line_prepare(
 #[read_component(MsaaFramebufferTextureDescriptor<'static>)]
 #[read_component(Usage<MsaaFramebuffer, TextureViewDescriptorComponent<'static>>)]
 pub fn msaa_line_resize(
+    #[state] tracker: &ChangeTracker,
     world: &SubWorld,
+    entity: &Entity,
     _: &MsaaLine,
     surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
     msaa_framebuffer_desc: &IndirectComponent<MsaaFramebufferTextureDescriptor<'static>>,
@@ -163,11 +166,11 @@ pub fn msaa_line_resize(
     let msaa_framebuffer_desc = world.get_indirect(msaa_framebuffer_desc).unwrap();
     let msaa_framebuffer_view_desc = world.get_indirect(msaa_framebuffer_view_desc).unwrap();
 
-    if !surface_config.get_changed() {
+    if !tracker.observe(*entity, surface_config) {
         return;
     }
 
-    println!("Surface config changed, recreating texture and view");
+    tracing::debug!("Surface config changed, recreating texture and view");
 
     let surface_config = surface_config.read();
     msaa_framebuffer_desc.write().size = Extent3d {
@@ -180,36 +183,27 @@ pub fn msaa_line_resize(
     msaa_framebuffer_view_desc.set_changed(true);
 }
 
-#[legion::system(par_for_each)]
+#[legion::system]
+#[read_component(MsaaLine)]
+#[read_component(IndirectComponent<WindowComponent>)]
+#[read_component(RenderBundleComponent)]
+#[read_component(IndirectComponent<MsaaFramebufferTextureDescriptor<'static>>)]
 #[read_component(WindowComponent)]
-#[read_component(WindowEventComponent)]
 #[read_component(MsaaFramebufferTextureDescriptor<'static>)]
 pub fn msaa_line_key_event(
+    #[state] resources: &ImmutableResources,
+    #[state] reader: &WindowEventReader,
     world: &SubWorld,
-    _: &MsaaLine,
-    window: &IndirectComponent<WindowComponent>,
-    render_bundle: &RenderBundleComponent,
-    msaa_framebuffer_desc: &IndirectComponent<MsaaFramebufferTextureDescriptor<'static>>,
 ) {
-    let window = world
-        .get_indirect(window)
-        .expect("No indirect WindowComponent");
-    let window = window.read();
-    let window = if let LazyComponent::Ready(window) = &*window {
-        window
+    let window_events = if let Some(window_events) = resources.get_or_warn::<WindowEvents>() {
+        window_events
     } else {
         return;
     };
 
-    let window_event = <&WindowEventComponent>::query()
-        .iter(world)
-        .next()
-        .expect("No WindowEventComponent");
-
-    let window_event = window_event.read();
-    let (window_id, key) = if let (
-        Some(window_id),
-        Some(WindowEvent::KeyboardInput {
+    let mut keys = vec![];
+    reader.read(&window_events, |(window_id, event)| {
+        if let WindowEvent::KeyboardInput {
             input:
                 KeyboardInput {
                     state: ElementState::Pressed,
@@ -217,42 +211,63 @@ pub fn msaa_line_key_event(
                     ..
                 },
             ..
-        }),
-    ) = &*window_event
-    {
-        (window_id, key)
-    } else {
-        return;
-    };
+        } = event
+        {
+            keys.push((*window_id, *key));
+        }
+    });
 
-    if window.id() != *window_id {
+    if keys.is_empty() {
         return;
     }
 
-    let msaa_framebuffer_desc = world.get_indirect(msaa_framebuffer_desc).unwrap();
+    <(
+        &MsaaLine,
+        &IndirectComponent<WindowComponent>,
+        &RenderBundleComponent,
+        &IndirectComponent<MsaaFramebufferTextureDescriptor<'static>>,
+    )>::query()
+    .for_each(world, |(_, window, render_bundle, framebuffer_desc)| {
+        let window = world
+            .get_indirect(window)
+            .expect("No indirect WindowComponent");
+        let window = if let Some(window) = window.read_ready() {
+            window
+        } else {
+            return;
+        };
 
-    match key {
-        VirtualKeyCode::Left => {
-            msaa_framebuffer_desc.write().sample_count = 1;
-            render_bundle.write().set_pending();
-            window.request_redraw();
-        }
-        VirtualKeyCode::Right => {
-            msaa_framebuffer_desc.write().sample_count = 4;
-            render_bundle.write().set_pending();
-            window.request_redraw();
+        let msaa_framebuffer_desc = world.get_indirect(framebuffer_desc).unwrap();
+
+        for (window_id, key) in &keys {
+            if *window_id != window.id() {
+                continue;
+            }
+
+            match key {
+                VirtualKeyCode::Left => {
+                    msaa_framebuffer_desc.write().sample_count = 1;
+                    render_bundle.write().set_pending();
+                    window.request_redraw();
+                }
+                VirtualKeyCode::Right => {
+                    msaa_framebuffer_desc.write().sample_count = 4;
+                    render_bundle.write().set_pending();
+                    window.request_redraw();
+                }
+                _ => (),
+            }
         }
-        _ => (),
-    }
+    });
 }
 
 // Render the MSAA lines pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 #[read_component(MsaaFramebufferTextureDescriptor<'static>)]
 #[read_component(MsaaFramebufferTextureView)]
 pub fn msaa_line_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &MsaaLine,
     render_bundle: &RenderBundleComponent,
@@ -261,48 +276,45 @@ pub fn msaa_line_render(
     msaa_framebuffer_desc: &IndirectComponent<MsaaFramebufferTextureDescriptor<'static>>,
     msaa_framebuffer_view: &IndirectComponent<MsaaFramebufferTextureView>,
 ) {
-    println!("MSAA Line Render");
+    tracing::trace!("MSAA Line Render");
 
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    println!("Device ready");
+    tracing::trace!("Device ready");
 
-    let render_bundle = render_bundle.read();
-    let render_bundle = if let LazyComponent::Ready(render_bundle) = &*render_bundle {
+    let render_bundle = if let Some(render_bundle) = render_bundle.read_ready() {
         render_bundle
     } else {
         return;
     };
 
-    println!("Render bundle ready");
+    tracing::trace!("Render bundle ready");
 
     let render_attachment = world.get_indirect(render_attachment).unwrap();
-    let render_attachment = render_attachment.read();
-    let render_attachment = if let LazyComponent::Ready(render_attachment) = &*render_attachment {
+    let render_attachment = if let Some(render_attachment) = render_attachment.read_ready() {
         render_attachment
     } else {
         return;
     };
 
-    println!("Render attachment ready");
+    tracing::trace!("Render attachment ready");
 
     let msaa_framebuffer_desc = world.get_indirect(msaa_framebuffer_desc).unwrap();
     let msaa_framebuffer_desc = msaa_framebuffer_desc.read();
 
     let msaa_framebuffer_view = world.get_indirect(msaa_framebuffer_view).unwrap();
-    let msaa_framebuffer_view = msaa_framebuffer_view.read();
     let msaa_framebuffer_view =
-        if let LazyComponent::Ready(msaa_framebuffer_view) = &*msaa_framebuffer_view {
+        if let Some(msaa_framebuffer_view) = msaa_framebuffer_view.read_ready() {
             msaa_framebuffer_view
         } else {
             return;
         };
 
-    println!("MSAA framebuffer view ready");
+    tracing::trace!("MSAA framebuffer view ready");
 
     let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
 
@@ -312,14 +324,14 @@ pub fn msaa_line_render(
     };
     let rpass_color_attachment = if msaa_framebuffer_desc.sample_count == 1 {
         RenderPassColorAttachment {
-            view: render_attachment,
+            view: &render_attachment,
             resolve_target: None,
             ops,
         }
     } else {
         RenderPassColorAttachment {
-            view: msaa_framebuffer_view,
-            resolve_target: Some(render_attachment),
+            view: &msaa_framebuffer_view,
+            resolve_target: Some(&render_attachment),
             ops,
         }
     };
@@ -329,7 +341,7 @@ pub fn msaa_line_render(
         color_attachments: &[rpass_color_attachment],
         depth_stencil_attachment: None,
     });
-    rpass.execute_bundles(std::iter::once(render_bundle));
+    rpass.execute_bundles(std::iter::once(&render_bundle));
     drop(rpass);
 
     command_buffers.write().push(encoder.finish());
@@ -9,12 +9,12 @@ use legion::Entity;
 pub use systems::*;
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, AsUsage, ImmutableSchedule, LazyComponent,
-    RwLock, Serial, Single, Construct,
+    parallel, serial, single, AddIndirectComponent, AsUsage, ChangeTracker, ImmutableResources,
+    ImmutableSchedule, LazyComponent, RwLock, Serial, Single, Construct,
 };
 
 use antigen_wgpu::{AssembleWgpu, BufferComponent, RenderAttachmentTextureView, SurfaceConfigurationComponent, TextureViewComponent, wgpu::{
-        AddressMode, BufferAddress, BufferDescriptor, BufferUsages, Color, CompareFunction, Device,
+        AddressMode, BufferAddress, BufferDescriptor, BufferUsages, Color, CompareFunction,
         Extent3d, FilterMode, IndexFormat, SamplerDescriptor, ShaderModuleDescriptor, ShaderSource,
         TextureAspect, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
         TextureViewDescriptor, TextureViewDimension,
@@ -175,7 +175,6 @@ pub fn assemble_light(
 }
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -500,22 +499,38 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     cmd.add_component(renderer_entity, LightsAreDirty::as_usage(RwLock::new(true)));
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_system(),
-            antigen_wgpu::create_buffers_system::<VertexTag>(),
-            antigen_wgpu::create_buffers_system::<IndexTag>(),
-            antigen_wgpu::buffer_write_system::<VertexTag, VertexDataComponent, Vec<Vertex>>(),
-            antigen_wgpu::buffer_write_system::<IndexTag, IndexDataComponent, Vec<Index>>(),
-            antigen_wgpu::create_textures_system::<ShadowPass>(),
-            antigen_wgpu::create_texture_views_system::<ShadowPass>(),
-            antigen_wgpu::create_samplers_with_usage_system::<ShadowPass>(),
+            antigen_wgpu::create_shader_modules_system(resources.clone(), ChangeTracker::default()),
+            antigen_wgpu::create_buffers_system::<VertexTag>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_buffers_system::<IndexTag>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::buffer_write_system::<VertexTag, VertexDataComponent, Vec<Vertex>>(
+                resources.clone(),
+            ),
+            antigen_wgpu::buffer_write_system::<IndexTag, IndexDataComponent, Vec<Index>>(
+                resources.clone(),
+            ),
+            antigen_wgpu::create_textures_system::<ShadowPass>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
+            antigen_wgpu::create_texture_views_system::<ShadowPass>(ChangeTracker::default()),
+            antigen_wgpu::create_samplers_with_usage_system::<ShadowPass>(
+                resources.clone(),
+                ChangeTracker::default(),
+            ),
         ],
-        shadow_prepare_system()
+        shadow_prepare_system(resources.clone())
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![shadow_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![shadow_render_system(resources.clone())]
 }
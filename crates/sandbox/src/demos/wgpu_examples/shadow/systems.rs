@@ -13,13 +13,13 @@ use super::{
     ShadowTextureViewComponent, ShadowUniformBuffer, UniformOffset, VertexBufferComponent,
 };
 use antigen_core::{
-    lazy_read_ready_else_return, ChangedTrait, GetIndirect, IndirectComponent, LazyComponent,
+    ChangeTracker, GetIndirect, ImmutableResources, IndirectComponent, LazyReadWriteLock,
     ReadWriteLock, Usage,
 };
 
 use antigen_wgpu::{CommandBuffersComponent, RenderAttachmentTextureView, ShaderModuleComponent, SurfaceConfigurationComponent, wgpu::{Adapter, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType, BufferAddress, BufferBinding, BufferBindingType, BufferDescriptor, BufferSize, BufferUsages, Color, CommandEncoderDescriptor, CompareFunction, DepthBiasState, DepthStencilState, Device, DownlevelFlags, Extent3d, Face, FragmentState, FrontFace, IndexFormat, LoadOp, MultisampleState, Operations, PipelineLayoutDescriptor, PrimitiveState, PrimitiveTopology, Queue, RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor, RenderPipelineDescriptor, SamplerBindingType, ShaderStages, StencilState, SurfaceConfiguration, TextureDescriptor, TextureDimension, TextureSampleType, TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension, VertexBufferLayout, VertexState, VertexStepMode, util::{BufferInitDescriptor, DeviceExt}, vertex_attr_array}};
 
-use legion::{world::SubWorld, IntoQuery};
+use legion::{world::SubWorld, Entity, IntoQuery};
 
 fn generate_matrix(aspect_ratio: f32) -> nalgebra::Matrix4<f32> {
     let projection = nalgebra_glm::perspective_rh_zo(aspect_ratio, 45.0, 1.0, 20.0);
@@ -54,8 +54,6 @@ fn create_depth_texture(config: &SurfaceConfiguration, device: &Device) -> Optio
 
 // Initialize the shadow render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Adapter)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 // Object query
 #[read_component(Mesh)]
@@ -70,6 +68,7 @@ fn create_depth_texture(config: &SurfaceConfiguration, device: &Device) -> Optio
 #[read_component(Range<f32>)]
 #[read_component(ShadowTextureViewComponent)]
 pub fn shadow_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Shadow,
     shader_module: &ShaderModuleComponent,
@@ -91,15 +90,35 @@ pub fn shadow_prepare(
         return;
     }
 
-    lazy_read_ready_else_return!(shader_module);
-    lazy_read_ready_else_return!(shadow_view);
-    lazy_read_ready_else_return!(shadow_sampler);
+    let shader_module = if let Some(shader_module) = shader_module.read_ready() {
+        shader_module
+    } else {
+        return;
+    };
+    let shadow_view = if let Some(shadow_view) = shadow_view.read_ready() {
+        shadow_view
+    } else {
+        return;
+    };
+    let shadow_sampler = if let Some(shadow_sampler) = shadow_sampler.read_ready() {
+        shadow_sampler
+    } else {
+        return;
+    };
 
     let surface_component = world.get_indirect(surface_component).unwrap();
     let config = surface_component.read();
 
-    let adapter = <&Adapter>::query().iter(world).next().unwrap();
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let adapter = if let Some(adapter) = resources.get_or_warn::<Adapter>() {
+        adapter
+    } else {
+        return;
+    };
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
     let supports_storage_resources = adapter
         .get_downlevel_properties()
@@ -391,15 +410,13 @@ pub fn shadow_prepare(
         .write()
         .set_ready(forward_uniform_buf);
 
-    let forward_depth = create_depth_texture(&config, device).unwrap();
+    let forward_depth = create_depth_texture(&config, &device).unwrap();
     forward_depth_view_component
         .write()
         .set_ready(forward_depth);
 }
 
 #[legion::system(par_for_each)]
-#[read_component(Device)]
-#[read_component(Queue)]
 #[read_component(SurfaceConfigurationComponent)]
 // Light query
 #[read_component(nalgebra::Vector3<f32>)]
@@ -408,22 +425,37 @@ pub fn shadow_prepare(
 #[read_component(Range<f32>)]
 #[read_component(ShadowTextureViewComponent)]
 pub fn shadow_resize(
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
     world: &SubWorld,
+    entity: &Entity,
     _: &Shadow,
     surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
     forward_depth_view_component: &ForwardDepthView,
     forward_uniform_buf: &ForwardUniformBuffer,
 ) {
-    let device = <&Device>::query().iter(world).next().unwrap();
-    let queue = <&Queue>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
+    let queue = if let Some(queue) = resources.get_or_warn::<Queue>() {
+        queue
+    } else {
+        return;
+    };
 
-    lazy_read_ready_else_return!(forward_uniform_buf);
+    let forward_uniform_buf = if let Some(forward_uniform_buf) = forward_uniform_buf.read_ready() {
+        forward_uniform_buf
+    } else {
+        return;
+    };
 
     let surface_config = world.get_indirect(surface_config).unwrap();
 
-    if surface_config.get_changed() {
+    if tracker.observe(*entity, surface_config) {
         let surface_config = surface_config.read();
-        if let Some(depth_view) = create_depth_texture(&*surface_config, device) {
+        if let Some(depth_view) = create_depth_texture(&*surface_config, &device) {
             forward_depth_view_component.write().set_ready(depth_view);
         }
 
@@ -444,8 +476,6 @@ pub fn shadow_resize(
 
 /// Render the shadow pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
-#[read_component(Queue)]
 #[read_component(RenderAttachmentTextureView)]
 // Mesh query
 #[read_component(PlaneMesh)]
@@ -467,6 +497,7 @@ pub fn shadow_resize(
 #[read_component(Range<f32>)]
 #[read_component(ShadowTextureViewComponent)]
 pub fn shadow_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Shadow,
     command_buffers: &CommandBuffersComponent,
@@ -482,14 +513,14 @@ pub fn shadow_render(
     lights_are_dirty: &LightsAreDirtyComponent,
     texture_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let queue = if let Some(components) = <&Queue>::query().iter(world).next() {
-        components
+    let queue = if let Some(queue) = resources.get_or_warn::<Queue>() {
+        queue
     } else {
         return;
     };
@@ -501,8 +532,16 @@ pub fn shadow_render(
             return;
         };
 
-    lazy_read_ready_else_return!(plane_vertex_buffer);
-    lazy_read_ready_else_return!(plane_index_buffer);
+    let plane_vertex_buffer = if let Some(plane_vertex_buffer) = plane_vertex_buffer.read_ready() {
+        plane_vertex_buffer
+    } else {
+        return;
+    };
+    let plane_index_buffer = if let Some(plane_index_buffer) = plane_index_buffer.read_ready() {
+        plane_index_buffer
+    } else {
+        return;
+    };
 
     let (_, cube_vertex_buffer, cube_index_buffer, cube_index_format, cube_index_count) =
         if let Some(components) = BufferQuery::<CubeMesh>::query().iter(world).next() {
@@ -511,21 +550,71 @@ pub fn shadow_render(
             return;
         };
 
-    lazy_read_ready_else_return!(cube_vertex_buffer);
-    lazy_read_ready_else_return!(cube_index_buffer);
+    let cube_vertex_buffer = if let Some(cube_vertex_buffer) = cube_vertex_buffer.read_ready() {
+        cube_vertex_buffer
+    } else {
+        return;
+    };
+    let cube_index_buffer = if let Some(cube_index_buffer) = cube_index_buffer.read_ready() {
+        cube_index_buffer
+    } else {
+        return;
+    };
 
-    lazy_read_ready_else_return!(forward_render_pipeline);
-    lazy_read_ready_else_return!(forward_bind_group);
-    lazy_read_ready_else_return!(forward_depth_view);
-    lazy_read_ready_else_return!(shadow_render_pipeline);
-    lazy_read_ready_else_return!(shadow_bind_group);
-    lazy_read_ready_else_return!(shadow_uniform_buf);
-    lazy_read_ready_else_return!(object_uniform_buf);
-    lazy_read_ready_else_return!(object_bind_group);
-    lazy_read_ready_else_return!(light_storage_buf);
+    let forward_render_pipeline =
+        if let Some(forward_render_pipeline) = forward_render_pipeline.read_ready() {
+            forward_render_pipeline
+        } else {
+            return;
+        };
+    let forward_bind_group = if let Some(forward_bind_group) = forward_bind_group.read_ready() {
+        forward_bind_group
+    } else {
+        return;
+    };
+    let forward_depth_view = if let Some(forward_depth_view) = forward_depth_view.read_ready() {
+        forward_depth_view
+    } else {
+        return;
+    };
+    let shadow_render_pipeline =
+        if let Some(shadow_render_pipeline) = shadow_render_pipeline.read_ready() {
+            shadow_render_pipeline
+        } else {
+            return;
+        };
+    let shadow_bind_group = if let Some(shadow_bind_group) = shadow_bind_group.read_ready() {
+        shadow_bind_group
+    } else {
+        return;
+    };
+    let shadow_uniform_buf = if let Some(shadow_uniform_buf) = shadow_uniform_buf.read_ready() {
+        shadow_uniform_buf
+    } else {
+        return;
+    };
+    let object_uniform_buf = if let Some(object_uniform_buf) = object_uniform_buf.read_ready() {
+        object_uniform_buf
+    } else {
+        return;
+    };
+    let object_bind_group = if let Some(object_bind_group) = object_bind_group.read_ready() {
+        object_bind_group
+    } else {
+        return;
+    };
+    let light_storage_buf = if let Some(light_storage_buf) = light_storage_buf.read_ready() {
+        light_storage_buf
+    } else {
+        return;
+    };
 
     let texture_view = world.get_indirect(texture_view).unwrap();
-    lazy_read_ready_else_return!(texture_view);
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
+        texture_view
+    } else {
+        return;
+    };
 
     let objects = ObjectQuery::query().iter(world).collect::<Vec<_>>();
     let lights = LightQuery::query().iter(world).collect::<Vec<_>>();
@@ -589,8 +678,7 @@ pub fn shadow_render(
 
     encoder.push_debug_group("shadow passes");
     for (i, (pos, _, _, _, target_view)) in lights.iter().enumerate() {
-        let target_view = target_view.read();
-        let target_view = if let LazyComponent::Ready(target_view) = &*target_view {
+        let target_view = if let Some(target_view) = target_view.read_ready() {
             target_view
         } else {
             continue;
@@ -614,7 +702,7 @@ pub fn shadow_render(
                 label: None,
                 color_attachments: &[],
                 depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
-                    view: target_view,
+                    view: &target_view,
                     depth_ops: Some(Operations {
                         load: LoadOp::Clear(1.0),
                         store: true,
@@ -627,13 +715,13 @@ pub fn shadow_render(
 
             for (mesh, _, _, _, uniform_offset) in &objects {
                 let vertex_buffer = match mesh {
-                    Mesh::Plane => plane_vertex_buffer,
-                    Mesh::Cube => cube_vertex_buffer,
+                    Mesh::Plane => &plane_vertex_buffer,
+                    Mesh::Cube => &cube_vertex_buffer,
                 };
 
                 let index_buffer = match mesh {
-                    Mesh::Plane => plane_index_buffer,
-                    Mesh::Cube => cube_index_buffer,
+                    Mesh::Plane => &plane_index_buffer,
+                    Mesh::Cube => &cube_index_buffer,
                 };
 
                 let index_format = match mesh {
@@ -689,13 +777,13 @@ pub fn shadow_render(
 
         for (mesh, _, _, _, uniform_offset) in &objects {
             let vertex_buffer = match mesh {
-                Mesh::Plane => plane_vertex_buffer,
-                Mesh::Cube => cube_vertex_buffer,
+                Mesh::Plane => &plane_vertex_buffer,
+                Mesh::Cube => &cube_vertex_buffer,
             };
 
             let index_buffer = match mesh {
-                Mesh::Plane => plane_index_buffer,
-                Mesh::Cube => cube_index_buffer,
+                Mesh::Plane => &plane_index_buffer,
+                Mesh::Cube => &cube_index_buffer,
             };
 
             let index_format = match mesh {
@@ -1,18 +1,18 @@
 mod components;
 mod systems;
 
-use antigen_winit::{AssembleWinit, WindowComponent};
+use antigen_winit::{AssembleWinit, WindowComponent, WindowEventReader};
 pub use components::*;
 pub use systems::*;
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, AsUsage, Construct, ImmutableSchedule,
-    LazyComponent, RwLock, Serial, Single,
+    parallel, serial, single, AddIndirectComponent, AsUsage, ChangeTracker, Construct,
+    ImmutableResources, ImmutableSchedule, LazyComponent, RwLock, Serial, Single,
 };
 
 use antigen_wgpu::{
     wgpu::{
-        AddressMode, BufferAddress, BufferDescriptor, BufferSize, BufferUsages, Device, FilterMode,
+        AddressMode, BufferAddress, BufferDescriptor, BufferSize, BufferUsages, FilterMode,
         SamplerDescriptor, ShaderModuleDescriptor, ShaderSource, TextureFormat,
     },
     AssembleWgpu, RenderAttachmentTextureView, SurfaceConfigurationComponent, TextureComponent,
@@ -71,7 +71,6 @@ impl ToBytes for Vertex {
 }
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -204,26 +203,37 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     );
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_system(),
-            antigen_wgpu::create_buffers_system::<Vertex>(),
-            antigen_wgpu::create_buffers_system::<Uniform>(),
-            antigen_wgpu::create_samplers_system(),
+            antigen_wgpu::create_shader_modules_system(resources.clone(), ChangeTracker::default()),
+            antigen_wgpu::create_buffers_system::<Vertex>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<Uniform>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_samplers_system(resources.clone(), ChangeTracker::default()),
         ],
         parallel![
             antigen_wgpu::buffer_write_system::<Vertex, RwLock<Vec<Vertex>>, Vec<Vertex>>(),
-            antigen_wgpu::staging_belt_write_system::<Uniform, RwLock<[f32; 52]>, [f32; 52]>(),
+            antigen_wgpu::staging_belt_write_system::<Uniform, RwLock<[f32; 52]>, [f32; 52]>(
+                resources.clone()
+            ),
         ],
-        skybox_prepare_system()
+        skybox_prepare_system(resources.clone())
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![skybox_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![skybox_render_system(resources.clone())]
 }
 
-pub fn cursor_moved_schedule() -> ImmutableSchedule<Single> {
-    single![skybox_cursor_moved_system()]
+pub fn cursor_moved_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![skybox_cursor_moved_system(
+        resources.clone(),
+        WindowEventReader::default()
+    )]
 }
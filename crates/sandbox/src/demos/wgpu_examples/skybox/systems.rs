@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::wgpu_examples::skybox::{DEPTH_FORMAT, IMAGE_SIZE};
 
 use super::{
@@ -6,14 +8,14 @@ use super::{
     VertexBufferComponent, VertexCountComponent,
 };
 use antigen_core::{
-    Changed, ChangedTrait, GetIndirect, IndirectComponent, LazyComponent,
-    ReadWriteLock, RwLock,
+    ChangeTracker, Changed, ChangedTrait, GetIndirect, ImmutableResources, IndirectComponent,
+    LazyReadWriteLock, ReadWriteLock, RwLock,
 };
 
 use antigen_wgpu::{BindGroupComponent, CommandBuffersComponent, RenderAttachmentTextureView, SamplerComponent, ShaderModuleComponent, SurfaceConfigurationComponent, wgpu::{BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType, BufferAddress, BufferBindingType, Color, CommandEncoderDescriptor, CompareFunction, DepthBiasState, DepthStencilState, Device, Extent3d, Features, FragmentState, FrontFace, LoadOp, MultisampleState, Operations, PipelineLayoutDescriptor, PrimitiveState, Queue, RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor, RenderPipelineDescriptor, SamplerBindingType, ShaderStages, StencilState, SurfaceConfiguration, TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension, VertexBufferLayout, VertexState, VertexStepMode, util::DeviceExt, vertex_attr_array}};
 
-use antigen_winit::{winit::event::WindowEvent, WindowComponent, WindowEventComponent};
-use legion::{world::SubWorld, IntoQuery};
+use antigen_winit::{winit::event::WindowEvent, WindowComponent, WindowEventReader, WindowEvents};
+use legion::{world::SubWorld, Entity, IntoQuery};
 
 fn create_depth_texture(config: &SurfaceConfiguration, device: &Device) -> TextureView {
     let depth_texture = device.create_texture(&TextureDescriptor {
@@ -35,10 +37,9 @@ fn create_depth_texture(config: &SurfaceConfiguration, device: &Device) -> Textu
 
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
-#[read_component(Queue)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn skybox_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Skybox,
     shader_module: &ShaderModuleComponent,
@@ -52,9 +53,17 @@ pub fn skybox_prepare(
     texture_view_component: &SkyboxTextureViewComponent,
     surface_component: &IndirectComponent<SurfaceConfigurationComponent>,
 ) {
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
-    let queue = <&Queue>::query().iter(world).next().unwrap();
+    let queue = if let Some(queue) = resources.get_or_warn::<Queue>() {
+        queue
+    } else {
+        return;
+    };
 
     let surface_component = world.get_indirect(surface_component).unwrap();
     let config = surface_component.read();
@@ -64,7 +73,7 @@ pub fn skybox_prepare(
     }
 
     if depth_texture_view_component.read().is_pending() {
-        let depth_texture_view = create_depth_texture(&config, device);
+        let depth_texture_view = create_depth_texture(&config, &device);
         depth_texture_view_component
             .write()
             .set_ready(depth_texture_view);
@@ -74,22 +83,19 @@ pub fn skybox_prepare(
         return;
     }
 
-    let shader_module = shader_module.read();
-    let shader_module = if let LazyComponent::Ready(shader_module) = &*shader_module {
+    let shader_module = if let Some(shader_module) = shader_module.read_ready() {
         shader_module
     } else {
         return;
     };
 
-    let sampler = sampler.read();
-    let sampler = if let LazyComponent::Ready(sampler) = &*sampler {
+    let sampler = if let Some(sampler) = sampler.read_ready() {
         sampler
     } else {
         return;
     };
 
-    let uniform_buffer = uniform_buffer.read();
-    let uniform_buffer = if let LazyComponent::Ready(uniform_buffer) = &*uniform_buffer {
+    let uniform_buffer = if let Some(uniform_buffer) = uniform_buffer.read_ready() {
         uniform_buffer
     } else {
         return;
@@ -199,16 +205,16 @@ pub fn skybox_prepare(
     let device_features = device.features();
 
     let skybox_format = if device_features.contains(Features::TEXTURE_COMPRESSION_ASTC_LDR) {
-        println!("Using ASTC_LDR");
+        tracing::info!("Using ASTC_LDR");
         TextureFormat::Astc4x4RgbaUnormSrgb
     } else if device_features.contains(Features::TEXTURE_COMPRESSION_ETC2) {
-        println!("Using ETC2");
+        tracing::info!("Using ETC2");
         TextureFormat::Etc2Rgb8UnormSrgb
     } else if device_features.contains(Features::TEXTURE_COMPRESSION_BC) {
-        println!("Using BC");
+        tracing::info!("Using BC");
         TextureFormat::Bc1RgbaUnormSrgb
     } else {
-        println!("Using plain");
+        tracing::info!("Using plain");
         TextureFormat::Bgra8UnormSrgb
     };
 
@@ -224,9 +230,12 @@ pub fn skybox_prepare(
     };
     let max_mips = layer_size.max_mips();
 
-    println!(
+    tracing::debug!(
         "Copying {:?} skybox images of size {}, {}, 6 with {} mips to gpu",
-        skybox_format, IMAGE_SIZE, IMAGE_SIZE, max_mips,
+        skybox_format,
+        IMAGE_SIZE,
+        IMAGE_SIZE,
+        max_mips,
     );
 
     let bytes = match skybox_format {
@@ -240,7 +249,7 @@ pub fn skybox_prepare(
     let image = ddsfile::Dds::read(&mut std::io::Cursor::new(&bytes)).unwrap();
 
     let texture = device.create_texture_with_data(
-        queue,
+        &queue,
         &TextureDescriptor {
             size,
             mip_level_count: max_mips as u32,
@@ -284,10 +293,12 @@ pub fn skybox_prepare(
 }
 
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn skybox_resize(
+    #[state] resources: &ImmutableResources,
+    #[state] tracker: &ChangeTracker,
     world: &SubWorld,
+    entity: &Entity,
     _: &Skybox,
     surface_config: &IndirectComponent<SurfaceConfigurationComponent>,
     camera_data: &Changed<RwLock<[f32; 52]>>,
@@ -295,16 +306,20 @@ pub fn skybox_resize(
 ) {
     let surface_config = world.get_indirect(surface_config).unwrap();
 
-    if surface_config.get_changed() {
+    if tracker.observe(*entity, surface_config) {
         let surface_config = surface_config.read();
 
-        let device = <&Device>::query().iter(world).next().unwrap();
+        let device = if let Some(device) = resources.get_or_warn::<Device>() {
+            device
+        } else {
+            return;
+        };
 
         if surface_config.width == 0 || surface_config.height == 0 {
             return;
         }
 
-        let depth_texture_view = create_depth_texture(&surface_config, device);
+        let depth_texture_view = create_depth_texture(&surface_config, &device);
 
         depth_texture_view_component
             .write()
@@ -323,11 +338,11 @@ pub fn skybox_resize(
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 #[read_component(VertexBufferComponent)]
 #[read_component(VertexCountComponent)]
 pub fn skybox_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &Skybox,
     entity_pipeline: &EntityPipelineComponent,
@@ -337,44 +352,39 @@ pub fn skybox_render(
     command_buffers: &CommandBuffersComponent,
     render_attachment_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let entity_pipeline = entity_pipeline.read();
-    let entity_pipeline = if let LazyComponent::Ready(entity_pipeline) = &*entity_pipeline {
+    let entity_pipeline = if let Some(entity_pipeline) = entity_pipeline.read_ready() {
         entity_pipeline
     } else {
         return;
     };
 
-    let sky_pipeline = sky_pipeline.read();
-    let sky_pipeline = if let LazyComponent::Ready(sky_pipeline) = &*sky_pipeline {
+    let sky_pipeline = if let Some(sky_pipeline) = sky_pipeline.read_ready() {
         sky_pipeline
     } else {
         return;
     };
 
-    let bind_group = bind_group.read();
-    let bind_group = if let LazyComponent::Ready(bind_group) = &*bind_group {
+    let bind_group = if let Some(bind_group) = bind_group.read_ready() {
         bind_group
     } else {
         return;
     };
 
-    let depth_view = depth_view.read();
-    let depth_view = if let LazyComponent::Ready(depth_view) = &*depth_view {
+    let depth_view = if let Some(depth_view) = depth_view.read_ready() {
         depth_view
     } else {
         return;
     };
 
     let render_attachment_view = world.get_indirect(render_attachment_view).unwrap();
-    let render_attachment_view = render_attachment_view.read();
     let render_attachment_view =
-        if let LazyComponent::Ready(render_attachment_view) = &*render_attachment_view {
+        if let Some(render_attachment_view) = render_attachment_view.read_ready() {
             render_attachment_view
         } else {
             return;
@@ -382,12 +392,7 @@ pub fn skybox_render(
 
     let vertex_buffers = <(&VertexBufferComponent, &VertexCountComponent)>::query()
         .iter(world)
-        .map(|(vertex_buffer, vertex_count)| (vertex_buffer.read(), **vertex_count))
-        .collect::<Vec<_>>();
-
-    let vertex_buffers = vertex_buffers
-        .iter()
-        .map(|(vertex_buffer, vertex_count)| (&*vertex_buffer, *vertex_count))
+        .map(|(vertex_buffer, vertex_count)| (vertex_buffer.read_ready(), **vertex_count))
         .collect::<Vec<_>>();
 
     let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
@@ -420,15 +425,15 @@ pub fn skybox_render(
     rpass.set_bind_group(0, &bind_group, &[]);
     rpass.set_pipeline(&entity_pipeline);
 
-    for (vertex_buffer, vertex_count) in vertex_buffers {
-        let vertex_buffer = if let LazyComponent::Ready(vertex_buffer) = &**vertex_buffer {
+    for (vertex_buffer, vertex_count) in &vertex_buffers {
+        let vertex_buffer = if let Some(vertex_buffer) = vertex_buffer {
             vertex_buffer
         } else {
             return;
         };
 
         rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
-        rpass.draw(0..vertex_count as u32, 0..1);
+        rpass.draw(0..*vertex_count as u32, 0..1);
     }
 
     rpass.set_pipeline(&sky_pipeline);
@@ -438,63 +443,75 @@ pub fn skybox_render(
     command_buffers.write().push(encoder.finish());
 }
 
-#[legion::system(par_for_each)]
+#[legion::system]
+#[read_component(Skybox)]
+#[read_component(Changed<RwLock<[f32; 52]>>)]
+#[read_component(IndirectComponent<WindowComponent>)]
+#[read_component(IndirectComponent<SurfaceConfigurationComponent>)]
 #[read_component(WindowComponent)]
 #[read_component(SurfaceConfigurationComponent)]
-#[read_component(WindowEventComponent)]
 pub fn skybox_cursor_moved(
+    #[state] resources: &ImmutableResources,
+    #[state] reader: &WindowEventReader,
     world: &SubWorld,
-    _: &Skybox,
-    camera_data: &Changed<RwLock<[f32; 52]>>,
-    window: &IndirectComponent<WindowComponent>,
-    surface_component: &IndirectComponent<SurfaceConfigurationComponent>,
 ) {
-    let window = world
-        .get_indirect(window)
-        .expect("No indirect WindowComponent");
-    let window = window.read();
-    let window = if let LazyComponent::Ready(window) = &*window {
-        window
+    let window_events = if let Some(window_events) = resources.get_or_warn::<WindowEvents>() {
+        window_events
     } else {
         return;
     };
 
-    let surface_component = world
-        .get_indirect(surface_component)
-        .expect("No indirect SurfaceConfigurationComponent");
-    let config = surface_component.read();
-
-    let window_event = <&WindowEventComponent>::query()
-        .iter(world)
-        .next()
-        .expect("No WindowEventComponent");
-
-    let window_event = window_event.read();
-    let (window_id, position) = if let (
-        Some(window_id),
-        Some(WindowEvent::CursorMoved { position, .. }),
-    ) = &*window_event
-    {
-        (window_id, position)
-    } else {
-        return;
-    };
+    // Only the latest position in each window is of interest
+    let mut positions = BTreeMap::new();
+    reader.read(&window_events, |(window_id, event)| {
+        if let WindowEvent::CursorMoved { position, .. } = event {
+            positions.insert(*window_id, *position);
+        }
+    });
 
-    if window.id() != *window_id {
+    if positions.is_empty() {
         return;
     }
 
-    let norm_x = position.x as f32 / config.width as f32;
-    let norm_y = position.y as f32 / config.height as f32;
+    <(
+        &Skybox,
+        &Changed<RwLock<[f32; 52]>>,
+        &IndirectComponent<WindowComponent>,
+        &IndirectComponent<SurfaceConfigurationComponent>,
+    )>::query()
+    .for_each(world, |(_, camera_data, window, surface_component)| {
+        let window = world
+            .get_indirect(window)
+            .expect("No indirect WindowComponent");
+        let window = if let Some(window) = window.read_ready() {
+            window
+        } else {
+            return;
+        };
 
-    let camera = Camera {
-        angle_xz: norm_x * 5.0,
-        angle_y: norm_y,
-        dist: 30.0,
-    };
+        let position = if let Some(position) = positions.get(&window.id()) {
+            position
+        } else {
+            return;
+        };
+
+        let surface_component = world
+            .get_indirect(surface_component)
+            .expect("No indirect SurfaceConfigurationComponent");
+        let config = surface_component.read();
 
-    *camera_data.write() = camera.to_uniform_data(config.width as f32 / config.height as f32);
-    camera_data.set_changed(true);
+        let norm_x = position.x as f32 / config.width as f32;
+        let norm_y = position.y as f32 / config.height as f32;
 
-    window.request_redraw();
+        let camera = Camera {
+            angle_xz: norm_x * 5.0,
+            angle_y: norm_y,
+            dist: 30.0,
+        };
+
+        *camera_data.write() = camera.to_uniform_data(config.width as f32 / config.height as f32);
+        camera_data.set_changed(true);
+
+        window.request_redraw();
+    });
 }
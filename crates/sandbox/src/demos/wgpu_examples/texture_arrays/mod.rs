@@ -8,13 +8,13 @@ pub use components::*;
 pub use systems::*;
 
 use antigen_core::{
-    parallel, serial, single, AddIndirectComponent, Construct, ImmutableSchedule, LazyComponent,
-    Serial, Single,
+    parallel, serial, single, AddIndirectComponent, ChangeTracker, Construct, ImmutableResources,
+    ImmutableSchedule, LazyComponent, Serial, Single,
 };
 
 use antigen_wgpu::{
     wgpu::{
-        include_spirv_raw, BufferAddress, BufferDescriptor, BufferUsages, Device, Extent3d,
+        include_spirv_raw, BufferAddress, BufferDescriptor, BufferUsages, Extent3d,
         ImageCopyTextureBase, ImageDataLayout, IndexFormat, TextureAspect, TextureDescriptor,
         TextureDimension, TextureFormat, TextureUsages,
     },
@@ -81,7 +81,6 @@ fn create_texture_data(color: Color) -> [u8; 4] {
 }
 
 #[legion::system]
-#[read_component(Device)]
 pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     let window_entity = cmd.push(());
     let renderer_entity = cmd.push(());
@@ -224,28 +223,51 @@ pub fn assemble(cmd: &mut legion::systems::CommandBuffer) {
     cmd.assemble_wgpu_sampler(renderer_entity, Default::default());
 }
 
-pub fn prepare_schedule() -> ImmutableSchedule<Serial> {
+pub fn prepare_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Serial> {
     serial![
         parallel![
-            antigen_wgpu::create_shader_modules_usage_spirv_system::<Vertex>(),
-            antigen_wgpu::create_buffers_system::<Vertex>(),
-            antigen_wgpu::create_buffers_system::<Index>(),
-            antigen_wgpu::create_textures_system::<Red>(),
-            antigen_wgpu::create_textures_system::<Green>(),
-            antigen_wgpu::create_texture_views_system::<Red>(),
-            antigen_wgpu::create_texture_views_system::<Green>(),
-            antigen_wgpu::create_samplers_system(),
+            antigen_wgpu::create_shader_modules_usage_spirv_system::<Vertex>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<Vertex>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_buffers_system::<Index>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_textures_system::<Red>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_textures_system::<Green>(
+                resources.clone(),
+                ChangeTracker::default()
+            ),
+            antigen_wgpu::create_texture_views_system::<Red>(ChangeTracker::default()),
+            antigen_wgpu::create_texture_views_system::<Green>(ChangeTracker::default()),
+            antigen_wgpu::create_samplers_system(resources.clone(), ChangeTracker::default()),
         ],
         parallel![
-            antigen_wgpu::buffer_write_system::<Vertex, VertexDataComponent, Vec<Vertex>>(),
-            antigen_wgpu::buffer_write_system::<Index, IndexDataComponent, Vec<Index>>(),
-            antigen_wgpu::texture_write_system::<Red, RedTexelComponent, [u8; 4]>(),
-            antigen_wgpu::texture_write_system::<Green, GreenTexelComponent, [u8; 4]>(),
+            antigen_wgpu::buffer_write_system::<Vertex, VertexDataComponent, Vec<Vertex>>(
+                resources.clone()
+            ),
+            antigen_wgpu::buffer_write_system::<Index, IndexDataComponent, Vec<Index>>(
+                resources.clone()
+            ),
+            antigen_wgpu::texture_write_system::<Red, RedTexelComponent, [u8; 4]>(
+                resources.clone()
+            ),
+            antigen_wgpu::texture_write_system::<Green, GreenTexelComponent, [u8; 4]>(
+                resources.clone()
+            ),
         ],
-        texture_arrays_prepare_system()
+        texture_arrays_prepare_system(resources.clone())
     ]
 }
 
-pub fn render_schedule() -> ImmutableSchedule<Single> {
-    single![texture_arrays_render_system()]
+pub fn render_schedule(resources: &ImmutableResources) -> ImmutableSchedule<Single> {
+    single![texture_arrays_render_system(resources.clone())]
 }
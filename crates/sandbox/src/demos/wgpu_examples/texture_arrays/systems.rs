@@ -5,17 +5,17 @@ use super::{
     RedTextureViewComponent, TextureArrays, UniformWorkaroundComponent, Vertex,
     VertexBufferComponent, VertexShaderComponent, INDEX_FORMAT,
 };
-use antigen_core::{GetIndirect, IndirectComponent, LazyComponent, ReadWriteLock};
+use antigen_core::{
+    GetIndirect, ImmutableResources, IndirectComponent, LazyReadWriteLock, ReadWriteLock,
+};
 
 use antigen_wgpu::{BindGroupComponent, CommandBuffersComponent, RenderAttachmentTextureView, RenderPipelineComponent, SamplerComponent, SurfaceConfigurationComponent, wgpu::{BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType, BufferAddress, Color, CommandEncoderDescriptor, Device, Features, FragmentState, FrontFace, LoadOp, MultisampleState, Operations, PipelineLayoutDescriptor, PrimitiveState, PushConstantRange, RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, SamplerBindingType, ShaderStages, TextureSampleType, TextureViewDimension, VertexBufferLayout, VertexState, VertexStepMode, include_spirv_raw, vertex_attr_array}};
 
-use legion::IntoQuery;
-
 // Initialize the hello triangle render pipeline
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(SurfaceConfigurationComponent)]
 pub fn texture_arrays_prepare(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &TextureArrays,
     render_pipeline_component: &RenderPipelineComponent,
@@ -32,32 +32,31 @@ pub fn texture_arrays_prepare(
         return;
     }
 
-    let device = <&Device>::query().iter(world).next().unwrap();
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
+    } else {
+        return;
+    };
 
-    let red_texture_view = red_texture_view.read();
-    let red_texture_view = if let LazyComponent::Ready(red_texture_view) = &*red_texture_view {
+    let red_texture_view = if let Some(red_texture_view) = red_texture_view.read_ready() {
         red_texture_view
     } else {
         return;
     };
 
-    let green_texture_view = green_texture_view.read();
-    let green_texture_view = if let LazyComponent::Ready(green_texture_view) = &*green_texture_view
-    {
+    let green_texture_view = if let Some(green_texture_view) = green_texture_view.read_ready() {
         green_texture_view
     } else {
         return;
     };
 
-    let sampler = sampler.read();
-    let sampler = if let LazyComponent::Ready(sampler) = &*sampler {
+    let sampler = if let Some(sampler) = sampler.read_ready() {
         sampler
     } else {
         return;
     };
 
-    let vertex_shader = vertex_shader.read();
-    let vertex_shader = if let LazyComponent::Ready(vertex_shader) = &*vertex_shader {
+    let vertex_shader = if let Some(vertex_shader) = vertex_shader.read_ready() {
         vertex_shader
     } else {
         return;
@@ -84,8 +83,7 @@ pub fn texture_arrays_prepare(
         .write()
         .set_ready(unsafe { device.create_shader_module_spirv(&fs_source) });
 
-    let fragment_shader = fragment_shader.read();
-    let fragment_shader = if let LazyComponent::Ready(fragment_shader) = &*fragment_shader {
+    let fragment_shader = if let Some(fragment_shader) = fragment_shader.read_ready() {
         fragment_shader
     } else {
         unreachable!()
@@ -179,9 +177,9 @@ pub fn texture_arrays_prepare(
 
 // Render the hello triangle pipeline to the specified entity's surface
 #[legion::system(par_for_each)]
-#[read_component(Device)]
 #[read_component(RenderAttachmentTextureView)]
 pub fn texture_arrays_render(
+    #[state] resources: &ImmutableResources,
     world: &legion::world::SubWorld,
     _: &TextureArrays,
     render_pipeline: &RenderPipelineComponent,
@@ -192,43 +190,38 @@ pub fn texture_arrays_render(
     command_buffers: &CommandBuffersComponent,
     texture_view: &IndirectComponent<RenderAttachmentTextureView>,
 ) {
-    let device = if let Some(components) = <&Device>::query().iter(world).next() {
-        components
+    let device = if let Some(device) = resources.get_or_warn::<Device>() {
+        device
     } else {
         return;
     };
 
-    let render_pipeline = render_pipeline.read();
-    let render_pipeline = if let LazyComponent::Ready(render_pipeline) = &*render_pipeline {
+    let render_pipeline = if let Some(render_pipeline) = render_pipeline.read_ready() {
         render_pipeline
     } else {
         return;
     };
 
-    let bind_group = bind_group.read();
-    let bind_group = if let LazyComponent::Ready(bind_group) = &*bind_group {
+    let bind_group = if let Some(bind_group) = bind_group.read_ready() {
         bind_group
     } else {
         return;
     };
 
-    let vertex_buffer = vertex_buffer.read();
-    let vertex_buffer = if let LazyComponent::Ready(vertex_buffer) = &*vertex_buffer {
+    let vertex_buffer = if let Some(vertex_buffer) = vertex_buffer.read_ready() {
         vertex_buffer
     } else {
         return;
     };
 
-    let index_buffer = index_buffer.read();
-    let index_buffer = if let LazyComponent::Ready(index_buffer) = &*index_buffer {
+    let index_buffer = if let Some(index_buffer) = index_buffer.read_ready() {
         index_buffer
     } else {
         return;
     };
 
     let texture_view = world.get_indirect(texture_view).unwrap();
-    let texture_view = texture_view.read();
-    let texture_view = if let LazyComponent::Ready(texture_view) = &*texture_view {
+    let texture_view = if let Some(texture_view) = texture_view.read_ready() {
         texture_view
     } else {
         return;
@@ -46,7 +46,9 @@
 //       Should be able to give TextureComponent a sibling GenerateMipmaps component,
 //       have everything be automatic from there
 //
-// TODO: [>] Boilerplate reduction for reading and unwrapping RwLock<LazyComponent::Ready>
+// TODO: [✓] Boilerplate reduction for reading and unwrapping RwLock<LazyComponent::Ready>
+//           LazyReadWriteLock::read_ready / write_ready return mapped guards
+//           [✓] Migrate remaining hand-written LazyComponent::Ready matches in demos
 //
 // TODO: [✓] Refactor ChangedFlag as a wrapper instead of a separate component
 //       Can implement its methods in a trait and use Deref to tag them onto an existing type,
@@ -61,6 +63,7 @@
 //
 // TODO: Improve WindowEventComponent
 //       Split into discrete components?
+//       [✓] Migrate demo input systems to WindowEvents readers
 //
 // TODO: Investigate Encoder::copy_buffer_to_texture
 //       What are its characteristics versus Queue::write_texture?
@@ -99,12 +102,19 @@ mod demos;
 pub use demos::*;
 
 use antigen_core::*;
+use antigen_util::{GameLoop, GameLoopStatistics, ShutdownToken};
 use antigen_wgpu::wgpu::{DeviceDescriptor, Features, Limits};
+use antigen_winit::winit::{
+    event::Event,
+    event_loop::{ControlFlow, EventLoopWindowTarget},
+};
+use std::thread::JoinHandle;
 
 const GAME_TICK_DURATION: std::time::Duration = std::time::Duration::from_nanos(16670000);
+const PROFILE_DUMP_TICKS: u64 = 600;
 
 fn main() -> ! {
-    //tracing_subscriber::fmt::fmt().pretty().init();
+    tracing_subscriber::fmt::fmt().pretty().init();
 
     // Create world
     let world = ImmutableWorld::default();
@@ -112,12 +122,18 @@ fn main() -> ! {
     // Assemble args
     antigen_core::assemble_args(&mut world.write());
 
+    // Assemble world command queue
+    antigen_core::assemble_world_command_queue(&mut world.write());
+
+    // Assemble name index
+    antigen_core::assemble_names(&world);
+
     // Assemble winit backend
-    antigen_winit::assemble_winit_backend(&mut world.write());
+    antigen_winit::assemble_winit_backend(&world);
 
     // Assemble WGPU backend
-    antigen_wgpu::assemble_wgpu_entity_from_env(
-        &mut world.write(),
+    antigen_wgpu::assemble_wgpu_resources_from_env(
+        &world,
         &DeviceDescriptor {
             label: None,
             features: Features::default()
@@ -149,46 +165,119 @@ fn main() -> ! {
         None,
     );
 
-    // Assemble modules
-    single![demos::transform_integration::assemble_system()].execute_and_flush(&world);
-    //demos::wgpu_examples::assemble_schedule().execute_and_flush(&world);
-    single![demos::phosphor::assemble_system()].execute_and_flush(&world);
-    serial![
-        antigen_fs::load_files_system::<crate::demos::phosphor::MapFile>(),
-        antigen_fs::read_file_string_system::<crate::demos::phosphor::MapFile>(),
-        antigen_shambler::parse_map_file_system::<crate::demos::phosphor::MapFile>(),
-        crate::demos::phosphor::build_map_system(false),
-    ]
-    .execute_and_flush(&world);
+    // Assemble modules, selecting a demo via --demo <name>
+    let app = App::new(&world)
+        .add_plugin(TimePlugin {
+            timestep: GAME_TICK_DURATION,
+        })
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(demos::transform_integration::TransformIntegrationPlugin)
+        .add_plugin(antigen_wgpu::WgpuIndirectPlugin)
+        .add_indirect_component::<antigen_winit::WindowComponent>(CascadePolicy::Remove);
+
+    let app = match demo_arg().as_deref() {
+        Some("wgpu_examples") => app.add_plugin(demos::wgpu_examples::WgpuExamplesPlugin),
+        Some("phosphor") | None => app.add_plugin(demos::phosphor::PhosphorPlugin),
+        Some(demo) => panic!("Unknown demo {}", demo),
+    };
+
+    let AppSchedules {
+        tick_schedule,
+        event_loop_schedules,
+    } = app
+        .build(&world)
+        .unwrap_or_else(|e| panic!("Failed to build app: {}", e));
 
     // Spawn threads
-    std::thread::spawn(game_thread(world.clone()));
-    winit_thread(world);
+    let shutdown = ShutdownToken::default();
+    let game_thread =
+        std::thread::spawn(game_thread(world.clone(), tick_schedule, shutdown.clone()));
+    winit_thread(world, event_loop_schedules, shutdown, game_thread);
+}
+
+fn demo_arg() -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--demo");
+    args.next()?;
+    args.next()
 }
 
-pub fn game_thread(world: ImmutableWorld) -> impl Fn() {
+pub fn game_thread(
+    world: ImmutableWorld,
+    mut tick_schedule: ImmutableSchedule<Serial>,
+    shutdown: ShutdownToken,
+) -> impl FnOnce() -> GameLoopStatistics {
     move || {
-        // Crate schedule
-        let mut tick_schedule = serial![
-            crate::demos::transform_integration::integrate_schedule(),
-            crate::demos::transform_integration::print_schedule(),
-        ];
-
-        // Run schedule in loop
-        antigen_util::spin_loop(GAME_TICK_DURATION, || {
-            tick_schedule.execute(&world);
+        if std::env::args().any(|arg| arg == "--print-schedules") {
+            tracing::info!("{}", tick_schedule.describe());
+        }
+
+        // Optionally profile, dumping statistics and a Chrome trace periodically
+        let profiler = if std::env::args().any(|arg| arg == "--profile") {
+            let profiler = std::sync::Arc::new(Profiler::default());
+            tick_schedule = tick_schedule.with_profiler(profiler.clone());
+            Some(profiler)
+        } else {
+            None
+        };
+
+        // Run schedule at a fixed rate until shutdown
+        let mut game_loop = GameLoop::new(GAME_TICK_DURATION).with_shutdown_token(shutdown);
+        game_loop.run(|tick| {
+            // Lifecycle hooks are left to the event loop thread, which owns thread-local resources
+            tick_schedule.execute_and_flush(&world);
             //io_schedule.execute_and_flush(&world);
+
+            if let Some(profiler) = &profiler {
+                if (tick.index + 1) % PROFILE_DUMP_TICKS == 0 {
+                    for statistics in profiler.statistics() {
+                        tracing::info!("{}", statistics);
+                    }
+
+                    let trace = profiler.to_chrome_trace();
+                    if let Err(e) = std::fs::write("game_thread_trace.json", trace) {
+                        tracing::error!("Failed to write trace: {}", e);
+                    }
+
+                    profiler.clear();
+                }
+            }
         })
     }
 }
 
-pub fn winit_thread(world: ImmutableWorld) -> ! {
+pub fn winit_thread(
+    world: ImmutableWorld,
+    event_loop_schedules: EventLoopSchedules<Event<'static, ()>>,
+    shutdown: ShutdownToken,
+    game_thread: JoinHandle<GameLoopStatistics>,
+) -> ! {
+    // Stop the game thread once the event loop exits
+    let mut game_thread = Some(game_thread);
+    let stop_game_thread = move |_: &ImmutableWorld,
+                                 event: Event<'static, ()>,
+                                 _: &EventLoopWindowTarget<()>,
+                                 _: &mut ControlFlow| {
+        if let Event::LoopDestroyed = event {
+            shutdown.shutdown();
+            if let Some(game_thread) = game_thread.take() {
+                match game_thread.join() {
+                    Ok(statistics) => tracing::info!("Game thread stopped: {:?}", statistics),
+                    Err(_) => tracing::error!("Game thread panicked"),
+                }
+            }
+        }
+    };
+
     // Enter winit event loop
+    let resources = world.resources().clone();
     antigen_winit::winit::event_loop::EventLoop::new().run(antigen_winit::wrap_event_loop(
         world,
-        antigen_winit::winit_event_handler(antigen_wgpu::winit_event_handler(
-            //demos::wgpu_examples::winit_event_handler(antigen_winit::winit_event_terminator()),
-            demos::phosphor::winit_event_handler(antigen_winit::winit_event_terminator()),
-        )),
+        antigen_winit::winit_event_handler(
+            &resources,
+            antigen_wgpu::winit_event_handler(
+                &resources,
+                antigen_winit::app_event_handler(event_loop_schedules, stop_game_thread),
+            ),
+        ),
     ))
 }